@@ -9,19 +9,62 @@ pub const OPERATOR_TOKEN_ACCOUNT: Pubkey = Pubkey::new_from_array([
     0x0f, 0x05, 0xb2, 0x61, 0xfc, 0xb6, 0x32, 0xb9, 0x55, 0x77, 0x97, 0xfa, 0xeb, 0xb8, 0xa5, 0xbc, 0x10, 0x0b, 0x8f,
     0xc5, 0x40, 0xf0, 0x21, 0x7c, 0x74, 0x70, 0xd8, 0x6e, 0xe6, 0xc5, 0xdc, 0x3e,
 ]);
+// the operator only initializes the council, the council members resolve escalated disputes and manage the games
+pub const MAX_COUNCIL_MEMBERS: usize = 10;
 
-pub const RENT_PER_POSITION: u64 = 1447680;
-pub const RENT_PER_BET: u64 = 1224960;
+pub const RENT_PER_BET: u64 = 1336320;
 pub const RENT_PER_ORACLE: u64 = 1183200;
 
+// the defaults of the config together with the windows, the payout rate and the reward shares below, see Config
 pub const MIN_BET_AMOUNT: u64 = 1000000 / 100;
 pub const MIN_ORACLE_STAKE: u64 = 1000000;
 pub const MIN_BETTOR_DISPUTE_STAKE: u64 = 1000000 * 10;
 
+pub const MAX_PARLAY_LEGS: usize = 10;
+
 pub const ORACLE_UPDATE_WINDOW: i64 = 60 * 10;
+// the lowest quorum a book can be initialized with, see Quorum
+pub const MIN_QUORUM_ORACLES: u32 = 3;
+pub const MIN_QUORUM_STAKE_RATIO: u64 = 1000;
+pub const MIN_QUORUM_SUPERMAJORITY: u64 = 6000;
+// the highest quorum, so a book can still reach it
+pub const MAX_QUORUM_ORACLES: u32 = 100;
+pub const MAX_QUORUM_STAKE_RATIO: u64 = 10000;
+// the limits of the parameters an initiator can choose for a book, see BookParams
+pub const MIN_BOOK_BET_AMOUNT: u64 = 1000000 / 1000;
+pub const MIN_BOOK_ORACLE_STAKE: u64 = 1000000 / 10;
+pub const MIN_BOOK_BETTOR_DISPUTE_STAKE: u64 = 1000000;
+pub const MIN_BOOK_ORACLE_UPDATE_WINDOW: i64 = 60 * 5;
+pub const MAX_BOOK_BETTOR_DISPUTE_WINDOW: i64 = 60 * 60 * 24 * 7;
+pub const MAX_BOOK_INITIATOR_REWARD_SHARE: u64 = 4000;
+// oracles commit to a hashed outcome for this long after the first commit, then reveal it
+pub const ORACLE_COMMIT_WINDOW: i64 = 60 * 10;
+pub const ORACLE_REVEAL_WINDOW: i64 = 60 * 10;
 pub const BETTOR_DISPUTE_WINDOW: i64 = ORACLE_UPDATE_WINDOW + 60 * 20;
+// every dispute round can be countered for this long with another outcome and at least twice the bond, the dispute
+// falls through to the council after the last round or once a bond reaches the cap
+pub const DISPUTE_ROUND_WINDOW: i64 = 60 * 20;
+pub const MAX_DISPUTE_ROUNDS: u8 = 4;
+pub const DISPUTE_BOND_CAP: u64 = 1000000 * 1000;
+// the council must resolve an escalated dispute this long after its latest round, after that anyone can cancel the
+// book and every stake and bond is returned
+pub const COUNCIL_RESOLUTION_WINDOW: i64 = 60 * 60 * 24 * 3;
+// the oracles must reach the quorum of a book this long after kickoff, after that anyone can cancel the book and every
+// stake is returned
+pub const QUORUM_DEADLINE: i64 = 60 * 60 * 24 * 7;
+// the longest bet delay an in-play book can be initialized with
+pub const MAX_IN_PLAY_BET_DELAY: i64 = 60;
+// how long an oracle reported event suspends an in-play market
+pub const IN_PLAY_SUSPENSION: i64 = 60 * 2;
+// after a suspension the market stays open at least this long before it can be suspended again
+pub const IN_PLAY_SUSPENSION_COOLDOWN: i64 = 60 * 5;
+// the share of the oracle stake of the book an oracle must hold to suspend it
+pub const MIN_SUSPENSION_STAKE_SHARE: u64 = 2000;
 
 pub const BETTOR_PAYOUT_RATE: u64 = 9900;
 
 pub const ORALCES_REWARD_SHARE: u64 = 6000;
 pub const INITIATOR_REWARD_SHARE: u64 = 2000;
+// the share of the stake slashed from the wrong oracles which goes to the bettors who disputed successfully, the rest
+// goes to the correct oracles
+pub const SLASHED_STAKE_DISPUTERS_SHARE: u64 = 5000;
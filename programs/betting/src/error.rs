@@ -20,4 +20,56 @@ pub enum BettingError {
     NotFound = 7,
     #[msg("NoResultYet")]
     NoResultYet = 8,
+    #[msg("InvalidOdds")]
+    InvalidOdds = 9,
+    #[msg("InvalidOutcome")]
+    InvalidOutcome = 10,
+    #[msg("InvalidLegs")]
+    InvalidLegs = 11,
+    #[msg("ParlayAlreadyMatched")]
+    ParlayAlreadyMatched = 12,
+    #[msg("ParlayNotMatched")]
+    ParlayNotMatched = 13,
+    #[msg("MarketSuspended")]
+    MarketSuspended = 14,
+    #[msg("NotInPlay")]
+    NotInPlay = 15,
+    #[msg("InvalidOrderType")]
+    InvalidOrderType = 16,
+    #[msg("OrderNotFilled")]
+    OrderNotFilled = 17,
+    #[msg("OrderWouldCross")]
+    OrderWouldCross = 18,
+    #[msg("InvalidExpiry")]
+    InvalidExpiry = 19,
+    #[msg("InvalidPages")]
+    InvalidPages = 20,
+    #[msg("PageFull")]
+    PageFull = 21,
+    #[msg("InvalidPositions")]
+    InvalidPositions = 22,
+    #[msg("InvalidOracles")]
+    InvalidOracles = 23,
+    #[msg("InvalidCommitment")]
+    InvalidCommitment = 24,
+    #[msg("InvalidQuorum")]
+    InvalidQuorum = 25,
+    #[msg("InvalidDispute")]
+    InvalidDispute = 26,
+    #[msg("InvalidCouncil")]
+    InvalidCouncil = 27,
+    #[msg("InvalidProposal")]
+    InvalidProposal = 28,
+    #[msg("ProposalNotApproved")]
+    ProposalNotApproved = 29,
+    #[msg("InvalidConfig")]
+    InvalidConfig = 30,
+    #[msg("InvalidBookParams")]
+    InvalidBookParams = 31,
+    #[msg("MathOverflow")]
+    MathOverflow = 32,
+    #[msg("DuplicateBet")]
+    DuplicateBet = 33,
+    #[msg("InvalidBetDelay")]
+    InvalidBetDelay = 34,
 }
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{BetDirection, OrderType};
+
+#[event]
+pub struct BetPlaced {
+    pub book: Pubkey,
+    pub bettor: Pubkey,
+    pub bet_direction: BetDirection,
+    pub order_type: OrderType,
+    pub odds: u32,
+    pub wager: u64,
+    // the part of the wager matched right away
+    pub dealt_wager: u64,
+    pub payout: u64,
+    // in thousandths, zero if nothing was matched
+    pub average_odds: u32,
+}
+
+#[event]
+pub struct BetAmended {
+    pub book: Pubkey,
+    pub bettor: Pubkey,
+    pub bet_direction: BetDirection,
+    pub previous_bet_id: u64,
+    // equal to the previous id if the bet kept its place in the queue
+    pub bet_id: u64,
+    pub odds: u32,
+    pub wager: u64,
+}
+
+#[event]
+pub struct BetsMatched {
+    pub book: Pubkey,
+    pub matches_count: u32,
+    // whether the queues still cross, a keeper should crank the book again
+    pub has_more: bool,
+}
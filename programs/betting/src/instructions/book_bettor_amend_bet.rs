@@ -0,0 +1,940 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::BettingError,
+    events::BetAmended,
+    state::{Bet, BetDirection, Book, BookPage, Game, Position},
+};
+
+#[derive(Accounts)]
+pub struct BookBettorAmendBetAccounts<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=bettor)]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Position".as_ref(),book_pda.key().as_ref(),bettor.key().as_ref()],bump)]
+    pub position_pda: AccountLoader<'info, Position>,
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// the pages of the current and the amended price level are passed as remaining accounts
+pub fn book_bettor_amend_bet(
+    ctx: Context<BookBettorAmendBetAccounts>,
+    bet_id: u64,
+    bet_direction: BetDirection,
+    odds: u32,
+    wager: u64,
+) -> Result<()> {
+    // check condition
+    require!(
+        wager >= ctx.accounts.book_pda.params.min_bet_amount,
+        BettingError::MinTokenAmountNotMet
+    );
+    require!(odds as u64 > Bet::ODDS_PRECISION, BettingError::InvalidOdds);
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    if ctx.accounts.book_pda.in_play.is_none() {
+        require!(now < ctx.accounts.game_pda.kickoff, BettingError::NotInWindow);
+    }
+    require!(!ctx.accounts.book_pda.is_suspended(now), BettingError::MarketSuspended);
+    // get the bet
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let mut positions = [ctx.accounts.position_pda.load_mut()?];
+    let previous_wager = if let Some(bet) = Book::find_bet(&pages, bet_id, bet_direction) {
+        // check bettor
+        require_keys_eq!(bet.bettor, ctx.accounts.bettor.key(), BettingError::NoAuthority);
+        bet.wager
+    } else {
+        return err!(BettingError::NotFound);
+    };
+    // update book pda
+    let amended_bet_id =
+        ctx.accounts
+            .book_pda
+            .amend_bet(&mut pages, &mut positions, bet_id, bet_direction, odds, wager, now)?;
+    // realloc, a new price level may be listed
+    let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
+    let book_pda_space = ctx.accounts.book_pda.current_space();
+    book_pda_account_info.realloc(book_pda_space, false)?;
+    // rent
+    let min_rent = Rent::get()?.minimum_balance(book_pda_space);
+    if book_pda_account_info.lamports() < min_rent {
+        let diff = min_rent - book_pda_account_info.lamports();
+        let rent_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bettor.to_account_info(),
+                to: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        system_program::transfer(rent_transfer_cpi_context, diff)?;
+    }
+
+    // transfer the wager delta
+    if wager > previous_wager {
+        let wager_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.bettor_token_account.to_account_info(),
+                to: ctx.accounts.book_ata.to_account_info(),
+                authority: ctx.accounts.bettor.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer(wager_transfer_cpi_context, wager - previous_wager)?;
+    } else if wager < previous_wager {
+        let wager_return_cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.book_ata.to_account_info(),
+                to: ctx.accounts.bettor_token_account.to_account_info(),
+                authority: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
+        let book_pda_signer_seeds = &[
+            b"Book".as_ref(),
+            &ctx.accounts.book_pda.game_id.to_le_bytes(),
+            bet_type_vec.as_slice(),
+            &[*ctx.bumps.get("book_pda").unwrap()],
+        ];
+        anchor_spl::token::transfer(
+            wager_return_cpi_context.with_signer(&[book_pda_signer_seeds]),
+            previous_wager - wager,
+        )?;
+    }
+
+    emit!(BetAmended {
+        book: ctx.accounts.book_pda.key(),
+        bettor: ctx.accounts.bettor.key(),
+        bet_direction,
+        previous_bet_id: bet_id,
+        bet_id: amended_bet_id,
+        odds,
+        wager,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, Game, Position, Quorum};
+
+    #[tokio::test]
+    async fn test_book_bettor_amend_bet_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1500_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 1500;
+        book_page2_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorAmendBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .args(crate::instruction::BookBettorAmendBet {
+                bet_id,
+                bet_direction: BetDirection::For,
+                odds: 1500,
+                wager: 1000000 * 30,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the wager delta should be transferred out from the bettor token account
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 90);
+        // the bet should be requeued with a new id in the page of its new price level
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 2);
+        assert_eq!(book_state.wager_total, 1000000 * 30);
+        assert_eq!(book_state.levels_for, vec![1500]);
+        let book_page1_account = banks_client.get_account(book_page1_pda).await.unwrap().unwrap();
+        let book_page1_state = BookPage::try_deserialize(&mut book_page1_account.data.as_slice()).unwrap();
+        assert!(book_page1_state.bets().is_empty());
+        let book_page2_account = banks_client.get_account(book_page2_pda).await.unwrap().unwrap();
+        let book_page2_state = BookPage::try_deserialize(&mut book_page2_account.data.as_slice()).unwrap();
+        assert_eq!(book_page2_state.bets().len(), 1);
+        assert_ne!(book_page2_state.bets()[0].id, bet_id);
+        assert_eq!(book_page2_state.bets()[0].odds(), 1500);
+        assert_eq!(book_page2_state.bets()[0].wager, 1000000 * 30);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 30);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        // the wager delta should be transferred to the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 30);
+    }
+
+    #[tokio::test]
+    async fn test_book_bettor_amend_bet_success_reduce_wager() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorAmendBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .args(crate::instruction::BookBettorAmendBet {
+                bet_id,
+                bet_direction: BetDirection::For,
+                odds: 1200,
+                wager: 1000000 * 5,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the wager delta should be returned to the bettor token account
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 115);
+        // the bet should keep its id
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 1);
+        assert_eq!(book_state.wager_total, 1000000 * 5);
+        let book_page_account = banks_client.get_account(book_page_pda).await.unwrap().unwrap();
+        let book_page_state = BookPage::try_deserialize(&mut book_page_account.data.as_slice()).unwrap();
+        assert_eq!(book_page_state.bets().len(), 1);
+        assert_eq!(book_page_state.bets()[0].id, bet_id);
+        assert_eq!(book_page_state.bets()[0].wager, 1000000 * 5);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 5);
+        // the wager delta should be transferred from the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 5);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_bettor_amend_bet_err_wrong_bettor() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let wrong_bettor = Pubkey::new_unique();
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (wrong_bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), wrong_bettor.as_ref()],
+            &program_id,
+        );
+        let mut wrong_bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: wrong_bettor,
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut wrong_bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                wrong_bettor,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut wrong_bettor_position_pda_data = Position::discriminator().to_vec();
+        wrong_bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&wrong_bettor_position_pda_state));
+        program_test.add_account(
+            wrong_bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: wrong_bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorAmendBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .args(crate::instruction::BookBettorAmendBet {
+                bet_id,
+                bet_direction: BetDirection::For,
+                odds: 1500,
+                wager: 1000000 * 30,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,600 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::BettingError,
+    state::{BetFilter, Book, BookPage, Position},
+};
+
+#[derive(Accounts)]
+pub struct BookBettorCancelAllBetsAccounts<'info> {
+    pub bettor: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=bettor)]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Position".as_ref(),book_pda.key().as_ref(),bettor.key().as_ref()],bump)]
+    pub position_pda: AccountLoader<'info, Position>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// the pages to cancel from are passed as remaining accounts
+pub fn book_bettor_cancel_all_bets(ctx: Context<BookBettorCancelAllBetsAccounts>, filter: BetFilter) -> Result<()> {
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // update book pda
+    let bettor = ctx.accounts.bettor.key();
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let mut positions = [ctx.accounts.position_pda.load_mut()?];
+    let wager = ctx
+        .accounts
+        .book_pda
+        .cancel_bets(&mut pages, &mut positions, &bettor, &filter)?;
+    require!(wager > 0, BettingError::NotFound);
+
+    // return the wager of all cancelled bets at once
+    let wager_return_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.book_ata.to_account_info(),
+            to: ctx.accounts.bettor_token_account.to_account_info(),
+            authority: ctx.accounts.book_pda.to_account_info(),
+        },
+    );
+    let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
+    let book_pda_signer_seeds = &[
+        b"Book".as_ref(),
+        &ctx.accounts.book_pda.game_id.to_le_bytes(),
+        bet_type_vec.as_slice(),
+        &[*ctx.bumps.get("book_pda").unwrap()],
+    ];
+    anchor_spl::token::transfer(wager_return_cpi_context.with_signer(&[book_pda_signer_seeds]), wager)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{BetDirection, BetFilter, BetType, Book, BookPage, BookParams, Position, Quorum};
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6007)")]
+    async fn test_book_bettor_cancel_all_bets_err_bet_not_found() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorCancelAllBetsAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .args(crate::instruction::BookBettorCancelAllBets {
+                filter: BetFilter {
+                    bet_direction: Some(BetDirection::Against),
+                    min_odds: None,
+                    max_odds: None,
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_book_bettor_cancel_all_bets_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &3000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 3000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        let (book_page3_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &5000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page3_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page3_pda_state.book = book_pda;
+        book_page3_pda_state.payer = Pubkey::new_unique();
+        book_page3_pda_state.odds = 5000;
+        book_page3_pda_state.bet_direction = BetDirection::For as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                3000,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page3_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                5000,
+                1000000 * 10,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page3_pda_data = BookPage::discriminator().to_vec();
+        book_page3_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page3_pda_state));
+        program_test.add_account(
+            book_page3_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page3_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 50,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorCancelAllBetsAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(book_page3_pda, false))
+            .args(crate::instruction::BookBettorCancelAllBets {
+                filter: BetFilter {
+                    bet_direction: None,
+                    min_odds: None,
+                    max_odds: Some(3000),
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the wagers should be returned to the bettor token account at once
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 140);
+        // the book pda should be updated, the bet outside the filter is kept
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.wager_total, 1000000 * 10);
+        assert_eq!(book_state.levels_for, vec![5000]);
+        assert!(book_state.levels_against.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 10);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 3);
+        // the wagers should be transferred from the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 10);
+    }
+}
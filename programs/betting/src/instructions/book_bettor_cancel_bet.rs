@@ -5,9 +5,8 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::ORACLE_UPDATE_WINDOW,
     error::BettingError,
-    state::{BetDirection, Book},
+    state::{BetDirection, Book, BookPage, Position},
 };
 
 #[derive(Accounts)]
@@ -17,11 +16,14 @@ pub struct BookBettorCancelBetAccounts<'info> {
     pub bettor_token_account: Account<'info, TokenAccount>,
     #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
     pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Position".as_ref(),book_pda.key().as_ref(),bettor.key().as_ref()],bump)]
+    pub position_pda: AccountLoader<'info, Position>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
+// the page of the price level of the bet is passed as a remaining account
 pub fn book_bettor_cancel_bet(
     ctx: Context<BookBettorCancelBetAccounts>,
     bet_id: u64,
@@ -31,28 +33,25 @@ pub fn book_bettor_cancel_bet(
     let now = Clock::get()?.unix_timestamp;
     require!(
         ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
         BettingError::NotInWindow
     );
     // get the bet
-    let bets = if let BetDirection::For = bet_direction {
-        &mut ctx.accounts.book_pda.bets_for
-    } else {
-        &mut ctx.accounts.book_pda.bets_against
-    };
-    let bet = if let Ok(index) = bets.binary_search_by_key(&bet_id, |b| b.id) {
-        bets.remove(index).unwrap()
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let mut positions = [ctx.accounts.position_pda.load_mut()?];
+    let bet = if let Some(bet) = Book::find_bet(&pages, bet_id, bet_direction) {
+        bet
     } else {
         return err!(BettingError::NotFound);
     };
     // check bettor
     require_keys_eq!(bet.bettor, ctx.accounts.bettor.key(), BettingError::NoAuthority);
     // update book pda
-    ctx.accounts.book_pda.wager_total -= bet.wager;
-    ctx.accounts.book_pda.positions.entry(bet.bettor).and_modify(|p| {
-        p.active_bets_count -= 1;
-        p.wager -= bet.wager;
-    });
+    ctx.accounts
+        .book_pda
+        .cancel_bet(&mut pages, &mut positions, bet_id, bet_direction)?;
 
     // return the wager
     let wager_return_cpi_context = CpiContext::new(
@@ -80,21 +79,18 @@ pub fn book_bettor_cancel_bet(
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::rc::Rc;
 
     use anchor_client::RequestBuilder;
-    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
     use anchor_spl::mint::USDC;
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
-        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-        signature::Keypair, signer::Signer, transaction::Transaction,
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
     };
 
-    use crate::state::{BetDirection, BetType, Book, Position};
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, Position, Quorum};
 
     #[tokio::test]
     #[should_panic(expected = "Custom(6006)")]
@@ -155,23 +151,87 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
-        book_pda_state.new_bet(1200, 1000000 * 20, bettor.pubkey(), BetDirection::For);
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -219,11 +279,13 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
             .args(crate::instruction::BookBettorCancelBet {
-                bet_id: book_pda_state.bets_for[0].id,
+                bet_id,
                 bet_direction: BetDirection::For,
             })
             .instructions()
@@ -244,10 +306,13 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.wager_total, 0);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
+        assert!(book_state.levels_for.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 0);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
         // the wager should be transferred from the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -312,23 +377,87 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
         };
-        book_pda_state.new_bet(1200, 1000000 * 20, bettor.pubkey(), BetDirection::For);
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -376,11 +505,13 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
             .args(crate::instruction::BookBettorCancelBet {
-                bet_id: book_pda_state.bets_for[0].id,
+                bet_id,
                 bet_direction: BetDirection::For,
             })
             .instructions()
@@ -401,10 +532,13 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.wager_total, 0);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
+        assert!(book_state.levels_for.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 0);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
         // the wager should be transferred from the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -470,23 +604,87 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
-        book_pda_state.new_bet(1200, 1000000 * 20, bettor.pubkey(), BetDirection::For);
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor.pubkey(),
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -534,11 +732,13 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
             .args(crate::instruction::BookBettorCancelBet {
-                bet_id: book_pda_state.bets_for[0].id,
+                bet_id,
                 bet_direction: BetDirection::Against,
             })
             .instructions()
@@ -559,10 +759,13 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.wager_total, 0);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
+        assert!(book_state.levels_for.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 0);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
         // the wager should be transferred from the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -629,23 +832,87 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                wrong_bettor,
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
-        book_pda_state.new_bet(1200, 1000000 * 20, wrong_bettor, BetDirection::For);
+        let (wrong_bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), wrong_bettor.as_ref()],
+            &program_id,
+        );
+        let mut wrong_bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: wrong_bettor,
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let bet_id = book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut wrong_bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                wrong_bettor,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut wrong_bettor_position_pda_data = Position::discriminator().to_vec();
+        wrong_bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&wrong_bettor_position_pda_state));
+        program_test.add_account(
+            wrong_bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: wrong_bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -678,6 +945,11 @@ mod test {
             },
         );
 
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -693,11 +965,13 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
             .args(crate::instruction::BookBettorCancelBet {
-                bet_id: book_pda_state.bets_for[0].id,
+                bet_id,
                 bet_direction: BetDirection::For,
             })
             .instructions()
@@ -718,10 +992,13 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.wager_total, 0);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
+        assert!(book_state.levels_for.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 0);
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
         // the wager should be transferred from the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -5,37 +5,64 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::{BETTOR_DISPUTE_WINDOW, MIN_BETTOR_DISPUTE_STAKE, ORACLE_UPDATE_WINDOW},
     error::BettingError,
-    state::Book,
+    state::{BetOutcome, Book, DisputeRound},
 };
 
 #[derive(Accounts)]
 pub struct BookBettorDisputeAccounts<'info> {
+    #[account(mut)]
     pub bettor: Signer<'info>,
     #[account(mut,token::mint=USDC,token::authority=bettor)]
     pub bettor_token_account: Account<'info, TokenAccount>,
     #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
     pub book_pda: Account<'info, Book>,
+    #[account(init,payer=bettor,space=DisputeRound::INIT_SPACE,seeds=[b"DisputeRound".as_ref(),book_pda.key().as_ref(),&[book_pda.next_dispute_round()]],bump)]
+    pub dispute_round_pda: Account<'info, DisputeRound>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn book_bettor_dispute(ctx: Context<BookBettorDisputeAccounts>, stake: u64) -> Result<()> {
-    // check stake
-    require!(stake >= MIN_BETTOR_DISPUTE_STAKE, BettingError::MinTokenAmountNotMet);
-    // check time window
-    require!(ctx.accounts.book_pda.concluded_at.is_some(), BettingError::NotInWindow);
-    let now = Clock::get()?.unix_timestamp;
-    let concluded_at = ctx.accounts.book_pda.concluded_at.unwrap();
+// opens the next round of the dispute with the outcome the disputer claims and the hash of the evidence for the
+// council, anyone can counter the latest round, only the rounds which claimed the final outcome are paid out
+pub fn book_bettor_dispute(
+    ctx: Context<BookBettorDisputeAccounts>,
+    bet_outcome: BetOutcome,
+    evidence: [u8; 32],
+    bond: u64,
+) -> Result<()> {
+    // check time window, the first round challenges the oracles within the dispute window, a book cancelled on the
+    // quorum deadline has no oracle outcome to challenge
     require!(
-        concluded_at + ORACLE_UPDATE_WINDOW < now && now < concluded_at + BETTOR_DISPUTE_WINDOW,
+        ctx.accounts.book_pda.concluded_at.is_some() && !ctx.accounts.book_pda.quorum_timed_out,
         BettingError::NotInWindow
     );
+    let now = Clock::get()?.unix_timestamp;
+    if ctx.accounts.book_pda.dispute.is_none() {
+        let concluded_at = ctx.accounts.book_pda.concluded_at.unwrap();
+        require!(
+            concluded_at + ctx.accounts.book_pda.params.oracle_update_window < now
+                && now < concluded_at + ctx.accounts.book_pda.params.bettor_dispute_window,
+            BettingError::NotInWindow
+        );
+    }
 
-    // transfer stake
-    let stake_transfer_cpi_context = CpiContext::new(
+    // update book pda
+    let round = ctx.accounts.book_pda.new_dispute_round(bet_outcome, bond, now)?;
+    // init dispute round pda
+    ctx.accounts.dispute_round_pda.set_inner(DisputeRound {
+        book: ctx.accounts.book_pda.key(),
+        disputer: ctx.accounts.bettor.key(),
+        round,
+        outcome: bet_outcome,
+        evidence,
+        bond,
+    });
+
+    // transfer bond
+    let bond_transfer_cpi_context = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         anchor_spl::token::Transfer {
             from: ctx.accounts.bettor_token_account.to_account_info(),
@@ -43,28 +70,14 @@ pub fn book_bettor_dispute(ctx: Context<BookBettorDisputeAccounts>, stake: u64)
             authority: ctx.accounts.bettor.to_account_info(),
         },
     );
-    anchor_spl::token::transfer(stake_transfer_cpi_context, stake)?;
-
-    // update book pda
-    match ctx.accounts.book_pda.positions.get_mut(ctx.accounts.bettor.key) {
-        Some(p) => {
-            p.dispute_stake += stake;
-            ctx.accounts.book_pda.total_dispute_stake += stake;
-        }
-        None => {
-            return err!(BettingError::UserDidNotOptIn);
-        }
-    };
+    anchor_spl::token::transfer(bond_transfer_cpi_context, bond)?;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::rc::Rc;
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -72,12 +85,12 @@ mod test {
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
         account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-        signature::Keypair, signer::Signer, transaction::Transaction,
+        signature::Keypair, signer::Signer, system_program, transaction::Transaction,
     };
 
     use crate::{
-        constants::ORACLE_UPDATE_WINDOW,
-        state::{BetType, Book, Position},
+        constants::{MAX_DISPUTE_ROUNDS, ORACLE_UPDATE_WINDOW},
+        state::{BetOutcome, BetType, Book, BookParams, Dispute, DisputeRound, Quorum},
     };
 
     #[tokio::test]
@@ -126,7 +139,7 @@ mod test {
         );
         let book_pda_state = Book {
             total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
             game_id,
             initiator: Pubkey::new_unique(),
             bets_count: 0,
@@ -138,21 +151,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -186,6 +201,9 @@ mod test {
             },
         );
 
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -201,10 +219,16 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                dispute_round_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::For,
+                evidence: [1; 32],
+                bond: 1000000 * 20,
             })
-            .args(crate::instruction::BookBettorDispute { stake: 1000000 * 20 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -215,7 +239,7 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the stake should be transferred from the bettor token account
+        // the bond should be transferred from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
         assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
@@ -223,8 +247,22 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.total_dispute_stake, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 20);
-        // the stake should be transferred to the book ata
+        assert_eq!(book_state.dispute_bonds, [1000000 * 20, 0, 0, 0, 0]);
+        assert_eq!(book_state.disputes_count, 1);
+        let dispute = book_state.dispute.unwrap();
+        assert_eq!(dispute.round, 1);
+        assert_eq!(dispute.outcome, BetOutcome::For);
+        assert_eq!(dispute.bond, 1000000 * 20);
+        assert!(!dispute.escalated);
+        // the dispute round pda should be initialized
+        let dispute_round_account = banks_client.get_account(dispute_round_pda).await.unwrap().unwrap();
+        let dispute_round_state = DisputeRound::try_deserialize(&mut dispute_round_account.data.as_slice()).unwrap();
+        assert_eq!(dispute_round_state.disputer, bettor.pubkey());
+        assert_eq!(dispute_round_state.round, 1);
+        assert_eq!(dispute_round_state.outcome, BetOutcome::For);
+        assert_eq!(dispute_round_state.evidence, [1; 32]);
+        assert_eq!(dispute_round_state.bond, 1000000 * 20);
+        // the bond should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
@@ -277,7 +315,7 @@ mod test {
         );
         let book_pda_state = Book {
             total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
             game_id,
             initiator: Pubkey::new_unique(),
             bets_count: 0,
@@ -289,21 +327,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -337,6 +377,9 @@ mod test {
             },
         );
 
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -352,10 +395,16 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                dispute_round_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::For,
+                evidence: [1; 32],
+                bond: 20,
             })
-            .args(crate::instruction::BookBettorDispute { stake: 20 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -366,7 +415,7 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the stake should be transferred from the bettor token account
+        // the bond should be transferred from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
         assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
@@ -374,8 +423,22 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.total_dispute_stake, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 20);
-        // the stake should be transferred to the book ata
+        assert_eq!(book_state.dispute_bonds, [1000000 * 20, 0, 0, 0, 0]);
+        assert_eq!(book_state.disputes_count, 1);
+        let dispute = book_state.dispute.unwrap();
+        assert_eq!(dispute.round, 1);
+        assert_eq!(dispute.outcome, BetOutcome::For);
+        assert_eq!(dispute.bond, 1000000 * 20);
+        assert!(!dispute.escalated);
+        // the dispute round pda should be initialized
+        let dispute_round_account = banks_client.get_account(dispute_round_pda).await.unwrap().unwrap();
+        let dispute_round_state = DisputeRound::try_deserialize(&mut dispute_round_account.data.as_slice()).unwrap();
+        assert_eq!(dispute_round_state.disputer, bettor.pubkey());
+        assert_eq!(dispute_round_state.round, 1);
+        assert_eq!(dispute_round_state.outcome, BetOutcome::For);
+        assert_eq!(dispute_round_state.evidence, [1; 32]);
+        assert_eq!(dispute_round_state.bond, 1000000 * 20);
+        // the bond should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
@@ -428,7 +491,7 @@ mod test {
         );
         let book_pda_state = Book {
             total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
             game_id,
             initiator: Pubkey::new_unique(),
             bets_count: 0,
@@ -440,21 +503,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -488,6 +553,9 @@ mod test {
             },
         );
 
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -503,10 +571,16 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                dispute_round_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::For,
+                evidence: [1; 32],
+                bond: 1000000 * 20,
             })
-            .args(crate::instruction::BookBettorDispute { stake: 1000000 * 20 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -517,7 +591,7 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the stake should be transferred from the bettor token account
+        // the bond should be transferred from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
         assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
@@ -525,8 +599,22 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.total_dispute_stake, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 20);
-        // the stake should be transferred to the book ata
+        assert_eq!(book_state.dispute_bonds, [1000000 * 20, 0, 0, 0, 0]);
+        assert_eq!(book_state.disputes_count, 1);
+        let dispute = book_state.dispute.unwrap();
+        assert_eq!(dispute.round, 1);
+        assert_eq!(dispute.outcome, BetOutcome::For);
+        assert_eq!(dispute.bond, 1000000 * 20);
+        assert!(!dispute.escalated);
+        // the dispute round pda should be initialized
+        let dispute_round_account = banks_client.get_account(dispute_round_pda).await.unwrap().unwrap();
+        let dispute_round_state = DisputeRound::try_deserialize(&mut dispute_round_account.data.as_slice()).unwrap();
+        assert_eq!(dispute_round_state.disputer, bettor.pubkey());
+        assert_eq!(dispute_round_state.round, 1);
+        assert_eq!(dispute_round_state.outcome, BetOutcome::For);
+        assert_eq!(dispute_round_state.evidence, [1; 32]);
+        assert_eq!(dispute_round_state.bond, 1000000 * 20);
+        // the bond should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
@@ -579,7 +667,7 @@ mod test {
         );
         let book_pda_state = Book {
             total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
             game_id,
             initiator: Pubkey::new_unique(),
             bets_count: 0,
@@ -591,21 +679,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -639,6 +729,9 @@ mod test {
             },
         );
 
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -654,10 +747,16 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                dispute_round_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::For,
+                evidence: [1; 32],
+                bond: 1000000 * 20,
             })
-            .args(crate::instruction::BookBettorDispute { stake: 1000000 * 20 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -668,7 +767,7 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the stake should be transferred from the bettor token account
+        // the bond should be transferred from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
         assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
@@ -676,16 +775,29 @@ mod test {
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.total_dispute_stake, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 20);
-        // the stake should be transferred to the book ata
+        assert_eq!(book_state.dispute_bonds, [1000000 * 20, 0, 0, 0, 0]);
+        assert_eq!(book_state.disputes_count, 1);
+        let dispute = book_state.dispute.unwrap();
+        assert_eq!(dispute.round, 1);
+        assert_eq!(dispute.outcome, BetOutcome::For);
+        assert_eq!(dispute.bond, 1000000 * 20);
+        assert!(!dispute.escalated);
+        // the dispute round pda should be initialized
+        let dispute_round_account = banks_client.get_account(dispute_round_pda).await.unwrap().unwrap();
+        let dispute_round_state = DisputeRound::try_deserialize(&mut dispute_round_account.data.as_slice()).unwrap();
+        assert_eq!(dispute_round_state.disputer, bettor.pubkey());
+        assert_eq!(dispute_round_state.round, 1);
+        assert_eq!(dispute_round_state.outcome, BetOutcome::For);
+        assert_eq!(dispute_round_state.evidence, [1; 32]);
+        assert_eq!(dispute_round_state.bond, 1000000 * 20);
+        // the bond should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6004)")]
-    async fn test_book_bettor_dispute_err_bettor_not_opt_in() {
+    async fn test_book_bettor_dispute_success_counter_escalates() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -730,7 +842,7 @@ mod test {
         );
         let book_pda_state = Book {
             total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
             game_id,
             initiator: Pubkey::new_unique(),
             bets_count: 0,
@@ -739,24 +851,33 @@ mod test {
             payout_against_total: 0,
             dealt_wager: 0,
             bet_type,
-            total_dispute_stake: 0,
+            total_dispute_stake: 1000000 * 20,
             dispute_resolution_result: None,
             concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                Pubkey::new_unique(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS - 1,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 20,
+                ends_at: chrono::Utc::now().timestamp() + 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 20, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -774,7 +895,7 @@ mod test {
         let book_ata_state = anchor_spl::token::spl_token::state::Account {
             mint: USDC,
             owner: book_pda,
-            amount: 0,
+            amount: 1000000 * 20,
             state: anchor_spl::token::spl_token::state::AccountState::Initialized,
             ..Default::default()
         };
@@ -790,6 +911,11 @@ mod test {
             },
         );
 
+        let (dispute_round_pda, _) = Pubkey::find_program_address(
+            &[b"DisputeRound".as_ref(), book_pda.as_ref(), &[MAX_DISPUTE_ROUNDS]],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -805,10 +931,16 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                dispute_round_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::Against,
+                evidence: [1; 32],
+                bond: 1000000 * 40,
             })
-            .args(crate::instruction::BookBettorDispute { stake: 1000000 * 20 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -819,18 +951,480 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the stake should be transferred from the bettor token account
+        // the bond should be transferred from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
-        // the book pda should be updated
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 60);
+        // the book pda should be updated, the last round falls through to the council
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.total_dispute_stake, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 20);
-        // the stake should be transferred to the book ata
+        assert_eq!(book_state.total_dispute_stake, 1000000 * 60);
+        assert_eq!(book_state.dispute_bonds, [1000000 * 20, 0, 1000000 * 40, 0, 0]);
+        assert_eq!(book_state.disputes_count, 2);
+        let dispute = book_state.dispute.unwrap();
+        assert_eq!(dispute.round, MAX_DISPUTE_ROUNDS);
+        assert_eq!(dispute.outcome, BetOutcome::Against);
+        assert_eq!(dispute.bond, 1000000 * 40);
+        assert!(dispute.escalated);
+        // the dispute round pda should be initialized
+        let dispute_round_account = banks_client.get_account(dispute_round_pda).await.unwrap().unwrap();
+        let dispute_round_state = DisputeRound::try_deserialize(&mut dispute_round_account.data.as_slice()).unwrap();
+        assert_eq!(dispute_round_state.round, MAX_DISPUTE_ROUNDS);
+        assert_eq!(dispute_round_state.outcome, BetOutcome::Against);
+        // the bond should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
-        assert_eq!(book_ata_account_state.amount, 1000000 * 20);
+        assert_eq!(book_ata_account_state.amount, 1000000 * 60);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6005)")]
+    async fn test_book_bettor_dispute_err_counter_bond_not_doubled() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 20,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 1,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 20,
+                ends_at: chrono::Utc::now().timestamp() + 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 20, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[2]], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorDisputeAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                dispute_round_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::Against,
+                evidence: [1; 32],
+                bond: 1000000 * 30,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6026)")]
+    async fn test_book_bettor_dispute_err_same_outcome_as_oracles() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorDisputeAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                dispute_round_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::Against,
+                evidence: [1; 32],
+                bond: 1000000 * 20,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6010)")]
+    async fn test_book_bettor_dispute_err_invalid_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorDisputeAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                dispute_round_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorDispute {
+                bet_outcome: BetOutcome::HalfAgainst,
+                evidence: [1; 32],
+                bond: 1000000 * 20,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
     }
 }
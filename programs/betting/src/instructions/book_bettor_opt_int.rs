@@ -1,7 +1,6 @@
 use anchor_lang::{prelude::*, system_program};
 
 use crate::{
-    constants::{ORACLE_UPDATE_WINDOW, RENT_PER_POSITION},
     error::BettingError,
     state::{user_account::UserAccount, Book, Position},
 };
@@ -14,6 +13,8 @@ pub struct BookBettorOptInAccounts<'info> {
     pub bettor_user_account: Account<'info, UserAccount>,
     #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
     pub book_pda: Account<'info, Book>,
+    #[account(init,payer=bettor,space=Position::INIT_SPACE,seeds=[b"Position".as_ref(),book_pda.key().as_ref(),bettor.key().as_ref()],bump)]
+    pub position_pda: AccountLoader<'info, Position>,
     pub system_program: Program<'info, System>,
 }
 
@@ -22,7 +23,7 @@ pub fn book_bettor_opt_int(ctx: Context<BookBettorOptInAccounts>) -> Result<()>
     let now = Clock::get()?.unix_timestamp;
     require!(
         ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
         BettingError::NotInWindow
     );
     // update bettor user account
@@ -58,48 +59,19 @@ pub fn book_bettor_opt_int(ctx: Context<BookBettorOptInAccounts>) -> Result<()>
         }
     }
 
+    // init position pda, the bettor pays its rent and gets it back on settlement
+    let mut position = ctx.accounts.position_pda.load_init()?;
+    position.book = ctx.accounts.book_pda.key();
+    position.bettor = ctx.accounts.bettor.key();
     // update book pda
-    if ctx.accounts.book_pda.positions.contains_key(ctx.accounts.bettor.key) {
-        return err!(BettingError::UserAlreadyOptIn);
-    } else {
-        // create position
-        ctx.accounts.book_pda.positions.insert(
-            ctx.accounts.bettor.key(),
-            Position {
-                active_bets_count: 0,
-                bets_count: 0,
-                payout_for: 0,
-                payout_against: 0,
-                wager: 0,
-                dealt_wager: 0,
-                dispute_stake: 0,
-            },
-        );
-        // realloc
-        let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
-        let book_pda_space = ctx.accounts.book_pda.current_space();
-        book_pda_account_info.realloc(book_pda_space, false)?;
-
-        // transfer rent
-        let position_rent_transfer_cpi_context = CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.bettor.to_account_info(),
-                to: ctx.accounts.book_pda.to_account_info(),
-            },
-        );
-        system_program::transfer(position_rent_transfer_cpi_context, RENT_PER_POSITION)?;
-    }
+    ctx.accounts.book_pda.positions_count += 1;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::VecDeque, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -109,7 +81,7 @@ mod test {
         signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::state::{user_account::UserAccount, BetType, Book, Position};
+    use crate::state::{user_account::UserAccount, BetType, Book, BookParams, Position, Quorum};
 
     #[tokio::test]
     async fn test_book_bettor_opt_in_success() {
@@ -169,10 +141,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -186,6 +171,11 @@ mod test {
             },
         );
 
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -201,6 +191,7 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_user_account: bettor_pda,
                 book_pda,
+                position_pda: bettor_position_pda,
                 system_program: system_program::id(),
             })
             .args(crate::instruction::BookBettorOptInt)
@@ -218,10 +209,15 @@ mod test {
         let bettor_user_account = banks_client.get_account(bettor_pda).await.unwrap().unwrap();
         let bettor_user_account_state = UserAccount::try_deserialize(&mut bettor_user_account.data.as_slice()).unwrap();
         assert!(bettor_user_account_state.books_bet_on.contains(&book_pda));
-        // a position should be created in the book pda account
+        // a position pda should be created and counted in the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.positions.contains_key(&bettor.pubkey()));
+        assert_eq!(book_state.positions_count, 1);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.book, book_pda);
+        assert_eq!(bettor_position_pda_state.bettor, bettor.pubkey());
     }
 
     #[tokio::test]
@@ -263,21 +259,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -311,6 +309,11 @@ mod test {
             },
         );
 
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -326,6 +329,7 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_user_account: bettor_pda,
                 book_pda,
+                position_pda: bettor_position_pda,
                 system_program: system_program::id(),
             })
             .args(crate::instruction::BookBettorOptInt)
@@ -343,10 +347,15 @@ mod test {
         let bettor_user_account = banks_client.get_account(bettor_pda).await.unwrap().unwrap();
         let bettor_user_account_state = UserAccount::try_deserialize(&mut bettor_user_account.data.as_slice()).unwrap();
         assert!(bettor_user_account_state.books_bet_on.contains(&book_pda));
-        // a position should be created in the book pda account
+        // a position pda should be created and counted in the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.positions.contains_key(&bettor.pubkey()));
+        assert_eq!(book_state.positions_count, 1);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.book, book_pda);
+        assert_eq!(bettor_position_pda_state.bettor, bettor.pubkey());
     }
 
     #[tokio::test]
@@ -408,10 +417,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -425,6 +447,11 @@ mod test {
             },
         );
 
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -440,6 +467,7 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_user_account: bettor_pda,
                 book_pda,
+                position_pda: bettor_position_pda,
                 system_program: system_program::id(),
             })
             .args(crate::instruction::BookBettorOptInt)
@@ -457,9 +485,14 @@ mod test {
         let bettor_user_account = banks_client.get_account(bettor_pda).await.unwrap().unwrap();
         let bettor_user_account_state = UserAccount::try_deserialize(&mut bettor_user_account.data.as_slice()).unwrap();
         assert!(bettor_user_account_state.books_bet_on.contains(&book_pda));
-        // a position should be created in the book pda account
+        // a position pda should be created and counted in the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.positions.contains_key(&bettor.pubkey()));
+        assert_eq!(book_state.positions_count, 1);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.book, book_pda);
+        assert_eq!(bettor_position_pda_state.bettor, bettor.pubkey());
     }
 }
@@ -5,9 +5,9 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::{MIN_BET_AMOUNT, ORACLE_UPDATE_WINDOW, RENT_PER_BET},
     error::BettingError,
-    state::{BetDirection, Book},
+    events::BetPlaced,
+    state::{Bet, BetDirection, BetExpiry, Book, BookPage, Game, OrderType, Position},
 };
 
 #[derive(Accounts)]
@@ -18,32 +18,108 @@ pub struct BookBettorPlaceBetAccounts<'info> {
     pub bettor_token_account: Account<'info, TokenAccount>,
     #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
     pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Position".as_ref(),book_pda.key().as_ref(),bettor.key().as_ref()],bump)]
+    pub position_pda: AccountLoader<'info, Position>,
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
+// the page of the price level of a resting bet, and the pages of the opposite price levels and the positions of the
+// bettors it may be matched against are passed as remaining accounts
 pub fn book_bettor_place_bet(
     ctx: Context<BookBettorPlaceBetAccounts>,
     odds: u32,
     wager: u64,
     bet_direction: BetDirection,
+    order_type: OrderType,
+    expiry: Option<BetExpiry>,
 ) -> Result<()> {
     // check condition
     require!(
-        ctx.accounts.book_pda.positions.contains_key(ctx.accounts.bettor.key),
-        BettingError::UserDidNotOptIn
+        wager >= ctx.accounts.book_pda.params.min_bet_amount,
+        BettingError::MinTokenAmountNotMet
     );
-    require!(wager >= MIN_BET_AMOUNT, BettingError::MinTokenAmountNotMet);
+    require!(odds as u64 > Bet::ODDS_PRECISION, BettingError::InvalidOdds);
     // check window
     let now = Clock::get()?.unix_timestamp;
     require!(
         ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
         BettingError::NotInWindow
     );
-    // transfer wager
+    // pre-match books close at kickoff, in-play books reject bets while suspended
+    if ctx.accounts.book_pda.in_play.is_none() {
+        require!(now < ctx.accounts.game_pda.kickoff, BettingError::NotInWindow);
+    }
+    require!(!ctx.accounts.book_pda.is_suspended(now), BettingError::MarketSuspended);
+    // orders matching right away would skip the bet delay of in-play books
+    let immediate = matches!(order_type, OrderType::ImmediateOrCancel | OrderType::FillOrKill);
+    require!(
+        !immediate || ctx.accounts.book_pda.in_play.is_none(),
+        BettingError::InvalidOrderType
+    );
+    // resolve the expiry of a resting bet
+    let expires_at = match expiry {
+        Some(BetExpiry::At(expires_at)) => expires_at,
+        Some(BetExpiry::Kickoff) => ctx.accounts.game_pda.kickoff,
+        None => 0,
+    };
+    require!(expiry.is_none() || expires_at > now, BettingError::InvalidExpiry);
+    // update book pda
+    let bettor = ctx.accounts.bettor.key();
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let position_loaders = Position::loaders(ctx.remaining_accounts)?;
+    let mut positions = vec![ctx.accounts.position_pda.load_mut()?];
+    positions.extend(Position::load_positions(&book_key, &position_loaders)?);
+    let (dealt_wager, payout) = match order_type {
+        OrderType::Limit => {
+            ctx.accounts.book_pda.new_bet(
+                &mut pages,
+                &mut positions,
+                odds,
+                wager,
+                bettor,
+                bet_direction,
+                now,
+                expires_at,
+            )?;
+            (0, 0)
+        }
+        OrderType::PostOnly => {
+            require!(
+                !ctx.accounts.book_pda.crosses_front(&pages, odds, bet_direction, now)?,
+                BettingError::OrderWouldCross
+            );
+            ctx.accounts.book_pda.new_bet(
+                &mut pages,
+                &mut positions,
+                odds,
+                wager,
+                bettor,
+                bet_direction,
+                now,
+                expires_at,
+            )?;
+            (0, 0)
+        }
+        OrderType::ImmediateOrCancel | OrderType::FillOrKill => {
+            let (dealt_wager, payout) =
+                ctx.accounts
+                    .book_pda
+                    .take_bet(&mut pages, &mut positions, odds, wager, bettor, bet_direction, now)?;
+            if order_type == OrderType::FillOrKill {
+                require!(dealt_wager == wager, BettingError::OrderNotFilled);
+            }
+            (dealt_wager, payout)
+        }
+    };
+    // transfer wager, the unmatched rest of an immediate order is never taken
     let wager_transfer_cpi_context = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         anchor_spl::token::Transfer {
@@ -52,45 +128,63 @@ pub fn book_bettor_place_bet(
             authority: ctx.accounts.bettor.to_account_info(),
         },
     );
-    anchor_spl::token::transfer(wager_transfer_cpi_context, wager)?;
-    // update book pda
-    ctx.accounts
-        .book_pda
-        .new_bet(odds, wager, ctx.accounts.bettor.key(), bet_direction);
+    anchor_spl::token::transfer(wager_transfer_cpi_context, if immediate { dealt_wager } else { wager })?;
 
-    // realloc
+    // realloc, a new price level may be listed
     let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
-    book_pda_account_info.realloc(ctx.accounts.book_pda.current_space(), false)?;
-    // transfer rent for bet
-    let rent_transfer_cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        system_program::Transfer {
-            from: ctx.accounts.bettor.to_account_info(),
-            to: ctx.accounts.book_pda.to_account_info(),
-        },
-    );
-    system_program::transfer(rent_transfer_cpi_context, RENT_PER_BET)?;
+    let book_pda_space = ctx.accounts.book_pda.current_space();
+    book_pda_account_info.realloc(book_pda_space, false)?;
+    // rent
+    let min_rent = Rent::get()?.minimum_balance(book_pda_space);
+    if book_pda_account_info.lamports() < min_rent {
+        let diff = min_rent - book_pda_account_info.lamports();
+        let rent_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bettor.to_account_info(),
+                to: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        system_program::transfer(rent_transfer_cpi_context, diff)?;
+    }
+
+    let average_odds = if dealt_wager > 0 {
+        u32::try_from(payout as u128 * Bet::ODDS_PRECISION as u128 / dealt_wager as u128)
+            .map_err(|_| error!(BettingError::MathOverflow))?
+    } else {
+        0
+    };
+    emit!(BetPlaced {
+        book: ctx.accounts.book_pda.key(),
+        bettor,
+        bet_direction,
+        order_type,
+        odds,
+        wager,
+        dealt_wager,
+        payout,
+        average_odds,
+    });
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::BTreeMap, rc::Rc};
 
     use anchor_client::RequestBuilder;
-    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
     use anchor_spl::mint::USDC;
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
-        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-        signature::Keypair, signer::Signer, system_program, transaction::Transaction,
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::state::{BetDirection, BetType, Book, Position};
+    use crate::state::{
+        BetDirection, BetExpiry, BetType, Book, BookPage, BookParams, Game, InPlay, OrderType, Position, Quorum,
+    };
 
     #[tokio::test]
     async fn test_book_bettor_place_bet_success() {
@@ -127,6 +221,30 @@ mod test {
         );
 
         let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -150,22 +268,75 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
         };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -213,14 +384,19 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
             .args(crate::instruction::BookBettorPlaceBet {
                 odds: 1200,
                 wager: 1000000 * 20,
                 bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
             })
             .instructions()
             .unwrap();
@@ -241,14 +417,19 @@ mod test {
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.bets_count, 1);
         assert_eq!(book_state.wager_total, 1000000 * 20);
-        assert_eq!(book_state.bets_for.len(), 1);
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].odds(), 1200);
-        assert_eq!(book_state.bets_for[0].wager, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 1000000 * 20);
+        assert_eq!(book_state.levels_for, vec![1200]);
+        let book_page_account = banks_client.get_account(book_page_pda).await.unwrap().unwrap();
+        let book_page_state = BookPage::try_deserialize(&mut book_page_account.data.as_slice()).unwrap();
+        assert_eq!(book_page_state.bets().len(), 1);
+        assert_eq!(book_page_state.bets()[0].bettor, bettor.pubkey());
+        assert_eq!(book_page_state.bets()[0].odds(), 1200);
+        assert_eq!(book_page_state.bets()[0].wager, 1000000 * 20);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 20);
         // wager should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -256,8 +437,7 @@ mod test {
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6004)")]
-    async fn test_book_bettor_place_bet_err_user_did_not_opt_in() {
+    async fn test_book_bettor_place_bet_success_immediate_or_cancel() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -291,6 +471,30 @@ mod test {
         );
 
         let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -300,7 +504,7 @@ mod test {
             ],
             &program_id,
         );
-        let book_pda_state = Book {
+        let mut book_pda_state = Book {
             total_oracle_stake: 0,
             aggregated_oracle_outcome: None,
             game_id,
@@ -314,11 +518,106 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+        };
+        let maker = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (maker_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), maker.as_ref()], &program_id);
+        let mut maker_position_pda_state = Position {
+            book: book_pda,
+            bettor: maker,
+            ..Default::default()
         };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 6000;
+        book_page_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut maker_position_pda_state],
+                6000,
+                1000000 * 2,
+                maker,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut maker_position_pda_data = Position::discriminator().to_vec();
+        maker_position_pda_data.extend_from_slice(bytemuck::bytes_of(&maker_position_pda_state));
+        program_test.add_account(
+            maker_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: maker_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -366,14 +665,20 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .accounts(AccountMeta::new(maker_position_pda, false))
             .args(crate::instruction::BookBettorPlaceBet {
                 odds: 1200,
                 wager: 1000000 * 20,
                 bet_direction: BetDirection::For,
+                order_type: OrderType::ImmediateOrCancel,
+                expiry: None,
             })
             .instructions()
             .unwrap();
@@ -385,32 +690,38 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // wager should be transferred  out from the bettor token account
+        // only the matched part of the wager should be transferred out from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
-        // book pda should be updated
-        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
-        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.bets_count, 1);
-        assert_eq!(book_state.wager_total, 1000000 * 20);
-        assert_eq!(book_state.bets_for.len(), 1);
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].odds(), 1200);
-        assert_eq!(book_state.bets_for[0].wager, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 1000000 * 20);
-        // wager should be transferred to the book ata
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 90);
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
-        assert_eq!(book_ata_account_state.amount, 1000000 * 20);
+        assert_eq!(book_ata_account_state.amount, 1000000 * 10);
+        // the bet should be matched against the resting bet and the rest should not rest in the book
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 2);
+        assert!(book_state.levels_for.is_empty());
+        assert!(book_state.levels_against.is_empty());
+        assert_eq!(book_state.dealt_wager, 1000000 * 12);
+        assert_eq!(book_state.payout_for_total, 1000000 * 12);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 10);
+        assert_eq!(bettor_position_pda_state.dealt_wager, 1000000 * 10);
+        assert_eq!(bettor_position_pda_state.payout_for, 1000000 * 12);
+        let maker_position_pda_account = banks_client.get_account(maker_position_pda).await.unwrap().unwrap();
+        let maker_position_pda_state =
+            Position::try_deserialize(&mut maker_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(maker_position_pda_state.active_bets_count, 0);
+        assert_eq!(maker_position_pda_state.payout_against, 1000000 * 12);
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6005)")]
-    async fn test_book_bettor_place_bet_err_wager_too_low() {
+    async fn test_book_bettor_place_bet_success_with_spread() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -444,6 +755,30 @@ mod test {
         );
 
         let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -453,7 +788,7 @@ mod test {
             ],
             &program_id,
         );
-        let book_pda_state = Book {
+        let mut book_pda_state = Book {
             total_oracle_stake: 0,
             aggregated_oracle_outcome: None,
             game_id,
@@ -467,22 +802,106 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+        };
+        let maker = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
         };
+        let (maker_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), maker.as_ref()], &program_id);
+        let mut maker_position_pda_state = Position {
+            book: book_pda,
+            bettor: maker,
+            ..Default::default()
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &2000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 2000;
+        book_page_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut maker_position_pda_state],
+                2000,
+                1000000 * 10,
+                maker,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut maker_position_pda_data = Position::discriminator().to_vec();
+        maker_position_pda_data.extend_from_slice(bytemuck::bytes_of(&maker_position_pda_state));
+        program_test.add_account(
+            maker_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: maker_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -499,7 +918,7 @@ mod test {
         let book_ata_state = anchor_spl::token::spl_token::state::Account {
             mint: USDC,
             owner: book_pda,
-            amount: 0,
+            amount: 1000000 * 10,
             state: anchor_spl::token::spl_token::state::AccountState::Initialized,
             ..Default::default()
         };
@@ -530,14 +949,20 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
             })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .accounts(AccountMeta::new(maker_position_pda, false))
             .args(crate::instruction::BookBettorPlaceBet {
-                odds: 1200,
-                wager: 20,
+                odds: 1500,
+                wager: 1000000 * 20,
                 bet_direction: BetDirection::For,
+                order_type: OrderType::ImmediateOrCancel,
+                expiry: None,
             })
             .instructions()
             .unwrap();
@@ -549,32 +974,42 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // wager should be transferred  out from the bettor token account
+        // the bettor only covers the rest of the payout of the resting bet at 2.0, below its own odds of 1.5
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
-        // book pda should be updated
-        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
-        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.bets_count, 1);
-        assert_eq!(book_state.wager_total, 1000000 * 20);
-        assert_eq!(book_state.bets_for.len(), 1);
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].odds(), 1200);
-        assert_eq!(book_state.bets_for[0].wager, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 1000000 * 20);
-        // wager should be transferred to the book ata
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 90);
+        // the book ata holds exactly the payout, so settlement pays it out without a remainder
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
+        // the bet should be matched against the resting bet and the rest should not rest in the book
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 2);
+        assert!(book_state.levels_for.is_empty());
+        assert!(book_state.levels_against.is_empty());
+        assert_eq!(book_state.dealt_wager, 1000000 * 20);
+        assert_eq!(book_state.payout_for_total, 1000000 * 20);
+        assert_eq!(book_state.payout_against_total, 1000000 * 20);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 0);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 10);
+        assert_eq!(bettor_position_pda_state.dealt_wager, 1000000 * 10);
+        assert_eq!(bettor_position_pda_state.payout_for, 1000000 * 20);
+        let maker_position_pda_account = banks_client.get_account(maker_position_pda).await.unwrap().unwrap();
+        let maker_position_pda_state =
+            Position::try_deserialize(&mut maker_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(maker_position_pda_state.active_bets_count, 0);
+        assert_eq!(maker_position_pda_state.payout_against, 1000000 * 20);
+        assert_eq!(maker_position_pda_state.dealt_wager, 1000000 * 10);
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6006)")]
-    async fn test_book_bettor_place_bet_err_bet_after_conclusion() {
+    #[should_panic(expected = "Custom(6004)")]
+    async fn test_book_bettor_place_bet_err_user_did_not_opt_in() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -608,6 +1043,30 @@ mod test {
         );
 
         let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -630,22 +1089,24 @@ mod test {
             bet_type,
             total_dispute_stake: 0,
             dispute_resolution_result: None,
-            concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -679,6 +1140,11 @@ mod test {
             },
         );
 
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -694,6 +1160,8 @@ mod test {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -702,6 +1170,8 @@ mod test {
                 odds: 1200,
                 wager: 1000000 * 20,
                 bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
             })
             .instructions()
             .unwrap();
@@ -722,17 +1192,1755 @@ mod test {
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
         assert_eq!(book_state.bets_count, 1);
         assert_eq!(book_state.wager_total, 1000000 * 20);
-        assert_eq!(book_state.bets_for.len(), 1);
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].bettor, bettor.pubkey());
-        assert_eq!(book_state.bets_for[0].odds(), 1200);
-        assert_eq!(book_state.bets_for[0].wager, 1000000 * 20);
-        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].bets_count, 1);
-        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 1000000 * 20);
+        assert_eq!(book_state.levels_for, vec![1200]);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 20);
         // wager should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_ata_account_state.amount, 1000000 * 20);
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6005)")]
+    async fn test_book_bettor_place_bet_err_wager_too_low() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // wager should be transferred  out from the bettor token account
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
+        // book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 1);
+        assert_eq!(book_state.wager_total, 1000000 * 20);
+        assert_eq!(book_state.levels_for, vec![1200]);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 20);
+        // wager should be transferred to the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 20);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_bettor_place_bet_err_bet_after_conclusion() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // wager should be transferred  out from the bettor token account
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
+        // book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 1);
+        assert_eq!(book_state.wager_total, 1000000 * 20);
+        assert_eq!(book_state.levels_for, vec![1200]);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 20);
+        // wager should be transferred to the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 20);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_bettor_place_bet_err_bet_after_kickoff() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6014)")]
+    async fn test_book_bettor_place_bet_err_market_suspended() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 5,
+                suspended_until: chrono::Utc::now().timestamp() + 60,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6009)")]
+    async fn test_book_bettor_place_bet_err_invalid_odds() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1000,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6017)")]
+    async fn test_book_bettor_place_bet_err_fill_or_kill_not_filled() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+        };
+        let maker = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (maker_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), maker.as_ref()], &program_id);
+        let mut maker_position_pda_state = Position {
+            book: book_pda,
+            bettor: maker,
+            ..Default::default()
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 6000;
+        book_page_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut maker_position_pda_state],
+                6000,
+                1000000 * 2,
+                maker,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut maker_position_pda_data = Position::discriminator().to_vec();
+        maker_position_pda_data.extend_from_slice(bytemuck::bytes_of(&maker_position_pda_state));
+        program_test.add_account(
+            maker_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: maker_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .accounts(AccountMeta::new(maker_position_pda, false))
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::FillOrKill,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6018)")]
+    async fn test_book_bettor_place_bet_err_post_only_would_cross() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+        };
+        let maker = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (maker_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), maker.as_ref()], &program_id);
+        let mut maker_position_pda_state = Position {
+            book: book_pda,
+            bettor: maker,
+            ..Default::default()
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = Pubkey::new_unique();
+        book_page_pda_state.odds = 6000;
+        book_page_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut maker_position_pda_state],
+                6000,
+                1000000 * 2,
+                maker,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut maker_position_pda_data = Position::discriminator().to_vec();
+        maker_position_pda_data.extend_from_slice(bytemuck::bytes_of(&maker_position_pda_state));
+        program_test.add_account(
+            maker_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: maker_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .accounts(AccountMeta::new(book_page_pda, false))
+            .accounts(AccountMeta::new(maker_position_pda, false))
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::PostOnly,
+                expiry: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6019)")]
+    async fn test_book_bettor_place_bet_err_expiry_in_past() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor.pubkey().as_ref()],
+            &program_id,
+        );
+        let bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor.pubkey(),
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::BookBettorPlaceBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                position_pda: bettor_position_pda,
+                game_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookBettorPlaceBet {
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::For,
+                order_type: OrderType::Limit,
+                expiry: Some(BetExpiry::At(1)),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
 }
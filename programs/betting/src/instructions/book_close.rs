@@ -33,10 +33,12 @@ pub fn book_close(ctx: Context<BookCloseAccounts>) -> Result<()> {
     );
     // check whether the book is settled
     require!(
-        ctx.accounts.book_pda.bets_for.is_empty()
-            && ctx.accounts.book_pda.bets_against.is_empty()
-            && ctx.accounts.book_pda.oracles.is_empty()
-            && ctx.accounts.book_pda.positions.is_empty()
+        ctx.accounts.book_pda.levels_for.is_empty()
+            && ctx.accounts.book_pda.levels_against.is_empty()
+            && ctx.accounts.book_pda.oracles_count == 0
+            && ctx.accounts.book_pda.disputes_count == 0
+            && ctx.accounts.book_pda.positions_count == 0
+            && ctx.accounts.book_pda.parlays_count == 0
             && ctx.accounts.book_ata.amount == 0,
         BettingError::BookNotSettled
     );
@@ -81,7 +83,7 @@ mod test {
         signature::Keypair, signer::Signer, transaction::Transaction,
     };
 
-    use crate::state::{game::Game, user_account::UserAccount, BetType, Book};
+    use crate::state::{game::Game, user_account::UserAccount, BetType, Book, BookParams, Quorum};
 
     #[tokio::test]
     async fn test_book_close_success() {
@@ -130,6 +132,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -165,10 +171,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -304,6 +323,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -339,10 +362,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -478,6 +514,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -513,10 +553,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
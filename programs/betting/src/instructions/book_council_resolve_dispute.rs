@@ -0,0 +1,1152 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Book, Council, CouncilAction, CouncilProposal},
+};
+
+#[derive(Accounts)]
+pub struct BookCouncilResolveDisputeAccounts<'info> {
+    pub member: Signer<'info>,
+    #[account(seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(mut,close=proposer,seeds=[b"CouncilProposal".as_ref(),&proposal_pda.id.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+}
+
+// applies the outcome of a proposal approved by the council, the rent of the proposal is returned to the proposer
+pub fn book_council_resolve_dispute(ctx: Context<BookCouncilResolveDisputeAccounts>) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // check proposer
+    require_keys_eq!(
+        ctx.accounts.proposer.key(),
+        ctx.accounts.proposal_pda.proposer,
+        BettingError::NoAuthority
+    );
+    // check proposal
+    let bet_outcome = match ctx.accounts.proposal_pda.action {
+        CouncilAction::ResolveDispute { book, outcome } if book == ctx.accounts.book_pda.key() => outcome,
+        _ => return err!(BettingError::InvalidProposal),
+    };
+    require!(
+        ctx.accounts.council_pda.is_approved(&ctx.accounts.proposal_pda),
+        BettingError::ProposalNotApproved
+    );
+    require!(
+        ctx.accounts.book_pda.bet_type.is_valid_outcome(bet_outcome),
+        BettingError::InvalidOutcome
+    );
+    // check if the dispute fell through to the council and has not been resolved yet
+    require!(
+        matches!(ctx.accounts.book_pda.dispute, Some(d) if d.escalated),
+        BettingError::NoAuthority
+    );
+    require!(
+        ctx.accounts.book_pda.dispute_resolution_result.is_none(),
+        BettingError::NoAuthority
+    );
+    // must be within the resolution deadline, after it the book can only be cancelled
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now <= ctx.accounts.book_pda.resolution_deadline().unwrap(),
+        BettingError::NotInWindow
+    );
+    // update book pda
+    ctx.accounts.book_pda.dispute_resolution_result = Some(bet_outcome);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::{BETTOR_DISPUTE_WINDOW, COUNCIL_RESOLUTION_WINDOW, DISPUTE_ROUND_WINDOW, MAX_DISPUTE_ROUNDS},
+        state::{BetOutcome, BetType, Book, BookParams, Council, CouncilAction, CouncilProposal, Dispute, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_book_council_resolve_dispute_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30 + DISPUTE_ROUND_WINDOW,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+        // the proposal pda should be closed
+        assert!(banks_client.get_account(proposal_pda).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_council_resolve_dispute_err_not_in_dispute() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_council_resolve_dispute_err_not_escalated() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 1,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30 + DISPUTE_ROUND_WINDOW,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_council_resolve_dispute_err_deadline_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - COUNCIL_RESOLUTION_WINDOW - 30,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6029)")]
+    async fn test_book_council_resolve_dispute_err_not_approved() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30 + DISPUTE_ROUND_WINDOW,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey(), Pubkey::new_unique()],
+            threshold: 2,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6028)")]
+    async fn test_book_council_resolve_dispute_err_wrong_proposal() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30 + DISPUTE_ROUND_WINDOW,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: Pubkey::new_unique(),
+                outcome: BetOutcome::For,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6010)")]
+    async fn test_book_council_resolve_dispute_err_invalid_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30 + DISPUTE_ROUND_WINDOW,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::ResolveDispute {
+                book: book_pda,
+                outcome: BetOutcome::HalfFor,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::BookCouncilResolveDisputeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::BookCouncilResolveDispute)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,543 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Book, Config, Game, Oracle},
+};
+
+#[derive(Accounts)]
+pub struct BookDeriveOutcomeAccounts<'info> {
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+}
+
+// the oracle pdas of every oracle of the book are passed as remaining accounts
+pub fn book_derive_outcome(ctx: Context<BookDeriveOutcomeAccounts>) -> Result<()> {
+    // the final score of the game can no longer be updated
+    require!(ctx.accounts.game_pda.final_score.is_some(), BettingError::NoResultYet);
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.game_pda.concluded_at.unwrap() + ctx.accounts.config_pda.oracle_update_window < now,
+        BettingError::NotInWindow
+    );
+    // check oracle update window of the book
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    require!(ctx.accounts.book_pda.dispute.is_none(), BettingError::NotInWindow);
+    // update the outcome of the oracles which reported a score
+    let book_key = ctx.accounts.book_pda.key();
+    let oracles = Oracle::load_oracles(&book_key, ctx.remaining_accounts)?;
+    require!(
+        oracles.len() == ctx.accounts.book_pda.oracles_count as usize,
+        BettingError::InvalidOracles
+    );
+    let bet_type = ctx.accounts.book_pda.bet_type;
+    for mut o in oracles {
+        if let Some(score) = ctx.accounts.game_pda.score_reports.get(&o.oracle).and_then(|r| r.score) {
+            let outcome = Some(bet_type.outcome(score.home, score.away));
+            ctx.accounts.book_pda.remove_oracle_stake(o.outcome, o.stake);
+            ctx.accounts.book_pda.add_oracle_stake(outcome, o.stake);
+            if !o.revealed {
+                ctx.accounts.book_pda.revealed_oracles_count += 1;
+            }
+            o.outcome = outcome;
+            o.commitment = None;
+            o.revealed = true;
+            o.exit(ctx.program_id)?;
+        }
+    }
+    // update book pda, the outcomes derived for the oracles of the book must meet its quorum like revealed ones
+    let aggregated_outcome = ctx.accounts.book_pda.aggregated_outcome();
+    require!(aggregated_outcome.is_some(), BettingError::NoResultYet);
+    if ctx.accounts.book_pda.aggregated_oracle_outcome != aggregated_outcome {
+        ctx.accounts.book_pda.aggregated_oracle_outcome = aggregated_outcome;
+        ctx.accounts.book_pda.concluded_at = Some(now);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+        transaction::Transaction,
+    };
+
+    use crate::state::{BetOutcome, BetType, Book, BookParams, Config, Game, Oracle, Quorum, Score, ScoreReport};
+
+    #[tokio::test]
+    async fn test_book_derive_outcome_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: Some(Score { home: 2, away: 1 }),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            score_reports: BTreeMap::from_iter(oracles.map(|o| {
+                (
+                    o,
+                    ScoreReport {
+                        stake: 1000000 * 100,
+                        score: Some(Score { home: 2, away: 1 }),
+                        commitment: None,
+                    },
+                )
+            })),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::OverUnder { line: 5 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [1000000 * 300, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let book_oracle_pdas = oracles.map(|oracle| {
+            let (book_oracle_pda, _) =
+                Pubkey::find_program_address(&[b"Oracle".as_ref(), book_pda.as_ref(), oracle.as_ref()], &program_id);
+            let book_oracle_pda_state = Oracle {
+                book: book_pda,
+                oracle,
+                stake: 1000000 * 100,
+                outcome: None,
+                commitment: None,
+                revealed: false,
+            };
+            let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+            book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+            program_test.add_account(
+                book_oracle_pda,
+                Account {
+                    lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                    data: book_oracle_pda_data,
+                    owner: program_id,
+                    ..Default::default()
+                },
+            );
+            book_oracle_pda
+        });
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDeriveOutcomeAccounts {
+                config_pda,
+                game_pda,
+                book_pda,
+            })
+            .accounts(AccountMeta::new(book_oracle_pdas[0], false))
+            .accounts(AccountMeta::new(book_oracle_pdas[1], false))
+            .accounts(AccountMeta::new(book_oracle_pdas[2], false))
+            .args(crate::instruction::BookDeriveOutcome {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be concluded with the outcome derived from the scores its oracles revealed
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pdas[0]).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.outcome, Some(BetOutcome::For));
+        assert_eq!(book_state.aggregated_oracle_outcome, Some(BetOutcome::For));
+        assert_eq!(book_state.oracle_stakes, [0, 1000000 * 300, 0, 0, 0, 0]);
+        assert_eq!(book_state.revealed_oracles_count, 3);
+        assert!(book_state.concluded_at.is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6008)")]
+    async fn test_book_derive_outcome_err_quorum_not_met() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Pubkey::new_unique();
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: Some(Score { home: 2, away: 1 }),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            score_reports: BTreeMap::from([(
+                oracle,
+                ScoreReport {
+                    stake: 1000000 * 100,
+                    score: Some(Score { home: 2, away: 1 }),
+                    commitment: None,
+                },
+            )]),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::OverUnder { line: 5 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let (book_oracle_pda, _) =
+            Pubkey::find_program_address(&[b"Oracle".as_ref(), book_pda.as_ref(), oracle.as_ref()], &program_id);
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle,
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDeriveOutcomeAccounts {
+                config_pda,
+                game_pda,
+                book_pda,
+            })
+            .accounts(AccountMeta::new(book_oracle_pda, false))
+            .args(crate::instruction::BookDeriveOutcome {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6008)")]
+    async fn test_book_derive_outcome_err_no_final_score() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Pubkey::new_unique();
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::OverUnder { line: 5 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let (book_oracle_pda, _) =
+            Pubkey::find_program_address(&[b"Oracle".as_ref(), book_pda.as_ref(), oracle.as_ref()], &program_id);
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle,
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDeriveOutcomeAccounts {
+                config_pda,
+                game_pda,
+                book_pda,
+            })
+            .accounts(AccountMeta::new(book_oracle_pda, false))
+            .args(crate::instruction::BookDeriveOutcome {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,1191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::BettingError,
+    state::{Book, DisputeRound},
+};
+
+#[derive(Accounts)]
+pub struct BookDisputerSettleAccounts<'info> {
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub disputer: UncheckedAccount<'info>,
+    #[account(mut,token::mint=USDC,token::authority=disputer)]
+    pub disputer_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    #[account(mut,close=disputer,seeds=[b"DisputeRound".as_ref(),book_pda.key().as_ref(),&[dispute_round_pda.round]],bump)]
+    pub dispute_round_pda: Account<'info, DisputeRound>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn book_disputer_settle(ctx: Context<BookDisputerSettleAccounts>) -> Result<()> {
+    // check disputer
+    require_keys_eq!(
+        ctx.accounts.disputer.key(),
+        ctx.accounts.dispute_round_pda.disputer,
+        BettingError::NoAuthority
+    );
+    // must be concluded
+    require!(ctx.accounts.book_pda.concluded_at.is_some(), BettingError::NotInWindow);
+    // must have passed the dispute window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.bettor_dispute_window < now,
+        BettingError::NotInWindow
+    );
+    // must have passed the window of the latest dispute round
+    require!(!ctx.accounts.book_pda.is_in_dispute(now), BettingError::NotInWindow);
+    // must have an outcome
+    let final_outcome = ctx.accounts.book_pda.final_outcome();
+    require!(final_outcome.is_some(), BettingError::NoResultYet);
+    // update book pda
+    ctx.accounts.book_pda.disputes_count -= 1;
+    let d = &ctx.accounts.dispute_round_pda;
+    // every bond is returned when the book was cancelled on a missed deadline
+    let timed_out = ctx.accounts.book_pda.is_timed_out();
+    if timed_out || d.outcome == final_outcome.unwrap() {
+        // the round proposed the final outcome, pay
+        let mut usdc_to_transfer = 0;
+        usdc_to_transfer += d.bond; // return bond
+        if !timed_out {
+            let winning_dispute_bonds = ctx.accounts.book_pda.winning_dispute_bonds(final_outcome.unwrap());
+            // share of the bonds lost by the other rounds
+            let (_, lost_dispute_bonds_share) = ctx.accounts.book_pda.lost_dispute_bonds_shares(final_outcome.unwrap());
+            usdc_to_transfer += Book::pro_rata_share(lost_dispute_bonds_share, d.bond, winning_dispute_bonds)?;
+            // share of the stake slashed from the wrong oracles
+            let (_, slashed_stake_share) = ctx.accounts.book_pda.slashed_stake_shares(final_outcome.unwrap());
+            usdc_to_transfer += Book::pro_rata_share(slashed_stake_share, d.bond, winning_dispute_bonds)?;
+        }
+        // transfer usdc
+        let usdc_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.book_ata.to_account_info(),
+                to: ctx.accounts.disputer_token_account.to_account_info(),
+                authority: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
+        let book_pda_signer_seeds = &[
+            b"Book".as_ref(),
+            &ctx.accounts.book_pda.game_id.to_le_bytes(),
+            bet_type_vec.as_slice(),
+            &[*ctx.bumps.get("book_pda").unwrap()],
+        ];
+        anchor_spl::token::transfer(
+            usdc_transfer_cpi_context.with_signer(&[book_pda_signer_seeds]),
+            usdc_to_transfer,
+        )?;
+    } else {
+        // the round proposed another outcome, the bond goes to the winning side
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+        signature::Keypair, signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::BETTOR_DISPUTE_WINDOW,
+        state::{BetOutcome, BetType, Book, BookParams, Dispute, DisputeRound, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_book_disputer_settle_success_with_winning_round() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the oracles said against, the first round said for, the second against and the third for again
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80,
+                ends_at: chrono::Utc::now().timestamp() - 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 100, 0, 1000000 * 40, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: disputer.pubkey(),
+            round: 1,
+            outcome: BetOutcome::For,
+            evidence: [1; 32],
+            bond: 1000000 * 20,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the disputer
+        let disputer_account = banks_client.get_account(disputer.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            disputer_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(DisputeRound::INIT_SPACE)
+        );
+        // the dispute round pda should be closed and removed from the book pda
+        assert!(banks_client.get_account(dispute_round_pda).await.unwrap().is_none());
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.disputes_count, 2);
+        // the bond, its share of the bond lost by the second round and of the stake slashed from the wrong oracles
+        // should be transferred to the disputer token account
+        let disputer_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(disputer_ata).await.unwrap();
+        assert_eq!(
+            disputer_token_account_state.amount,
+            1000000 * 20 + 1000000 * 40 * 20 / 100 + 1000000 * 100 * 20 / 100
+        );
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 1000 - 1000000 * 48);
+    }
+
+    #[tokio::test]
+    async fn test_book_disputer_settle_success_with_losing_round() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the oracles said against, the first round said for, the second against and the third for again
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80,
+                ends_at: chrono::Utc::now().timestamp() - 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 100, 0, 1000000 * 40, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[2]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: disputer.pubkey(),
+            round: 2,
+            outcome: BetOutcome::Against,
+            evidence: [1; 32],
+            bond: 1000000 * 40,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the disputer
+        let disputer_account = banks_client.get_account(disputer.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            disputer_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(DisputeRound::INIT_SPACE)
+        );
+        // the dispute round pda should be closed and removed from the book pda
+        assert!(banks_client.get_account(dispute_round_pda).await.unwrap().is_none());
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.disputes_count, 2);
+        // the bond should stay in the book ata for the winning rounds
+        let disputer_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(disputer_ata).await.unwrap();
+        assert_eq!(disputer_token_account_state.amount, 0);
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 1000);
+    }
+
+    #[tokio::test]
+    async fn test_book_disputer_settle_success_with_timed_out_dispute() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the third round escalated the dispute and the council missed the resolution deadline
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140,
+            dispute_resolution_result: Some(BetOutcome::Cancel),
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80,
+                ends_at: chrono::Utc::now().timestamp() - 60,
+                escalated: true,
+                timed_out: true,
+            }),
+            dispute_bonds: [1000000 * 100, 0, 1000000 * 40, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[2]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: disputer.pubkey(),
+            round: 2,
+            outcome: BetOutcome::Against,
+            evidence: [1; 32],
+            bond: 1000000 * 40,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the disputer
+        let disputer_account = banks_client.get_account(disputer.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            disputer_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(DisputeRound::INIT_SPACE)
+        );
+        // the dispute round pda should be closed and removed from the book pda
+        assert!(banks_client.get_account(dispute_round_pda).await.unwrap().is_none());
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.disputes_count, 2);
+        // the bond should be returned even though the round lost against the other rounds
+        let disputer_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(disputer_ata).await.unwrap();
+        assert_eq!(disputer_token_account_state.amount, 1000000 * 40);
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 960);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_disputer_settle_err_latest_round_not_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the oracles said against, the first round said for, the second against and the third for again
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80,
+                ends_at: chrono::Utc::now().timestamp() + 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 100, 0, 1000000 * 40, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: disputer.pubkey(),
+            round: 1,
+            outcome: BetOutcome::For,
+            evidence: [1; 32],
+            bond: 1000000 * 20,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_disputer_settle_err_wrong_disputer() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the oracles said against, the first round said for, the second against and the third for again
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80,
+                ends_at: chrono::Utc::now().timestamp() - 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 100, 0, 1000000 * 40, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: Pubkey::new_unique(),
+            round: 1,
+            outcome: BetOutcome::For,
+            evidence: [1; 32],
+            bond: 1000000 * 20,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_book_disputer_settle_success_with_large_bonds() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let disputer = Keypair::new();
+        program_test.add_account(
+            disputer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let disputer_ata = anchor_spl::associated_token::get_associated_token_address(&disputer.pubkey(), &USDC);
+        let disputer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: disputer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut disputer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(disputer_ata_state, &mut disputer_ata_data).unwrap();
+        program_test.add_account(
+            disputer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(disputer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        // the oracles said against, the first round said for, the second against and the third for again
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300000,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 140000,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100000, 0, 1000000 * 200000, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: 3,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 80000,
+                ends_at: chrono::Utc::now().timestamp() - 60,
+                escalated: false,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 100000, 0, 1000000 * 40000, 0, 0],
+            disputes_count: 3,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (dispute_round_pda, _) =
+            Pubkey::find_program_address(&[b"DisputeRound".as_ref(), book_pda.as_ref(), &[1]], &program_id);
+        let dispute_round_pda_state = DisputeRound {
+            book: book_pda,
+            disputer: disputer.pubkey(),
+            round: 1,
+            outcome: BetOutcome::For,
+            evidence: [1; 32],
+            bond: 1000000 * 20000,
+        };
+        let mut dispute_round_pda_data: Vec<u8> = Vec::new();
+        dispute_round_pda_state
+            .try_serialize(&mut dispute_round_pda_data)
+            .unwrap();
+        program_test.add_account(
+            dispute_round_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(DisputeRound::INIT_SPACE),
+                data: dispute_round_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookDisputerSettleAccounts {
+                disputer: disputer.pubkey(),
+                disputer_token_account: disputer_ata,
+                book_pda,
+                book_ata,
+                dispute_round_pda,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookDisputerSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the disputer
+        let disputer_account = banks_client.get_account(disputer.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            disputer_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(DisputeRound::INIT_SPACE)
+        );
+        // the dispute round pda should be closed and removed from the book pda
+        assert!(banks_client.get_account(dispute_round_pda).await.unwrap().is_none());
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.disputes_count, 2);
+        // the pro rata shares of bonds this large should not overflow
+        let disputer_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(disputer_ata).await.unwrap();
+        assert_eq!(
+            disputer_token_account_state.amount,
+            1000000 * 20000 + 1000000 * 40000 * 20 / 100 + 1000000 * 100000 * 20 / 100
+        );
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 1000000 - 1000000 * 48000);
+    }
+}
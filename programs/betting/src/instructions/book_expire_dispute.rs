@@ -0,0 +1,330 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{BetOutcome, Book},
+};
+
+#[derive(Accounts)]
+pub struct BookExpireDisputeAccounts<'info> {
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+}
+
+// anyone can cancel the book once the council missed the deadline to resolve an escalated dispute, the bettors are
+// refunded and every oracle stake and dispute bond is returned
+pub fn book_expire_dispute(ctx: Context<BookExpireDisputeAccounts>) -> Result<()> {
+    // check if the dispute fell through to the council
+    require!(
+        matches!(ctx.accounts.book_pda.dispute, Some(d) if d.escalated),
+        BettingError::NoAuthority
+    );
+    // check if the council has not resolved it yet
+    require!(
+        ctx.accounts.book_pda.dispute_resolution_result.is_none(),
+        BettingError::NoAuthority
+    );
+    // must have passed the resolution deadline
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.resolution_deadline().unwrap() < now,
+        BettingError::NotInWindow
+    );
+    // update book pda
+    ctx.accounts.book_pda.dispute_resolution_result = Some(BetOutcome::Cancel);
+    ctx.accounts.book_pda.dispute.as_mut().unwrap().timed_out = true;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::{BETTOR_DISPUTE_WINDOW, COUNCIL_RESOLUTION_WINDOW, MAX_DISPUTE_ROUNDS},
+        state::{BetOutcome, BetType, Book, BookParams, Dispute, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_book_expire_dispute_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - COUNCIL_RESOLUTION_WINDOW - 30,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::BookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::Cancel));
+        assert!(book_state.is_dispute_timed_out());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_expire_dispute_err_deadline_not_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - COUNCIL_RESOLUTION_WINDOW + 30,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::BookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::Cancel));
+        assert!(book_state.is_dispute_timed_out());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_expire_dispute_err_already_resolved() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 1000000 * 10,
+            dispute_resolution_result: Some(BetOutcome::For),
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 30),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 10,
+                ends_at: chrono::Utc::now().timestamp() - COUNCIL_RESOLUTION_WINDOW - 30,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 10, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::BookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dispute_resolution_result, Some(BetOutcome::Cancel));
+        assert!(book_state.is_dispute_timed_out());
+    }
+}
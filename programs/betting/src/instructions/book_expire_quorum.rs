@@ -0,0 +1,376 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::QUORUM_DEADLINE,
+    error::BettingError,
+    state::{BetOutcome, Book, Game},
+};
+
+#[derive(Accounts)]
+pub struct BookExpireQuorumAccounts<'info> {
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+}
+
+// anyone can cancel the book once the oracles missed the deadline to reach the quorum, the bettors are refunded after
+// the dispute window and every oracle stake is returned
+pub fn book_expire_quorum(ctx: Context<BookExpireQuorumAccounts>) -> Result<()> {
+    // check if the book has not concluded yet
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none() && !ctx.accounts.book_pda.quorum_timed_out,
+        BettingError::NoAuthority
+    );
+    // must have passed the quorum deadline
+    let now = Clock::get()?.unix_timestamp;
+    let deadline = ctx
+        .accounts
+        .game_pda
+        .kickoff
+        .checked_add(QUORUM_DEADLINE)
+        .ok_or(BettingError::MathOverflow)?;
+    require!(deadline < now, BettingError::NotInWindow);
+    // update book pda
+    ctx.accounts.book_pda.aggregated_oracle_outcome = Some(BetOutcome::Cancel);
+    ctx.accounts.book_pda.concluded_at = Some(now);
+    ctx.accounts.book_pda.quorum_timed_out = true;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::QUORUM_DEADLINE,
+        state::{BetOutcome, BetType, Book, BookParams, Game, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_book_expire_quorum_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() - QUORUM_DEADLINE - 30,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 2,
+            wager_total: 1000000 * 20,
+            payout_for_total: 1000000 * 20,
+            payout_against_total: 1000000 * 20,
+            dealt_wager: 1000000 * 20,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - QUORUM_DEADLINE),
+            oracles_count: 2,
+            oracle_stakes: [1000000 * 50, 1000000 * 50, 0, 0, 0, 0],
+            revealed_oracles_count: 1,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+            aggregated_oracle_outcome: None,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireQuorumAccounts { game_pda, book_pda })
+            .args(crate::instruction::BookExpireQuorum {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.aggregated_oracle_outcome, Some(BetOutcome::Cancel));
+        assert_eq!(book_state.final_outcome(), Some(BetOutcome::Cancel));
+        assert!(book_state.concluded_at.is_some());
+        assert!(book_state.is_timed_out());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_expire_quorum_err_deadline_not_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() - QUORUM_DEADLINE + 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 2,
+            wager_total: 1000000 * 20,
+            payout_for_total: 1000000 * 20,
+            payout_against_total: 1000000 * 20,
+            dealt_wager: 1000000 * 20,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60 * 60),
+            oracles_count: 2,
+            oracle_stakes: [1000000 * 50, 1000000 * 50, 0, 0, 0, 0],
+            revealed_oracles_count: 1,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+            aggregated_oracle_outcome: None,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireQuorumAccounts { game_pda, book_pda })
+            .args(crate::instruction::BookExpireQuorum {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_expire_quorum_err_concluded() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 2;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() - QUORUM_DEADLINE - 30,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 2,
+            wager_total: 1000000 * 20,
+            payout_for_total: 1000000 * 20,
+            payout_against_total: 1000000 * 20,
+            dealt_wager: 1000000 * 20,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - QUORUM_DEADLINE),
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - QUORUM_DEADLINE),
+            oracles_count: 2,
+            oracle_stakes: [1000000 * 50, 1000000 * 50, 0, 0, 0, 0],
+            revealed_oracles_count: 1,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 2,
+            aggregated_oracle_outcome: Some(BetOutcome::For),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_data.len()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookExpireQuorumAccounts { game_pda, book_pda })
+            .args(crate::instruction::BookExpireQuorum {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -1,5 +1,3 @@
-use std::collections::{BTreeMap, VecDeque};
-
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -7,7 +5,11 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount},
 };
 
-use crate::state::{BetType, Book, Game, UserAccount};
+use crate::{
+    constants::MAX_IN_PLAY_BET_DELAY,
+    error::BettingError,
+    state::{BetType, Book, BookParams, Config, Game, InPlay, Quorum, UserAccount},
+};
 
 #[derive(Accounts)]
 #[instruction(bet_type:BetType)]
@@ -24,13 +26,43 @@ pub struct BookInitAccounts<'info> {
     pub book_ata: Account<'info, TokenAccount>,
     #[account(address=USDC)]
     pub usdc_mint: Account<'info, Mint>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn book_init(ctx: Context<BookInitAccounts>, bet_type: BetType) -> Result<()> {
+// books with a bet delay stay open in play, the others close at kickoff, books without params take the ones of the
+// config
+pub fn book_init(
+    ctx: Context<BookInitAccounts>,
+    bet_type: BetType,
+    in_play_bet_delay: Option<i64>,
+    quorum: Quorum,
+    params: Option<BookParams>,
+) -> Result<()> {
+    // check quorum
+    require!(quorum.is_valid(), BettingError::InvalidQuorum);
+    // check bet delay
+    if let Some(bet_delay) = in_play_bet_delay {
+        require!(
+            (0..=MAX_IN_PLAY_BET_DELAY).contains(&bet_delay),
+            BettingError::InvalidBetDelay
+        );
+    }
+    // check params
+    let params = match params {
+        Some(params) => {
+            require!(
+                params.is_valid(&ctx.accounts.config_pda),
+                BettingError::InvalidBookParams
+            );
+            params
+        }
+        None => BookParams::from_config(&ctx.accounts.config_pda),
+    };
     // update initiator user account
     ctx.accounts.initiator_user_account.books_initialized += 1;
     // update game pda
@@ -49,10 +81,26 @@ pub fn book_init(ctx: Context<BookInitAccounts>, bet_type: BetType) -> Result<()
         total_dispute_stake: 0,
         dispute_resolution_result: None,
         concluded_at: None,
-        oracles: BTreeMap::new(),
-        bets_for: VecDeque::new(),
-        bets_against: VecDeque::new(),
-        positions: BTreeMap::new(),
+        reveal_starts_at: None,
+        oracles_count: 0,
+        oracle_stakes: [0; 6],
+        revealed_oracles_count: 0,
+        quorum_timed_out: false,
+        quorum,
+        params,
+        dispute: None,
+        dispute_bonds: [0; 5],
+        disputes_count: 0,
+        in_play: in_play_bet_delay.map(|bet_delay| InPlay {
+            bet_delay,
+            suspended_until: 0,
+        }),
+        parlays_count: 0,
+        levels_for: Vec::new(),
+        levels_against: Vec::new(),
+        level_pages: Vec::new(),
+        delayed_levels: Vec::new(),
+        positions_count: 0,
         aggregated_oracle_outcome: None,
     });
 
@@ -61,7 +109,11 @@ pub fn book_init(ctx: Context<BookInitAccounts>, bet_type: BetType) -> Result<()
 
 #[cfg(test)]
 mod test {
-    use std::{collections::VecDeque, rc::Rc, str::FromStr};
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+        str::FromStr,
+    };
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -72,7 +124,12 @@ mod test {
         signature::Keypair, signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::state::{game::Game, user_account::UserAccount, BetType, Book};
+    use crate::{
+        constants::{BETTOR_PAYOUT_RATE, MAX_IN_PLAY_BET_DELAY, ORALCES_REWARD_SHARE},
+        state::{
+            game::Game, user_account::UserAccount, AggregationRule, BetType, Book, BookParams, Config, InPlay, Quorum,
+        },
+    };
 
     #[tokio::test]
     async fn test_book_init_success() {
@@ -121,6 +178,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -164,6 +225,216 @@ mod test {
             },
         );
 
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::BookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::BookInit {
+                bet_type,
+                in_play_bet_delay: None,
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+                params: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the user account should be updated
+        let initiator_user_account = banks_client.get_account(initiator_pda).await.unwrap().unwrap();
+        let initiator_user_account_state =
+            UserAccount::try_deserialize(&mut initiator_user_account.data.as_slice()).unwrap();
+        assert_eq!(initiator_user_account_state.books_initialized, 1);
+        // the game pda account should be updated
+        let game_account = banks_client.get_account(game_pda).await.unwrap().unwrap();
+        let game_state = Game::try_deserialize(&mut game_account.data.as_slice()).unwrap();
+        assert_eq!(game_state.books_count, 1);
+        // the book pda account should be created
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.game_id, game_id);
+        assert_eq!(book_state.initiator, initiator.pubkey());
+        assert_eq!(book_state.bets_count, 0);
+        assert_eq!(book_state.wager_total, 0);
+        assert_eq!(book_state.payout_for_total, 0);
+        assert_eq!(book_state.payout_against_total, 0);
+        assert_eq!(book_state.dealt_wager, 0);
+        assert_eq!(book_state.bet_type, bet_type);
+        assert_eq!(book_state.total_dispute_stake, 0);
+        assert!(book_state.dispute_resolution_result.is_none());
+        assert!(book_state.concluded_at.is_none());
+        assert!(book_state.in_play.is_none());
+        assert_eq!(book_state.oracles_count, 0);
+        assert_eq!(book_state.revealed_oracles_count, 0);
+        assert_eq!(
+            book_state.quorum,
+            Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Supermajority { threshold: 6000 },
+            }
+        );
+        assert_eq!(book_state.params, BookParams::default());
+        assert!(book_state.levels_for.is_empty());
+        assert!(book_state.levels_against.is_empty());
+        assert_eq!(book_state.positions_count, 0);
+        // the book ata account should be created
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.owner, book_pda);
+        assert_eq!(book_ata_account_state.mint, USDC);
+        assert_eq!(book_ata_account_state.amount, 0);
+    }
+
+    #[tokio::test]
+    async fn test_book_init_success_over_under() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let league_id: u32 = 1;
+        let home_team_id: u32 = 1;
+        let away_team_id: u32 = 1;
+        let kickoff: i64 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id,
+            home_team_id,
+            away_team_id,
+            kickoff,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::OverUnder { line: 5 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -182,12 +453,31 @@ mod test {
                 book_pda,
                 book_ata,
                 usdc_mint: USDC,
+                config_pda,
                 token_program: anchor_spl::token::ID,
                 associated_token_program: anchor_spl::associated_token::ID,
                 system_program: system_program::id(),
                 rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
             })
-            .args(crate::instruction::BookInit { bet_type })
+            .args(crate::instruction::BookInit {
+                bet_type,
+                in_play_bet_delay: Some(5),
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+                params: Some(BookParams {
+                    min_bet_amount: 1000000,
+                    min_oracle_stake: 1000000 * 10,
+                    min_bettor_dispute_stake: 1000000 * 100,
+                    oracle_update_window: 60 * 30,
+                    bettor_dispute_window: 60 * 60,
+                    bettor_payout_rate: BETTOR_PAYOUT_RATE,
+                    oracles_reward_share: ORALCES_REWARD_SHARE,
+                    initiator_reward_share: 1000,
+                }),
+            })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -221,10 +511,30 @@ mod test {
         assert_eq!(book_state.total_dispute_stake, 0);
         assert!(book_state.dispute_resolution_result.is_none());
         assert!(book_state.concluded_at.is_none());
-        assert!(book_state.oracles.is_empty());
-        assert!(book_state.bets_for.is_empty());
-        assert!(book_state.bets_against.is_empty());
-        assert!(book_state.positions.is_empty());
+        assert_eq!(
+            book_state.in_play,
+            Some(InPlay {
+                bet_delay: 5,
+                suspended_until: 0
+            })
+        );
+        assert_eq!(book_state.oracles_count, 0);
+        assert_eq!(
+            book_state.params,
+            BookParams {
+                min_bet_amount: 1000000,
+                min_oracle_stake: 1000000 * 10,
+                min_bettor_dispute_stake: 1000000 * 100,
+                oracle_update_window: 60 * 30,
+                bettor_dispute_window: 60 * 60,
+                bettor_payout_rate: BETTOR_PAYOUT_RATE,
+                oracles_reward_share: ORALCES_REWARD_SHARE,
+                initiator_reward_share: 1000,
+            }
+        );
+        assert!(book_state.levels_for.is_empty());
+        assert!(book_state.levels_against.is_empty());
+        assert_eq!(book_state.positions_count, 0);
         // the book ata account should be created
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -232,4 +542,472 @@ mod test {
         assert_eq!(book_ata_account_state.mint, USDC);
         assert_eq!(book_ata_account_state.amount, 0);
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6025)")]
+    async fn test_book_init_err_invalid_quorum() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let league_id: u32 = 1;
+        let home_team_id: u32 = 1;
+        let away_team_id: u32 = 1;
+        let kickoff: i64 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id,
+            home_team_id,
+            away_team_id,
+            kickoff,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::BookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::BookInit {
+                bet_type,
+                in_play_bet_delay: None,
+                quorum: Quorum {
+                    min_oracles: 1,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+                params: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6031)")]
+    async fn test_book_init_err_invalid_params() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let league_id: u32 = 1;
+        let home_team_id: u32 = 1;
+        let away_team_id: u32 = 1;
+        let kickoff: i64 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id,
+            home_team_id,
+            away_team_id,
+            kickoff,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::BookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::BookInit {
+                bet_type,
+                in_play_bet_delay: None,
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+                params: Some(BookParams {
+                    min_bet_amount: 1000000,
+                    min_oracle_stake: 1000000 * 10,
+                    min_bettor_dispute_stake: 1000000 * 100,
+                    oracle_update_window: 60 * 30,
+                    bettor_dispute_window: 60 * 10,
+                    bettor_payout_rate: BETTOR_PAYOUT_RATE,
+                    oracles_reward_share: ORALCES_REWARD_SHARE,
+                    initiator_reward_share: 1000,
+                }),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6034)")]
+    async fn test_book_init_err_invalid_bet_delay() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let league_id: u32 = 1;
+        let home_team_id: u32 = 1;
+        let away_team_id: u32 = 1;
+        let kickoff: i64 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id,
+            home_team_id,
+            away_team_id,
+            kickoff,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::BookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::BookInit {
+                bet_type,
+                in_play_bet_delay: Some(MAX_IN_PLAY_BET_DELAY + 1),
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+                params: None,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
 }
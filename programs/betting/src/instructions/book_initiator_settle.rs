@@ -5,7 +5,7 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::{BETTOR_DISPUTE_WINDOW, BETTOR_PAYOUT_RATE, INITIATOR_REWARD_SHARE, OPERATOR_TOKEN_ACCOUNT},
+    constants::OPERATOR_TOKEN_ACCOUNT,
     error::BettingError,
     state::{Book, UserAccount},
 };
@@ -39,24 +39,32 @@ pub fn book_initiator_settle(ctx: Context<BookInitiatorSettleAccounts>) -> Resul
     // must have passed the dispute window
     let now = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.book_pda.concluded_at.unwrap() + BETTOR_DISPUTE_WINDOW < now,
+        ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.bettor_dispute_window < now,
         BettingError::NotInWindow
     );
+    // must have passed the window of the latest dispute round
+    require!(!ctx.accounts.book_pda.is_in_dispute(now), BettingError::NotInWindow);
     // bettors should all be settled
     require!(
-        ctx.accounts.book_pda.bets_for.is_empty()
-            && ctx.accounts.book_pda.bets_against.is_empty()
-            && ctx.accounts.book_pda.positions.is_empty(),
+        ctx.accounts.book_pda.levels_for.is_empty()
+            && ctx.accounts.book_pda.levels_against.is_empty()
+            && ctx.accounts.book_pda.positions_count == 0,
         BettingError::BookNotSettled
     );
     // oracles should all be settled
-    require!(ctx.accounts.book_pda.oracles.is_empty(), BettingError::BookNotSettled);
+    require!(ctx.accounts.book_pda.oracles_count == 0, BettingError::BookNotSettled);
+    // disputers should all be settled
+    require!(ctx.accounts.book_pda.disputes_count == 0, BettingError::BookNotSettled);
+
+    // must have an outcome
+    let final_outcome = ctx.accounts.book_pda.final_outcome();
+    require!(final_outcome.is_some(), BettingError::NoResultYet);
 
     // update user account
     ctx.accounts.initiator_user_account.books_initialized -= 1;
     // pay reward
-    let total_profit = ctx.accounts.book_pda.dealt_wager * (10000 - BETTOR_PAYOUT_RATE) / 10000;
-    let initiator_reward = total_profit * INITIATOR_REWARD_SHARE / 10000;
+    let total_profit = ctx.accounts.book_pda.total_profit(final_outcome.unwrap());
+    let initiator_reward = total_profit * ctx.accounts.book_pda.params.initiator_reward_share / 10000;
     let initiator_reward_transfer_cpi_context = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         anchor_spl::token::Transfer {
@@ -95,10 +103,7 @@ pub fn book_initiator_settle(ctx: Context<BookInitiatorSettleAccounts>) -> Resul
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::VecDeque, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -111,7 +116,7 @@ mod test {
 
     use crate::{
         constants::{BETTOR_DISPUTE_WINDOW, OPERATOR_PUBKEY, OPERATOR_TOKEN_ACCOUNT},
-        state::{Bet, BetOutcome, BetType, Book, Oracle, UserAccount},
+        state::{BetOutcome, BetType, Book, BookParams, Quorum, UserAccount},
     };
 
     #[tokio::test]
@@ -191,10 +196,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -376,10 +394,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -561,10 +592,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -746,10 +790,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW + 60),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -931,14 +988,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::from([Bet {
-                id: 0,
-                bettor: Pubkey::new_unique(),
-                wager: 1000000 * 100,
-            }]),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: vec![2000],
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -1120,16 +1186,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::from([(
-                Pubkey::new_unique(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: None,
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -1232,4 +1305,201 @@ mod test {
             .unwrap();
         assert_eq!(op_token_account_state.amount, 1000000 * 100 + 1000000 * 1998);
     }
+
+    #[tokio::test]
+    async fn test_book_initiator_settle_success_with_half_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 1,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let initiator_ata = anchor_spl::associated_token::get_associated_token_address(&initiator.pubkey(), &USDC);
+        let initiator_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: initiator.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut initiator_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(initiator_ata_state, &mut initiator_ata_data).unwrap();
+        program_test.add_account(
+            initiator_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(initiator_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let bet_type = BetType::AsianHandicap { line: -1 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: initiator.pubkey(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 1000,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::HalfAgainst),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 2000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let operator_token_account_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: OPERATOR_PUBKEY,
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut operator_token_account_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(
+            operator_token_account_state,
+            &mut operator_token_account_data,
+        )
+        .unwrap();
+        program_test.add_account(
+            OPERATOR_TOKEN_ACCOUNT,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(operator_token_account_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookInitiatorSettleAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                initiator_token_account: initiator_ata,
+                book_pda,
+                book_ata,
+                operator_token_account: OPERATOR_TOKEN_ACCOUNT,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::BookInitiatorSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the initiator user account should be updated
+        let initiator_user_account = banks_client.get_account(initiator_pda).await.unwrap().unwrap();
+        let initiator_user_account_state =
+            UserAccount::try_deserialize(&mut initiator_user_account.data.as_slice()).unwrap();
+        assert_eq!(initiator_user_account_state.books_initialized, 0);
+        // the reward should be transferred to the initiator token account
+        let initiator_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(initiator_ata).await.unwrap();
+        assert_eq!(initiator_token_account_state.amount, 1000000 * 100 + 1000000);
+        // all the usdc in book ata should be transferred out
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 0);
+        // the remaining usdc should all be transferred to the operator token account
+        let op_token_account_state: anchor_spl::token::spl_token::state::Account = banks_client
+            .get_packed_account_data(OPERATOR_TOKEN_ACCOUNT)
+            .await
+            .unwrap();
+        assert_eq!(op_token_account_state.amount, 1000000 * 100 + 1000000 * 1999);
+    }
 }
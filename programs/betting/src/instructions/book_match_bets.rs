@@ -1,41 +1,66 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::ORACLE_UPDATE_WINDOW, error::BettingError, state::Book};
+use crate::{
+    error::BettingError,
+    events::BetsMatched,
+    state::{Book, BookPage, Game, Position},
+};
 
 #[derive(Accounts)]
 pub struct BookMatchBetsAccounts<'info> {
     #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
     pub book_pda: Account<'info, Book>,
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
 }
 
-pub fn book_match_bets(ctx: Context<BookMatchBetsAccounts>) -> Result<()> {
+// the pages of the price levels and the positions of their bettors are passed as remaining accounts
+pub fn book_match_bets(ctx: Context<BookMatchBetsAccounts>, max_matches: u32) -> Result<()> {
     // check window
     let now = Clock::get()?.unix_timestamp;
     require!(
         ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
         BettingError::NotInWindow
     );
-    // match bets
-    while ctx.accounts.book_pda.match_bets() {}
+    // pre-match books close at kickoff, in-play books stop matching while suspended
+    if ctx.accounts.book_pda.in_play.is_none() {
+        require!(now < ctx.accounts.game_pda.kickoff, BettingError::NotInWindow);
+    }
+    require!(!ctx.accounts.book_pda.is_suspended(now), BettingError::MarketSuspended);
+    // match bets, a deep book is matched across several transactions
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let position_loaders = Position::loaders(ctx.remaining_accounts)?;
+    let mut positions = Position::load_positions(&book_key, &position_loaders)?;
+    let (matches_count, has_more) = ctx
+        .accounts
+        .book_pda
+        .match_bets(&mut pages, &mut positions, max_matches, now)?;
+
+    emit!(BetsMatched {
+        book: ctx.accounts.book_pda.key(),
+        matches_count,
+        has_more,
+    });
+
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::BTreeMap, rc::Rc};
 
     use anchor_client::RequestBuilder;
-    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
-        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+        account::Account, instruction::AccountMeta, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer,
+        transaction::Transaction,
     };
 
-    use crate::state::{BetDirection, BetType, Book, Position};
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, Game, InPlay, Position, Quorum};
 
     #[tokio::test]
     async fn test_book_match_bets_success() {
@@ -43,6 +68,30 @@ mod test {
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
         let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -65,28 +114,139 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let bettor_key = Pubkey::new_unique();
-        book_pda_state.positions.insert(
-            bettor_key,
-            Position {
-                active_bets_count: 0,
-                bets_count: 0,
-                payout_for: 0,
-                payout_against: 0,
-                wager: 0,
-                dealt_wager: 0,
-                dispute_stake: 0,
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
             },
         );
-        book_pda_state.new_bet(1200, 1000000 * 100, bettor_key, BetDirection::For);
-        book_pda_state.new_bet(1200, 1000000 * 100, bettor_key, BetDirection::For);
-        book_pda_state.new_bet(6000, 1000000 * 200, bettor_key, BetDirection::Against);
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -109,8 +269,11 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .accounts(crate::accounts::BookMatchBetsAccounts { book_pda })
-            .args(crate::instruction::BookMatchBets)
+            .accounts(crate::accounts::BookMatchBetsAccounts { book_pda, game_pda })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookMatchBets { max_matches: 10 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
@@ -124,15 +287,257 @@ mod test {
         assert_eq!(book_state.payout_for_total, 1000000 * 240);
         assert_eq!(book_state.payout_against_total, 1000000 * 240);
         assert_eq!(book_state.dealt_wager, 1000000 * 240);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.bets_against.len(), 1);
-        assert_eq!(book_state.bets_against[0].wager, 1000000 * 160);
-        assert_eq!(book_state.positions[&bettor_key].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor_key].bets_count, 3);
-        assert_eq!(book_state.positions[&bettor_key].payout_for, 1000000 * 240);
-        assert_eq!(book_state.positions[&bettor_key].payout_against, 1000000 * 240);
-        assert_eq!(book_state.positions[&bettor_key].wager, 1000000 * 400);
-        assert_eq!(book_state.positions[&bettor_key].dealt_wager, 1000000 * 240);
+        assert!(book_state.levels_for.is_empty());
+        assert_eq!(book_state.levels_against, vec![6000]);
+        let book_page2_account = banks_client.get_account(book_page2_pda).await.unwrap().unwrap();
+        let book_page2_state = BookPage::try_deserialize(&mut book_page2_account.data.as_slice()).unwrap();
+        assert_eq!(book_page2_state.bets().len(), 1);
+        assert_eq!(book_page2_state.bets()[0].wager, 1000000 * 160);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 3);
+        assert_eq!(bettor_position_pda_state.payout_for, 1000000 * 240);
+        assert_eq!(bettor_position_pda_state.payout_against, 1000000 * 240);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 400);
+        assert_eq!(bettor_position_pda_state.dealt_wager, 1000000 * 240);
+    }
+
+    #[tokio::test]
+    async fn test_book_match_bets_success_in_play_bet_delay() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 60,
+                suspended_until: 0,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_key = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                chrono::Utc::now().timestamp(),
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookMatchBetsAccounts { book_pda, game_pda })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookMatchBets { max_matches: 10 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the bet against is still in its bet delay and should not be matched
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.dealt_wager, 0);
+        let book_page1_account = banks_client.get_account(book_page1_pda).await.unwrap().unwrap();
+        let book_page1_state = BookPage::try_deserialize(&mut book_page1_account.data.as_slice()).unwrap();
+        assert_eq!(book_page1_state.bets().len(), 2);
+        let book_page2_account = banks_client.get_account(book_page2_pda).await.unwrap().unwrap();
+        let book_page2_state = BookPage::try_deserialize(&mut book_page2_account.data.as_slice()).unwrap();
+        assert_eq!(book_page2_state.bets().len(), 1);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 3);
     }
 
     #[tokio::test]
@@ -142,6 +547,30 @@ mod test {
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
         let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let bet_type = BetType::One { handicap: 0 };
         let (book_pda, _) = Pubkey::find_program_address(
             &[
@@ -164,28 +593,139 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let bettor_key = Pubkey::new_unique();
-        book_pda_state.positions.insert(
-            bettor_key,
-            Position {
-                active_bets_count: 0,
-                bets_count: 0,
-                payout_for: 0,
-                payout_against: 0,
-                wager: 0,
-                dealt_wager: 0,
-                dispute_stake: 0,
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            active_bets_count: 0,
+            bets_count: 0,
+            payout_for: 0,
+            payout_against: 0,
+            wager: 0,
+            dealt_wager: 0,
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
             },
         );
-        book_pda_state.new_bet(1200, 1000000 * 100, bettor_key, BetDirection::For);
-        book_pda_state.new_bet(1200, 1000000 * 100, bettor_key, BetDirection::For);
-        book_pda_state.new_bet(6000, 1000000 * 200, bettor_key, BetDirection::Against);
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         program_test.add_account(
@@ -208,8 +748,11 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .accounts(crate::accounts::BookMatchBetsAccounts { book_pda })
-            .args(crate::instruction::BookMatchBets)
+            .accounts(crate::accounts::BookMatchBetsAccounts { book_pda, game_pda })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookMatchBets { max_matches: 10 })
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
@@ -223,14 +766,20 @@ mod test {
         assert_eq!(book_state.payout_for_total, 1000000 * 240);
         assert_eq!(book_state.payout_against_total, 1000000 * 240);
         assert_eq!(book_state.dealt_wager, 1000000 * 240);
-        assert!(book_state.bets_for.is_empty());
-        assert_eq!(book_state.bets_against.len(), 1);
-        assert_eq!(book_state.bets_against[0].wager, 1000000 * 160);
-        assert_eq!(book_state.positions[&bettor_key].active_bets_count, 1);
-        assert_eq!(book_state.positions[&bettor_key].bets_count, 3);
-        assert_eq!(book_state.positions[&bettor_key].payout_for, 1000000 * 240);
-        assert_eq!(book_state.positions[&bettor_key].payout_against, 1000000 * 240);
-        assert_eq!(book_state.positions[&bettor_key].wager, 1000000 * 400);
-        assert_eq!(book_state.positions[&bettor_key].dealt_wager, 1000000 * 240);
+        assert!(book_state.levels_for.is_empty());
+        assert_eq!(book_state.levels_against, vec![6000]);
+        let book_page2_account = banks_client.get_account(book_page2_pda).await.unwrap().unwrap();
+        let book_page2_state = BookPage::try_deserialize(&mut book_page2_account.data.as_slice()).unwrap();
+        assert_eq!(book_page2_state.bets().len(), 1);
+        assert_eq!(book_page2_state.bets()[0].wager, 1000000 * 160);
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.bets_count, 3);
+        assert_eq!(bettor_position_pda_state.payout_for, 1000000 * 240);
+        assert_eq!(bettor_position_pda_state.payout_against, 1000000 * 240);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 400);
+        assert_eq!(bettor_position_pda_state.dealt_wager, 1000000 * 240);
     }
 }
@@ -4,7 +4,10 @@ use anchor_spl::{
     token::{Token, TokenAccount},
 };
 
-use crate::{constants::ORACLE_UPDATE_WINDOW, error::BettingError, state::Book};
+use crate::{
+    error::BettingError,
+    state::{Book, Oracle},
+};
 
 #[derive(Accounts)]
 pub struct BookOracleAddStakeAccounts<'info> {
@@ -15,15 +18,16 @@ pub struct BookOracleAddStakeAccounts<'info> {
     pub book_pda: Account<'info, Book>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
     pub token_program: Program<'info, Token>,
 }
 
 pub fn book_oracle_add_stake(ctx: Context<BookOracleAddStakeAccounts>, stake: u64) -> Result<()> {
-    // check window
+    // check window, stake can only be added while the outcome of the oracle is still hidden
     let now = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+        !ctx.accounts.oracle_pda.revealed && !ctx.accounts.book_pda.is_oracle_stake_locked(now),
         BettingError::NotInWindow
     );
     // transfer stake
@@ -37,26 +41,19 @@ pub fn book_oracle_add_stake(ctx: Context<BookOracleAddStakeAccounts>, stake: u6
     );
     anchor_spl::token::transfer(stake_transfer_cpi_context, stake)?;
 
+    // update oracle pda
+    ctx.accounts.oracle_pda.stake += stake;
     // update book pda
+    let outcome = ctx.accounts.oracle_pda.outcome;
     ctx.accounts.book_pda.total_oracle_stake += stake;
-    match ctx.accounts.book_pda.oracles.get_mut(ctx.accounts.oracle.key) {
-        Some(o) => {
-            o.stake += stake;
-        }
-        None => {
-            return err!(BettingError::UserDidNotOptIn);
-        }
-    };
+    ctx.accounts.book_pda.add_oracle_stake(outcome, stake);
 
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::rc::Rc;
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -67,7 +64,7 @@ mod test {
         signature::Keypair, signer::Signer, transaction::Transaction,
     };
 
-    use crate::state::{BetType, Book, Oracle};
+    use crate::state::{BetOutcome, BetType, Book, BookParams, Oracle, Quorum};
 
     #[tokio::test]
     async fn test_book_oracle_add_stake_success() {
@@ -126,17 +123,46 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
-        book_pda_state.oracles.insert(
-            oracle.pubkey(),
-            Oracle {
-                stake: 1000000 * 100,
-                outcome: None,
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
             },
         );
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -187,6 +213,7 @@ mod test {
                 oracle: oracle.pubkey(),
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
@@ -208,8 +235,11 @@ mod test {
         // book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 120);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 120);
         assert_eq!(book_state.total_oracle_stake, 1000000 * 120);
+        assert_eq!(book_state.oracle_stakes, [1000000 * 120, 0, 0, 0, 0, 0]);
         // stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -217,7 +247,7 @@ mod test {
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6004)")]
+    #[should_panic(expected = "Custom(3012)")]
     async fn test_book_oracle_add_stake_err_oracle_did_not_opt_in() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
@@ -274,10 +304,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -313,6 +356,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -328,6 +376,7 @@ mod test {
                 oracle: oracle.pubkey(),
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
@@ -349,7 +398,9 @@ mod test {
         // book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 120);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 120);
         // stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -414,17 +465,46 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
-        book_pda_state.oracles.insert(
-            oracle.pubkey(),
-            Oracle {
-                stake: 1000000 * 100,
-                outcome: None,
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
             },
         );
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -475,6 +555,7 @@ mod test {
                 oracle: oracle.pubkey(),
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
             })
@@ -496,10 +577,340 @@ mod test {
         // book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 120);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 120);
         // stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_token_account_state.amount, 1000000 * 120);
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_add_stake_err_after_reveal() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 1,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleAddStakeAccounts {
+                oracle: oracle.pubkey(),
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::BookOracleAddStake { stake: 1000000 * 20 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_add_stake_err_after_conclusion() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - 60),
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 120),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some([1; 32]),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleAddStakeAccounts {
+                oracle: oracle.pubkey(),
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::BookOracleAddStake { stake: 1000000 * 20 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
 }
@@ -0,0 +1,596 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ORACLE_COMMIT_WINDOW, ORACLE_REVEAL_WINDOW},
+    error::BettingError,
+    state::{Book, Oracle},
+};
+
+#[derive(Accounts)]
+pub struct BookOracleCommitOutcomeAccounts<'info> {
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
+}
+
+// the commitment is the hash of the keys of the book and the oracle, the outcome and a salt, see Oracle::commitment,
+// it can be replaced until the commit phase ends
+pub fn book_oracle_commit_outcome(ctx: Context<BookOracleCommitOutcomeAccounts>, commitment: [u8; 32]) -> Result<()> {
+    // check oracle update window, a book cancelled on the quorum deadline takes no more outcomes
+    let now = Clock::get()?.unix_timestamp;
+    require!(!ctx.accounts.book_pda.quorum_timed_out, BettingError::NotInWindow);
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // check commit window, the first commit starts it, another round starts if the reveal phase ended without a
+    // quorum, the outcomes revealed so far stand
+    match ctx.accounts.book_pda.reveal_starts_at {
+        Some(t) if t > now => {}
+        Some(t) if t + ORACLE_REVEAL_WINDOW <= now && ctx.accounts.book_pda.aggregated_oracle_outcome.is_none() => {
+            ctx.accounts.book_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+        Some(_) => {
+            return err!(BettingError::NotInWindow);
+        }
+        None => {
+            ctx.accounts.book_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+    }
+    // an outcome can only be revealed once
+    require!(!ctx.accounts.oracle_pda.revealed, BettingError::NotInWindow);
+    // update oracle pda
+    ctx.accounts.oracle_pda.commitment = Some(commitment);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{BetOutcome, BetType, Book, BookParams, Oracle, Quorum};
+
+    #[tokio::test]
+    async fn test_book_oracle_commit_outcome_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleCommitOutcome {
+                commitment: Oracle::commitment(&book_pda, &oracle.pubkey(), Some(BetOutcome::For), &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the commit phase should be started and the outcome should stay hidden
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(
+            book_oracle_pda_state.commitment,
+            Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32]
+            ))
+        );
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert!(!book_oracle_pda_state.revealed);
+        assert!(book_state.reveal_starts_at.is_some());
+        assert!(book_state.concluded_at.is_none());
+        assert_eq!(book_state.oracle_stakes, [1000000 * 100, 0, 0, 0, 0, 0]);
+        assert!(book_state.aggregated_oracle_outcome.is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_commit_outcome_err_commit_phase_ended() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleCommitOutcome {
+                commitment: Oracle::commitment(&book_pda, &oracle.pubkey(), Some(BetOutcome::For), &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_commit_outcome_restart() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(0),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleCommitOutcome {
+                commitment: Oracle::commitment(&book_pda, &oracle.pubkey(), Some(BetOutcome::For), &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the reveal phase ended without a quorum, so another commit phase should be started
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(
+            book_oracle_pda_state.commitment,
+            Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32]
+            ))
+        );
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert!(!book_oracle_pda_state.revealed);
+        assert!(book_state.reveal_starts_at.unwrap() > chrono::Utc::now().timestamp());
+        assert!(book_state.concluded_at.is_none());
+        assert_eq!(book_state.oracle_stakes, [1000000 * 100, 0, 0, 0, 0, 0]);
+        assert!(book_state.aggregated_oracle_outcome.is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_commit_outcome_err_concluded() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - 60),
+            reveal_starts_at: Some(0),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::For),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleCommitOutcome {
+                commitment: Oracle::commitment(&book_pda, &oracle.pubkey(), Some(BetOutcome::For), &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -5,7 +5,6 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::{MIN_ORACLE_STAKE, ORACLE_UPDATE_WINDOW, RENT_PER_ORACLE},
     error::BettingError,
     state::{user_account::UserAccount, Book, Oracle},
 };
@@ -22,18 +21,22 @@ pub struct BookOracleOptInAccounts<'info> {
     pub book_pda: Account<'info, Book>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
+    #[account(init,payer=oracle,space=Oracle::INIT_SPACE,seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn book_oracle_opt_in(ctx: Context<BookOracleOptInAccounts>, stake: u64) -> Result<()> {
     // check stake
-    require!(stake >= MIN_ORACLE_STAKE, BettingError::MinTokenAmountNotMet);
+    require!(
+        stake >= ctx.accounts.book_pda.params.min_oracle_stake,
+        BettingError::MinTokenAmountNotMet
+    );
     // check window
     let now = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.book_pda.concluded_at.is_none()
-            || ctx.accounts.book_pda.concluded_at.unwrap() + ORACLE_UPDATE_WINDOW > now,
+        !ctx.accounts.book_pda.is_oracle_stake_locked(now),
         BettingError::NotInWindow
     );
     // update user account
@@ -83,42 +86,23 @@ pub fn book_oracle_opt_in(ctx: Context<BookOracleOptInAccounts>, stake: u64) ->
         },
     );
     anchor_spl::token::transfer(stake_transfer_cpi_context, stake)?;
+    // init oracle pda
+    ctx.accounts.oracle_pda.book = ctx.accounts.book_pda.key();
+    ctx.accounts.oracle_pda.oracle = ctx.accounts.oracle.key();
+    ctx.accounts.oracle_pda.stake = stake;
+    ctx.accounts.oracle_pda.outcome = None;
+    ctx.accounts.oracle_pda.commitment = None;
+    ctx.accounts.oracle_pda.revealed = false;
     // update book pda
-    match ctx.accounts.book_pda.oracles.get(ctx.accounts.oracle.key) {
-        Some(_) => {
-            return err!(BettingError::UserAlreadyOptIn);
-        }
-        None => {
-            ctx.accounts
-                .book_pda
-                .oracles
-                .insert(ctx.accounts.oracle.key(), Oracle { stake, outcome: None });
-            ctx.accounts.book_pda.total_oracle_stake += stake;
-
-            // realloc
-            let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
-            let book_pda_space = ctx.accounts.book_pda.current_space();
-            book_pda_account_info.realloc(book_pda_space, false)?;
-            // rent
-            let transfer_cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer {
-                    from: ctx.accounts.oracle.to_account_info(),
-                    to: ctx.accounts.book_pda.to_account_info(),
-                },
-            );
-            system_program::transfer(transfer_cpi_context, RENT_PER_ORACLE)?;
-        }
-    }
+    ctx.accounts.book_pda.oracles_count += 1;
+    ctx.accounts.book_pda.total_oracle_stake += stake;
+    ctx.accounts.book_pda.add_oracle_stake(None, stake);
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::VecDeque, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -129,7 +113,7 @@ mod test {
         signature::Keypair, signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::state::{user_account::UserAccount, BetType, Book, Oracle};
+    use crate::state::{user_account::UserAccount, BetType, Book, BookParams, Oracle, Quorum};
 
     #[tokio::test]
     async fn test_book_oracle_opt_in_success() {
@@ -208,10 +192,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -247,6 +244,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -263,6 +265,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -289,10 +292,14 @@ mod test {
         // the book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.contains_key(&oracle.pubkey()));
-        assert!(book_state.oracles[&oracle.pubkey()].outcome.is_none());
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 20);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.oracle, oracle.pubkey());
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 20);
         assert_eq!(book_state.total_oracle_stake, 1000000 * 20);
+        assert_eq!(book_state.oracles_count, 1);
+        assert_eq!(book_state.oracle_stakes, [1000000 * 20, 0, 0, 0, 0, 0]);
         // the stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -377,10 +384,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -416,6 +436,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -432,6 +457,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -458,9 +484,11 @@ mod test {
         // the book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.contains_key(&oracle.pubkey()));
-        assert!(book_state.oracles[&oracle.pubkey()].outcome.is_none());
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 20);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.oracle, oracle.pubkey());
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 20);
         // the stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -525,19 +553,25 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 10, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
-        book_pda_state.oracles.insert(
-            oracle.pubkey(),
-            Oracle {
-                stake: 1000000 * 10,
-                outcome: None,
-            },
-        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -590,6 +624,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -606,6 +645,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -632,9 +672,11 @@ mod test {
         // the book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.contains_key(&oracle.pubkey()));
-        assert!(book_state.oracles[&oracle.pubkey()].outcome.is_none());
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 20);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.oracle, oracle.pubkey());
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 20);
         // the stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -719,10 +761,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -758,6 +813,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -774,6 +834,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -800,12 +861,182 @@ mod test {
         // the book pda should be updated
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.contains_key(&oracle.pubkey()));
-        assert!(book_state.oracles[&oracle.pubkey()].outcome.is_none());
-        assert_eq!(book_state.oracles[&oracle.pubkey()].stake, 1000000 * 20);
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.oracle, oracle.pubkey());
+        assert!(book_oracle_pda_state.outcome.is_none());
+        assert_eq!(book_oracle_pda_state.stake, 1000000 * 20);
         // the stake should be transferred to the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
         assert_eq!(book_token_account_state.amount, 1000000 * 20);
     }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_opt_in_err_after_conclusion() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - 60),
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 120),
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleOptInAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleOptIn { stake: 1000000 * 20 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
 }
@@ -0,0 +1,868 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::ORACLE_REVEAL_WINDOW,
+    error::BettingError,
+    state::{BetOutcome, Book, Oracle},
+};
+
+#[derive(Accounts)]
+pub struct BookOracleRevealOutcomeAccounts<'info> {
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
+}
+
+pub fn book_oracle_reveal_outcome(
+    ctx: Context<BookOracleRevealOutcomeAccounts>,
+    bet_outcome: Option<BetOutcome>,
+    salt: [u8; 32],
+) -> Result<()> {
+    // check reveal window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        matches!(ctx.accounts.book_pda.reveal_starts_at, Some(t) if t <= now && t + ORACLE_REVEAL_WINDOW > now),
+        BettingError::NotInWindow
+    );
+    // the outcome can no longer change once the oracle update window passed or while it is disputed
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    require!(ctx.accounts.book_pda.dispute.is_none(), BettingError::NotInWindow);
+    require!(!ctx.accounts.book_pda.quorum_timed_out, BettingError::NotInWindow);
+    // check commitment
+    let commitment = Oracle::commitment(
+        &ctx.accounts.book_pda.key(),
+        ctx.accounts.oracle.key,
+        bet_outcome,
+        &salt,
+    );
+    require!(
+        ctx.accounts.oracle_pda.commitment == Some(commitment),
+        BettingError::InvalidCommitment
+    );
+    if let Some(outcome) = bet_outcome {
+        require!(
+            ctx.accounts.book_pda.bet_type.is_valid_outcome(outcome),
+            BettingError::InvalidOutcome
+        );
+    }
+    // update oracle pda
+    ctx.accounts.oracle_pda.outcome = bet_outcome;
+    ctx.accounts.oracle_pda.commitment = None;
+    ctx.accounts.oracle_pda.revealed = true;
+    ctx.accounts.book_pda.revealed_oracles_count += 1;
+    // move the stake of the oracle to its revealed outcome
+    let stake = ctx.accounts.oracle_pda.stake;
+    ctx.accounts.book_pda.remove_oracle_stake(None, stake);
+    ctx.accounts.book_pda.add_oracle_stake(bet_outcome, stake);
+    // update book pda
+    let aggregated_outcome = ctx.accounts.book_pda.aggregated_outcome();
+    if ctx.accounts.book_pda.aggregated_oracle_outcome != aggregated_outcome {
+        ctx.accounts.book_pda.aggregated_oracle_outcome = aggregated_outcome;
+        if aggregated_outcome.is_some() {
+            ctx.accounts.book_pda.concluded_at = Some(now);
+        } else {
+            ctx.accounts.book_pda.concluded_at = None;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{BetOutcome, BetType, Book, BookParams, Oracle, Quorum};
+
+    #[tokio::test]
+    async fn test_book_oracle_reveal_outcome_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::For),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.outcome, Some(BetOutcome::For));
+        assert!(book_oracle_pda_state.commitment.is_none());
+        assert!(book_oracle_pda_state.revealed);
+        assert!(book_state.concluded_at.is_some());
+        assert_eq!(book_state.oracle_stakes, [0, 1000000 * 100, 0, 0, 0, 0]);
+        assert_eq!(book_state.aggregated_oracle_outcome, Some(BetOutcome::For));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_reveal_outcome_err_window_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(0),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::For),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.outcome, Some(BetOutcome::For));
+        assert!(book_state.concluded_at.is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(3012)")]
+    async fn test_book_oracle_reveal_outcome_err_oracle() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::For),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        let book_oracle_pda_account = banks_client.get_account(book_oracle_pda).await.unwrap().unwrap();
+        let book_oracle_pda_state = Oracle::try_deserialize(&mut book_oracle_pda_account.data.as_slice()).unwrap();
+        assert_eq!(book_oracle_pda_state.outcome, Some(BetOutcome::For));
+        assert!(book_state.concluded_at.is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6024)")]
+    async fn test_book_oracle_reveal_outcome_err_invalid_commitment() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::Against),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6024)")]
+    async fn test_book_oracle_reveal_outcome_err_copied_commitment() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &Pubkey::new_unique(),
+                Some(BetOutcome::For),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::For),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_reveal_outcome_err_update_window_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(chrono::Utc::now().timestamp() - 60 * 11),
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60 * 9),
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::For),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleRevealOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .args(crate::instruction::BookOracleRevealOutcome {
+                bet_outcome: Some(BetOutcome::For),
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -5,9 +5,8 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::{BETTOR_DISPUTE_WINDOW, BETTOR_PAYOUT_RATE, ORALCES_REWARD_SHARE, RENT_PER_ORACLE},
     error::BettingError,
-    state::{Book, UserAccount},
+    state::{Book, Oracle, UserAccount},
 };
 
 #[derive(Accounts)]
@@ -23,6 +22,8 @@ pub struct BookOracleSettleAccounts<'info> {
     pub book_pda: Account<'info, Book>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
+    #[account(mut,close=oracle,seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
@@ -33,16 +34,21 @@ pub fn book_oracle_settle(ctx: Context<BookOracleSettleAccounts>) -> Result<()>
     // must have passed the dispute window
     let now = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.book_pda.concluded_at.unwrap() + BETTOR_DISPUTE_WINDOW < now,
+        ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.bettor_dispute_window < now,
         BettingError::NotInWindow
     );
+    // must have passed the window of the latest dispute round
+    require!(!ctx.accounts.book_pda.is_in_dispute(now), BettingError::NotInWindow);
     // bettors should all be settled
     require!(
-        ctx.accounts.book_pda.bets_for.is_empty()
-            && ctx.accounts.book_pda.bets_against.is_empty()
-            && ctx.accounts.book_pda.positions.is_empty(),
+        ctx.accounts.book_pda.levels_for.is_empty()
+            && ctx.accounts.book_pda.levels_against.is_empty()
+            && ctx.accounts.book_pda.positions_count == 0,
         BettingError::BookNotSettled
     );
+    // must have an outcome
+    let final_outcome = ctx.accounts.book_pda.final_outcome();
+    require!(final_outcome.is_some(), BettingError::NoResultYet);
     // update user account
     if let Ok(index) = ctx
         .accounts
@@ -53,66 +59,61 @@ pub fn book_oracle_settle(ctx: Context<BookOracleSettleAccounts>) -> Result<()>
         ctx.accounts.oracle_user_account.books_oracled.remove(index);
     };
     // update book pda
-    match ctx.accounts.book_pda.oracles.remove(ctx.accounts.oracle.key) {
-        Some(o) => {
-            let final_outcome = if ctx.accounts.book_pda.total_dispute_stake > 0 {
-                ctx.accounts.book_pda.dispute_resolution_result
-            } else {
-                ctx.accounts.book_pda.aggregated_oracle_outcome
-            };
-            if o.outcome == final_outcome {
-                // oracle gave the correct result, pay
-                let mut usdc_to_transfer = 0;
-                usdc_to_transfer += o.stake; // return stake
-                let total_profit = ctx.accounts.book_pda.dealt_wager * (10000 - BETTOR_PAYOUT_RATE) / 10000;
-                let total_oralce_reward = total_profit * ORALCES_REWARD_SHARE / 10000;
-                usdc_to_transfer += total_oralce_reward * o.stake / ctx.accounts.book_pda.total_oracle_stake;
-                // transfer usdc
-                let usdc_transfer_cpi_context = CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    anchor_spl::token::Transfer {
-                        from: ctx.accounts.book_ata.to_account_info(),
-                        to: ctx.accounts.oracle_token_account.to_account_info(),
-                        authority: ctx.accounts.book_pda.to_account_info(),
-                    },
-                );
-                let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
-                let book_pda_signer_seeds = &[
-                    b"Book".as_ref(),
-                    &ctx.accounts.book_pda.game_id.to_le_bytes(),
-                    bet_type_vec.as_slice(),
-                    &[*ctx.bumps.get("book_pda").unwrap()],
-                ];
-                anchor_spl::token::transfer(
-                    usdc_transfer_cpi_context.with_signer(&[book_pda_signer_seeds]),
-                    usdc_to_transfer,
-                )?;
-            } else {
-                // oracle gave the wrong result, no pay
-            }
-            // realloc
-            let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
-            book_pda_account_info.realloc(ctx.accounts.book_pda.current_space(), false)?;
-            // return lamports
-            let oracle_account_info = ctx.accounts.oracle.to_account_info();
-            **oracle_account_info.lamports.borrow_mut() =
-                oracle_account_info.lamports().checked_add(RENT_PER_ORACLE).unwrap();
-            **book_pda_account_info.lamports.borrow_mut() =
-                book_pda_account_info.lamports().checked_sub(RENT_PER_ORACLE).unwrap();
+    ctx.accounts.book_pda.oracles_count -= 1;
+    let o = &ctx.accounts.oracle_pda;
+    // every stake is returned when the book was cancelled on a missed deadline
+    let timed_out = ctx.accounts.book_pda.is_timed_out();
+    if timed_out || (o.revealed && o.outcome == final_outcome) {
+        // oracle revealed the correct result, pay
+        let mut usdc_to_transfer = 0;
+        usdc_to_transfer += o.stake; // return stake
+        let total_profit = ctx.accounts.book_pda.total_profit(final_outcome.unwrap());
+        let total_oralce_reward = total_profit * ctx.accounts.book_pda.params.oracles_reward_share / 10000;
+        usdc_to_transfer +=
+            Book::pro_rata_share(total_oralce_reward, o.stake, ctx.accounts.book_pda.total_oracle_stake)?;
+        if !timed_out {
+            // share of the stake slashed from the wrong oracles
+            let (slashed_stake_share, _) = ctx.accounts.book_pda.slashed_stake_shares(final_outcome.unwrap());
+            // share of the bonds lost by the dispute rounds when no round proposed the final outcome
+            let (lost_dispute_bonds_share, _) = ctx.accounts.book_pda.lost_dispute_bonds_shares(final_outcome.unwrap());
+            let correct_oracles_share = slashed_stake_share
+                .checked_add(lost_dispute_bonds_share)
+                .ok_or(BettingError::MathOverflow)?;
+            usdc_to_transfer += Book::pro_rata_share(
+                correct_oracles_share,
+                o.stake,
+                ctx.accounts.book_pda.correct_oracle_stake(final_outcome.unwrap()),
+            )?;
         }
-        None => {
-            return err!(BettingError::NotFound);
-        }
-    };
+        // transfer usdc
+        let usdc_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.book_ata.to_account_info(),
+                to: ctx.accounts.oracle_token_account.to_account_info(),
+                authority: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
+        let book_pda_signer_seeds = &[
+            b"Book".as_ref(),
+            &ctx.accounts.book_pda.game_id.to_le_bytes(),
+            bet_type_vec.as_slice(),
+            &[*ctx.bumps.get("book_pda").unwrap()],
+        ];
+        anchor_spl::token::transfer(
+            usdc_transfer_cpi_context.with_signer(&[book_pda_signer_seeds]),
+            usdc_to_transfer,
+        )?;
+    } else {
+        // oracle gave the wrong result or never revealed its commitment, the stake is forfeited
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::VecDeque, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -124,8 +125,8 @@ mod test {
     };
 
     use crate::{
-        constants::{BETTOR_DISPUTE_WINDOW, RENT_PER_ORACLE},
-        state::{Bet, BetOutcome, BetType, Book, Oracle, Position, UserAccount},
+        constants::{BETTOR_DISPUTE_WINDOW, MAX_DISPUTE_ROUNDS},
+        state::{BetOutcome, BetType, Book, BookParams, Dispute, Oracle, Quorum, UserAccount},
     };
 
     #[tokio::test]
@@ -185,18 +186,48 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::from([(
-                oracle.pubkey(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -265,6 +296,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -277,7 +309,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -289,7 +324,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -356,18 +391,48 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::from([(
-                oracle.pubkey(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::Against),
         };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -436,6 +501,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -448,7 +514,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -460,7 +529,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should not be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -525,18 +594,48 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::from([(
-                oracle.pubkey(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -605,6 +704,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -617,7 +717,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -629,7 +732,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -697,18 +800,48 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW + 60),
-            oracles: BTreeMap::from([(
-                oracle.pubkey(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -777,6 +910,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -789,7 +923,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -801,7 +938,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -870,33 +1007,48 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::from([(
-                oracle.pubkey(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::from([Bet {
-                id: 0,
-                bettor: unsettled_bettor,
-                wager: 1000000 * 100,
-            }]),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                unsettled_bettor,
-                Position {
-                    active_bets_count: 1,
-                    bets_count: 1,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 1000000 * 100,
-                    dealt_wager: 0,
-                    dispute_stake: 0,
-                },
-            )]),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: vec![2000],
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
         book_pda_data.resize(book_pda_state.current_space(), 0);
@@ -965,6 +1117,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -977,7 +1130,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -989,7 +1145,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -1000,7 +1156,7 @@ mod test {
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6007)")]
+    #[should_panic(expected = "Custom(3012)")]
     async fn test_book_oracle_settle_err_wrong_oracle() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
@@ -1057,16 +1213,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: Some(0),
-            oracles: BTreeMap::from([(
-                Pubkey::new_unique(),
-                Oracle {
-                    stake: 1000000 * 100,
-                    outcome: Some(BetOutcome::For),
-                },
-            )]),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: Some(BetOutcome::For),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
@@ -1122,6 +1285,11 @@ mod test {
             },
         );
 
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -1137,6 +1305,7 @@ mod test {
                 oracle_user_account: oracle_pda,
                 oracle_token_account: oracle_ata,
                 book_pda,
+                oracle_pda: book_oracle_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
                 system_program: system_program::id(),
@@ -1149,7 +1318,10 @@ mod test {
 
         // rent should be returned to the oracle system account
         let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
-        assert_eq!(oracle_system_account.lamports, LAMPORTS_PER_SOL + RENT_PER_ORACLE);
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
         // the book pda should be removed from the oracle user account
         let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
         let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
@@ -1161,7 +1333,7 @@ mod test {
         // the oracle should be removed from the book pda
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
         let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert!(book_state.oracles.get(&oracle.pubkey()).is_none());
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
         // reward and oracle stake should be transferred from the the book ata
         let book_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
@@ -1170,4 +1342,1480 @@ mod test {
             1000000 * 1000 - 1000000 * 100 - 3000000
         );
     }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_half_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::AsianHandicap { line: -3 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 0, 0, 0, 1000000 * 100, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::HalfFor),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::HalfFor),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // reward and oracle stake should be transferred to the oracle token account
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(user_token_account_state.amount, 1000000 * 100 + 1000000 * 100 + 1500000);
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // reward and oracle stake should be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(
+            book_token_account_state.amount,
+            1000000 * 1000 - 1000000 * 100 - 1500000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_oracle_unrevealed_commitment() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::Against),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // the stake of an unrevealed commitment is forfeited
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(user_token_account_state.amount, 1000000 * 100);
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // reward and oracle stake should not be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_token_account_state.amount, 1000000 * 1000);
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_slashed_oracle_stake() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 1000000 * 30,
+            dispute_resolution_result: Some(BetOutcome::For),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 2,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 30,
+                ends_at: 0,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 30, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // reward, oracle stake and the oracles share of the stake of the wrong oracle should be transferred to the oracle
+        // token account, the disputers get the other half of the slashed stake
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(
+            user_token_account_state.amount,
+            1000000 * 100 + 1000000 * 100 + 1000000 + 1000000 * 100
+        );
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // reward and oracle stake should be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(
+            book_token_account_state.amount,
+            1000000 * 1000 - 1000000 * 100 - 1000000 - 1000000 * 100
+        );
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_lost_dispute_bond() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 1000000 * 30,
+            dispute_resolution_result: Some(BetOutcome::For),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 2,
+            oracle_stakes: [0, 1000000 * 200, 0, 1000000 * 100, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::Against,
+                bond: 1000000 * 30,
+                ends_at: 0,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [0, 0, 1000000 * 30, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::For),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 200,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // reward, oracle stake, the stake of the wrong oracle and the bond of the failed dispute should be transferred to
+        // the oracle token account
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(
+            user_token_account_state.amount,
+            1000000 * 200 + 1000000 * 2 + 1000000 * 100 + 1000000 * 30
+        );
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // reward and oracle stake should be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(
+            book_token_account_state.amount,
+            1000000 * 1000 - 1000000 * 200 - 1000000 * 2 - 1000000 * 100 - 1000000 * 30
+        );
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_timed_out_dispute() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 1000000 * 1000,
+            dispute_resolution_result: Some(BetOutcome::Cancel),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [0, 1000000 * 100, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            // the dispute escalated and the council missed the resolution deadline
+            dispute: Some(Dispute {
+                round: 1,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 1000,
+                ends_at: 0,
+                escalated: true,
+                timed_out: true,
+            }),
+            dispute_bonds: [1000000 * 1000, 0, 0, 0, 0],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 2000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // the oracle stake should be returned to the oracle token account even though the oracle was wrong
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(user_token_account_state.amount, 1000000 * 200);
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // the oracle stake should be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_token_account_state.amount, 1000000 * 1900);
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_large_stakes() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 300000,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500000,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500000,
+            bet_type,
+            total_dispute_stake: 1000000 * 30000,
+            dispute_resolution_result: Some(BetOutcome::For),
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 2,
+            oracle_stakes: [0, 1000000 * 100000, 0, 1000000 * 200000, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: Some(Dispute {
+                round: MAX_DISPUTE_ROUNDS,
+                outcome: BetOutcome::For,
+                bond: 1000000 * 30000,
+                ends_at: 0,
+                escalated: true,
+                timed_out: false,
+            }),
+            dispute_bonds: [1000000 * 30000, 0, 0, 0, 0],
+            disputes_count: 1,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100000,
+            outcome: Some(BetOutcome::For),
+            commitment: None,
+            revealed: true,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // the pro rata shares of stakes this large should not overflow
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(
+            user_token_account_state.amount,
+            1000000 * 100 + 1000000 * 100000 + 1000000 * 1000 + 1000000 * 100000
+        );
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // reward and oracle stake should be transferred from the the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(
+            book_token_account_state.amount,
+            1000000 * 1000000 - 1000000 * 100000 - 1000000 * 1000 - 1000000 * 100000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_book_oracle_settle_success_with_timed_out_quorum() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let oracle_ata = anchor_spl::associated_token::get_associated_token_address(&oracle.pubkey(), &USDC);
+        let oracle_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: oracle.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut oracle_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(oracle_ata_state, &mut oracle_ata_data).unwrap();
+        program_test.add_account(
+            oracle_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(oracle_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 1000000 * 500,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 500,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: true,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Cancel),
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: Some(Oracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(BetOutcome::Against),
+                &[1; 32],
+            )),
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 1000,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (oracle_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), oracle.pubkey().as_ref()], &program_id);
+        let oracle_pda_state = UserAccount {
+            authority: oracle.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::from([book_pda]),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut oracle_pda_data: Vec<u8> = Vec::new();
+        oracle_pda_state.try_serialize(&mut oracle_pda_data).unwrap();
+        program_test.add_account(
+            oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(oracle_pda_state.current_space()),
+                data: oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookOracleSettleAccounts {
+                oracle: oracle.pubkey(),
+                oracle_user_account: oracle_pda,
+                oracle_token_account: oracle_ata,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookOracleSettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // rent should be returned to the oracle system account
+        let oracle_system_account = banks_client.get_account(oracle.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            oracle_system_account.lamports,
+            LAMPORTS_PER_SOL + Rent::default().minimum_balance(Oracle::INIT_SPACE)
+        );
+        // the book pda should be removed from the oracle user account
+        let oracle_user_account = banks_client.get_account(oracle_pda).await.unwrap().unwrap();
+        let oracle_user_account_state = UserAccount::try_deserialize(&mut oracle_user_account.data.as_slice()).unwrap();
+        assert!(!oracle_user_account_state.books_oracled.contains(&book_pda));
+        // the stake is returned even though the commitment was never revealed
+        let user_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(oracle_ata).await.unwrap();
+        assert_eq!(user_token_account_state.amount, 1000000 * 200);
+        // the oracle should be removed from the book pda
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(banks_client.get_account(book_oracle_pda).await.unwrap().is_none());
+        // only the oracle stake should be transferred from the book ata
+        let book_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_token_account_state.amount, 1000000 * 900);
+    }
 }
@@ -0,0 +1,973 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{IN_PLAY_SUSPENSION, IN_PLAY_SUSPENSION_COOLDOWN, MIN_SUSPENSION_STAKE_SHARE},
+    error::BettingError,
+    state::{Book, BookPage, Oracle, Position},
+};
+
+#[derive(Accounts)]
+pub struct BookOracleSuspendAccounts<'info> {
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
+}
+
+// reports a market moving event like a goal or a red card, the pages of the price levels with bets still in their bet
+// delay and the positions of their bettors are passed as remaining accounts
+pub fn book_oracle_suspend(ctx: Context<BookOracleSuspendAccounts>) -> Result<()> {
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // only in-play books can be suspended
+    require!(ctx.accounts.book_pda.in_play.is_some(), BettingError::NotInPlay);
+    // check the oracle holds enough of the oracle stake
+    require!(
+        ctx.accounts.oracle_pda.stake as u128 * 10000
+            >= ctx.accounts.book_pda.total_oracle_stake as u128 * MIN_SUSPENSION_STAKE_SHARE as u128,
+        BettingError::NoAuthority
+    );
+    // check the market is not suspended or in its cooldown
+    require!(
+        ctx.accounts.book_pda.in_play.unwrap().suspended_until + IN_PLAY_SUSPENSION_COOLDOWN <= now,
+        BettingError::NotInWindow
+    );
+    // update book pda
+    let book_key = ctx.accounts.book_pda.key();
+    let loaders = BookPage::loaders(ctx.remaining_accounts)?;
+    let mut pages = BookPage::load_pages(&book_key, &loaders)?;
+    let position_loaders = Position::loaders(ctx.remaining_accounts)?;
+    let mut positions = Position::load_positions(&book_key, &position_loaders)?;
+    ctx.accounts
+        .book_pda
+        .suspend(&mut pages, &mut positions, now, now + IN_PLAY_SUSPENSION)?;
+    // realloc
+    let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
+    book_pda_account_info.realloc(ctx.accounts.book_pda.current_space(), false)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent,
+        signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, InPlay, Oracle, Position, Quorum};
+
+    #[tokio::test]
+    async fn test_book_oracle_suspend_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 60,
+                suspended_until: 0,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_key = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            ..Default::default()
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                chrono::Utc::now().timestamp(),
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleSuspendAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookOracleSuspend)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // only the delayed level is needed, the market should be suspended and the bet still in its bet delay should
+        // be void
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(book_state.in_play.unwrap().suspended_until > chrono::Utc::now().timestamp());
+        assert_eq!(book_state.levels_for, vec![1200]);
+        assert!(book_state.levels_against.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.active_bets_count, 1);
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 300);
+        assert_eq!(book_state.wager_total, 1000000 * 300);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6015)")]
+    async fn test_book_oracle_suspend_err_not_in_play() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_key = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            ..Default::default()
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                chrono::Utc::now().timestamp(),
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleSuspendAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .accounts(AccountMeta::new(book_page1_pda, false))
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookOracleSuspend)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_book_oracle_suspend_err_stake_share() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 1000000 * 600,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 60,
+                suspended_until: 0,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_key = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            ..Default::default()
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                chrono::Utc::now().timestamp(),
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleSuspendAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookOracleSuspend)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_oracle_suspend_err_cooldown() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 60,
+                suspended_until: chrono::Utc::now().timestamp() - 60,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_key = Pubkey::new_unique();
+        let (bettor_position_pda, _) = Pubkey::find_program_address(
+            &[b"Position".as_ref(), book_pda.as_ref(), bettor_key.as_ref()],
+            &program_id,
+        );
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor: bettor_key,
+            ..Default::default()
+        };
+        let (book_page1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page1_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page1_pda_state.book = book_pda;
+        book_page1_pda_state.payer = Pubkey::new_unique();
+        book_page1_pda_state.odds = 1200;
+        book_page1_pda_state.bet_direction = BetDirection::For as u8;
+        let (book_page2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &6000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page2_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page2_pda_state.book = book_pda;
+        book_page2_pda_state.payer = Pubkey::new_unique();
+        book_page2_pda_state.odds = 6000;
+        book_page2_pda_state.bet_direction = BetDirection::Against as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page1_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 100,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page2_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                6000,
+                1000000 * 200,
+                bettor_key,
+                BetDirection::Against,
+                chrono::Utc::now().timestamp(),
+                0,
+            )
+            .unwrap();
+        let mut book_page1_pda_data = BookPage::discriminator().to_vec();
+        book_page1_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page1_pda_state));
+        program_test.add_account(
+            book_page1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_page2_pda_data = BookPage::discriminator().to_vec();
+        book_page2_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page2_pda_state));
+        program_test.add_account(
+            book_page2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        book_pda_data.resize(book_pda_state.current_space(), 0);
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::BookOracleSuspendAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+                oracle_pda: book_oracle_pda,
+            })
+            .accounts(AccountMeta::new(book_page2_pda, false))
+            .accounts(AccountMeta::new(bettor_position_pda, false))
+            .args(crate::instruction::BookOracleSuspend)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
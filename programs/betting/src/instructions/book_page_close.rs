@@ -0,0 +1,355 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Book, BookPage},
+};
+
+#[derive(Accounts)]
+pub struct BookPageCloseAccounts<'info> {
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(mut,close=payer)]
+    pub book_page_pda: AccountLoader<'info, BookPage>,
+}
+
+// anyone can close a page once it is empty or its bets can no longer be matched, the rent goes back to the payer
+pub fn book_page_close(ctx: Context<BookPageCloseAccounts>) -> Result<()> {
+    let book_page = ctx.accounts.book_page_pda.load()?;
+    // check book page
+    require_keys_eq!(book_page.book, ctx.accounts.book_pda.key(), BettingError::InvalidPages);
+    require_keys_eq!(book_page.payer, ctx.accounts.payer.key(), BettingError::NoAuthority);
+    // check window, the wagers of the bets left in the page are refunded on settlement
+    let now = Clock::get()?.unix_timestamp;
+    let oracle_update_window = ctx.accounts.book_pda.params.oracle_update_window;
+    require!(
+        book_page.len == 0
+            || matches!(ctx.accounts.book_pda.concluded_at, Some(concluded_at) if concluded_at + oracle_update_window <= now),
+        BettingError::NotInWindow
+    );
+    // update book pda
+    ctx.accounts.book_pda.remove_level(&book_page);
+    // realloc
+    let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
+    book_pda_account_info.realloc(ctx.accounts.book_pda.current_space(), false)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize, Discriminator};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, Position, Quorum};
+
+    #[tokio::test]
+    async fn test_book_page_close_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let page_payer = Pubkey::new_unique();
+        let bettor = Pubkey::new_unique();
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+
+        let (bettor_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), bettor.as_ref()], &program_id);
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor,
+            ..Default::default()
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = page_payer;
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookPageCloseAccounts {
+                payer: page_payer,
+                book_pda,
+                book_page_pda,
+            })
+            .args(crate::instruction::BookPageClose)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the page should be closed and its rent returned to the payer
+        assert!(banks_client.get_account(book_page_pda).await.unwrap().is_none());
+        let page_payer_account = banks_client.get_account(page_payer).await.unwrap().unwrap();
+        assert_eq!(
+            page_payer_account.lamports,
+            Rent::default().minimum_balance(BookPage::INIT_SPACE)
+        );
+        // the price level should be removed from the book pda, the bet is refunded on settlement
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(book_state.levels_for.is_empty());
+        let bettor_position_pda_account = banks_client.get_account(bettor_position_pda).await.unwrap().unwrap();
+        let bettor_position_pda_state =
+            Position::try_deserialize(&mut bettor_position_pda_account.data.as_slice()).unwrap();
+        assert_eq!(bettor_position_pda_state.wager, 1000000 * 20);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_book_page_close_err_bets_still_open() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let page_payer = Pubkey::new_unique();
+        let bettor = Pubkey::new_unique();
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let mut book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 1,
+        };
+
+        let (bettor_position_pda, _) =
+            Pubkey::find_program_address(&[b"Position".as_ref(), book_pda.as_ref(), bettor.as_ref()], &program_id);
+        let mut bettor_position_pda_state = Position {
+            book: book_pda,
+            bettor,
+            ..Default::default()
+        };
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1200_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let mut book_page_pda_state: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        book_page_pda_state.book = book_pda;
+        book_page_pda_state.payer = page_payer;
+        book_page_pda_state.odds = 1200;
+        book_page_pda_state.bet_direction = BetDirection::For as u8;
+        book_pda_state
+            .new_bet(
+                &mut [&mut *book_page_pda_state],
+                &mut [&mut bettor_position_pda_state],
+                1200,
+                1000000 * 20,
+                bettor,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        let mut book_page_pda_data = BookPage::discriminator().to_vec();
+        book_page_pda_data.extend_from_slice(bytemuck::bytes_of(&*book_page_pda_state));
+        program_test.add_account(
+            book_page_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(BookPage::INIT_SPACE),
+                data: book_page_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let mut bettor_position_pda_data = Position::discriminator().to_vec();
+        bettor_position_pda_data.extend_from_slice(bytemuck::bytes_of(&bettor_position_pda_state));
+        program_test.add_account(
+            bettor_position_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Position::INIT_SPACE),
+                data: bettor_position_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::BookPageCloseAccounts {
+                payer: page_payer,
+                book_pda,
+                book_page_pda,
+            })
+            .args(crate::instruction::BookPageClose)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
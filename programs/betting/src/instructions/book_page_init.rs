@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Bet, BetDirection, Book, BookPage},
+};
+
+#[derive(Accounts)]
+#[instruction(bet_direction:BetDirection,odds:u32,index:u16)]
+pub struct BookPageInitAccounts<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(init,payer=payer,space=BookPage::INIT_SPACE,seeds=[b"BookPage".as_ref(),book_pda.key().as_ref(),bet_direction.try_to_vec().unwrap().as_slice(),&odds.to_le_bytes(),&index.to_le_bytes()],bump)]
+    pub book_page_pda: AccountLoader<'info, BookPage>,
+    pub system_program: Program<'info, System>,
+}
+
+// opens a page of a price level, a crowded level overflows into its next page, the payer gets the rent back when the
+// page is closed
+pub fn book_page_init(
+    ctx: Context<BookPageInitAccounts>,
+    bet_direction: BetDirection,
+    odds: u32,
+    index: u16,
+) -> Result<()> {
+    // check condition
+    require!(odds as u64 > Bet::ODDS_PRECISION, BettingError::InvalidOdds);
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // init book page pda
+    let mut book_page = ctx.accounts.book_page_pda.load_init()?;
+    book_page.book = ctx.accounts.book_pda.key();
+    book_page.payer = ctx.accounts.payer.key();
+    book_page.odds = odds;
+    book_page.index = index;
+    book_page.bet_direction = bet_direction as u8;
+    book_page.bump = *ctx.bumps.get("book_page_pda").unwrap();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{BetDirection, BetType, Book, BookPage, BookParams, Quorum};
+
+    #[tokio::test]
+    async fn test_book_page_init_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let page_payer = Keypair::new();
+        program_test.add_account(
+            page_payer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::Against.try_to_vec().unwrap().as_slice(),
+                &2500_u32.to_le_bytes(),
+                &1_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&page_payer)
+            .accounts(crate::accounts::BookPageInitAccounts {
+                payer: page_payer.pubkey(),
+                book_pda,
+                book_page_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookPageInit {
+                bet_direction: BetDirection::Against,
+                odds: 2500,
+                index: 1,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &page_payer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the page should be empty and point to the book
+        let book_page_account = banks_client.get_account(book_page_pda).await.unwrap().unwrap();
+        assert_eq!(book_page_account.data.len(), BookPage::INIT_SPACE);
+        let book_page_state = BookPage::try_deserialize(&mut book_page_account.data.as_slice()).unwrap();
+        assert_eq!(book_page_state.book, book_pda);
+        assert_eq!(book_page_state.payer, page_payer.pubkey());
+        assert_eq!(book_page_state.odds, 2500);
+        assert_eq!(book_page_state.index, 1);
+        assert_eq!(book_page_state.bet_direction(), BetDirection::Against);
+        assert!(book_page_state.bets().is_empty());
+        // the payer should pay the rent of the page
+        let page_payer_account = banks_client.get_account(page_payer.pubkey()).await.unwrap().unwrap();
+        assert_eq!(
+            page_payer_account.lamports,
+            LAMPORTS_PER_SOL - Rent::default().minimum_balance(BookPage::INIT_SPACE)
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6009)")]
+    async fn test_book_page_init_err_invalid_odds() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let page_payer = Keypair::new();
+        program_test.add_account(
+            page_payer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (book_page_pda, _) = Pubkey::find_program_address(
+            &[
+                b"BookPage".as_ref(),
+                book_pda.as_ref(),
+                BetDirection::For.try_to_vec().unwrap().as_slice(),
+                &1000_u32.to_le_bytes(),
+                &0_u16.to_le_bytes(),
+            ],
+            &program_id,
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&page_payer)
+            .accounts(crate::accounts::BookPageInitAccounts {
+                payer: page_payer.pubkey(),
+                book_pda,
+                book_page_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::BookPageInit {
+                bet_direction: BetDirection::For,
+                odds: 1000,
+                index: 0,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &page_payer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
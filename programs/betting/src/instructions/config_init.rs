@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::OPERATOR_PUBKEY, error::BettingError, state::Config};
+
+#[derive(Accounts)]
+pub struct ConfigInitAccounts<'info> {
+    #[account(mut,address=OPERATOR_PUBKEY)]
+    pub operator: Signer<'info>,
+    #[account(init,payer=operator,space=Config::INIT_SPACE,seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn config_init(ctx: Context<ConfigInitAccounts>, config: Config) -> Result<()> {
+    require!(config.is_valid(), BettingError::InvalidConfig);
+    ctx.accounts.config_pda.set_inner(config);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::AccountDeserialize;
+    use home::home_dir;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair},
+        signer::Signer,
+        system_program,
+        transaction::Transaction,
+    };
+
+    use crate::state::Config;
+
+    #[tokio::test]
+    async fn test_config_init_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
+        let operator = read_keypair_file(key_file_path).unwrap();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let admin = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::ConfigInitAccounts {
+                operator: operator.pubkey(),
+                config_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::ConfigInit {
+                config: Config {
+                    admin,
+                    min_bet_amount: 1000000,
+                    ..Config::default()
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the config pda should be created
+        let config_account = banks_client.get_account(config_pda).await.unwrap().unwrap();
+        let config_state = Config::try_deserialize(&mut config_account.data.as_slice()).unwrap();
+        assert_eq!(
+            config_state,
+            Config {
+                admin,
+                min_bet_amount: 1000000,
+                ..Config::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(2012)")] // ConstraintAddress
+    async fn test_config_init_err_wrong_operator() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let operator = Keypair::new();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let admin = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::ConfigInitAccounts {
+                operator: operator.pubkey(),
+                config_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::ConfigInit {
+                config: Config {
+                    admin,
+                    ..Config::default()
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6030)")]
+    async fn test_config_init_err_invalid_shares() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
+        let operator = read_keypair_file(key_file_path).unwrap();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let admin = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::ConfigInitAccounts {
+                operator: operator.pubkey(),
+                config_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::ConfigInit {
+                config: Config {
+                    admin,
+                    oracles_reward_share: 9000,
+                    initiator_reward_share: 2000,
+                    ..Config::default()
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::BettingError, state::Config};
+
+#[derive(Accounts)]
+pub struct ConfigUpdateAccounts<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut,seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+}
+
+// the admin can hand over the config by updating it with another admin
+pub fn config_update(ctx: Context<ConfigUpdateAccounts>, config: Config) -> Result<()> {
+    // check admin
+    require_keys_eq!(
+        ctx.accounts.admin.key(),
+        ctx.accounts.config_pda.admin,
+        BettingError::NoAuthority
+    );
+    require!(config.is_valid(), BettingError::InvalidConfig);
+    ctx.accounts.config_pda.set_inner(config);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::Config;
+
+    #[tokio::test]
+    async fn test_config_update_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let admin = Keypair::new();
+        program_test.add_account(
+            admin.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let new_admin = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let config_pda_state = Config {
+            admin: admin.pubkey(),
+            ..Config::default()
+        };
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        config_pda_state.try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&admin)
+            .accounts(crate::accounts::ConfigUpdateAccounts {
+                admin: admin.pubkey(),
+                config_pda,
+            })
+            .args(crate::instruction::ConfigUpdate {
+                config: Config {
+                    admin: new_admin,
+                    bettor_dispute_window: 60 * 60,
+                    ..Config::default()
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &admin],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the config pda should be updated and handed over to the new admin
+        let config_account = banks_client.get_account(config_pda).await.unwrap().unwrap();
+        let config_state = Config::try_deserialize(&mut config_account.data.as_slice()).unwrap();
+        assert_eq!(config_state.admin, new_admin);
+        assert_eq!(config_state.bettor_dispute_window, 60 * 60);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_config_update_err_wrong_admin() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let admin = Keypair::new();
+        program_test.add_account(
+            admin.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let config_pda_state = Config {
+            admin: Pubkey::new_unique(),
+            ..Config::default()
+        };
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        config_pda_state.try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&admin)
+            .accounts(crate::accounts::ConfigUpdateAccounts {
+                admin: admin.pubkey(),
+                config_pda,
+            })
+            .args(crate::instruction::ConfigUpdate {
+                config: Config::default(),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &admin],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6030)")]
+    async fn test_config_update_err_dispute_window_shorter_than_oracle_window() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let admin = Keypair::new();
+        program_test.add_account(
+            admin.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let config_pda_state = Config {
+            admin: admin.pubkey(),
+            ..Config::default()
+        };
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        config_pda_state.try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&admin)
+            .accounts(crate::accounts::ConfigUpdateAccounts {
+                admin: admin.pubkey(),
+                config_pda,
+            })
+            .args(crate::instruction::ConfigUpdate {
+                config: Config {
+                    admin: admin.pubkey(),
+                    oracle_update_window: 60 * 60,
+                    bettor_dispute_window: 60 * 30,
+                    ..Config::default()
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &admin],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::OPERATOR_PUBKEY, error::BettingError, state::Council};
+
+#[derive(Accounts)]
+pub struct CouncilInitAccounts<'info> {
+    #[account(mut,address=OPERATOR_PUBKEY)]
+    pub operator: Signer<'info>,
+    #[account(init,payer=operator,space=Council::INIT_SPACE,seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    pub system_program: Program<'info, System>,
+}
+
+// the council can only be initialized once, after that its members are rotated through council proposals
+pub fn council_init(ctx: Context<CouncilInitAccounts>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    require!(Council::is_valid(&members, threshold), BettingError::InvalidCouncil);
+    ctx.accounts.council_pda.set_inner(Council {
+        members,
+        threshold,
+        proposals_count: 0,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::AccountDeserialize;
+    use home::home_dir;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair},
+        signer::Signer,
+        system_program,
+        transaction::Transaction,
+    };
+
+    use crate::state::Council;
+
+    #[tokio::test]
+    async fn test_council_init_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
+        let operator = read_keypair_file(key_file_path).unwrap();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let members = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::CouncilInitAccounts {
+                operator: operator.pubkey(),
+                council_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilInit {
+                members: members.clone(),
+                threshold: 2,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the council pda should be created
+        let council_account = banks_client.get_account(council_pda).await.unwrap().unwrap();
+        let council_state = Council::try_deserialize(&mut council_account.data.as_slice()).unwrap();
+        assert_eq!(council_state.members, members);
+        assert_eq!(council_state.threshold, 2);
+        assert_eq!(council_state.proposals_count, 0);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(2012)")] // ConstraintAddress
+    async fn test_council_init_err_wrong_operator() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let operator = Keypair::new();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let members = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::CouncilInitAccounts {
+                operator: operator.pubkey(),
+                council_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilInit { members, threshold: 2 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6027)")]
+    async fn test_council_init_err_invalid_threshold() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
+        let operator = read_keypair_file(key_file_path).unwrap();
+        program_test.add_account(
+            operator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let members = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&operator)
+            .accounts(crate::accounts::CouncilInitAccounts {
+                operator: operator.pubkey(),
+                council_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilInit { members, threshold: 4 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &operator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
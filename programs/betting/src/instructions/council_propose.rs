@@ -0,0 +1,267 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Council, CouncilAction, CouncilProposal},
+};
+
+#[derive(Accounts)]
+pub struct CouncilProposeAccounts<'info> {
+    #[account(mut)]
+    pub member: Signer<'info>,
+    #[account(mut,seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(init,payer=member,space=CouncilProposal::INIT_SPACE,seeds=[b"CouncilProposal".as_ref(),&council_pda.proposals_count.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+    pub system_program: Program<'info, System>,
+}
+
+// the proposer votes for its own proposal
+pub fn council_propose(ctx: Context<CouncilProposeAccounts>, action: CouncilAction) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // check action
+    if let CouncilAction::RotateMembers { members, threshold } = &action {
+        require!(Council::is_valid(members, *threshold), BettingError::InvalidCouncil);
+    }
+    // init proposal pda
+    ctx.accounts.proposal_pda.set_inner(CouncilProposal {
+        id: ctx.accounts.council_pda.proposals_count,
+        proposer: ctx.accounts.member.key(),
+        action,
+        votes: vec![ctx.accounts.member.key()],
+    });
+    // update council pda
+    ctx.accounts.council_pda.proposals_count += 1;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{Council, CouncilAction, CouncilProposal};
+
+    #[tokio::test]
+    async fn test_council_propose_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 3,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &3_u32.to_le_bytes()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilProposeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilPropose {
+                action: CouncilAction::GameClose { game_id: 1 },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the proposal pda should be created with the vote of the proposer
+        let proposal_account = banks_client.get_account(proposal_pda).await.unwrap().unwrap();
+        let proposal_state = CouncilProposal::try_deserialize(&mut proposal_account.data.as_slice()).unwrap();
+        assert_eq!(proposal_state.id, 3);
+        assert_eq!(proposal_state.proposer, member.pubkey());
+        assert_eq!(proposal_state.action, CouncilAction::GameClose { game_id: 1 });
+        assert_eq!(proposal_state.votes, vec![member.pubkey()]);
+        // the council pda should be updated
+        let council_account = banks_client.get_account(council_pda).await.unwrap().unwrap();
+        let council_state = Council::try_deserialize(&mut council_account.data.as_slice()).unwrap();
+        assert_eq!(council_state.proposals_count, 4);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_council_propose_err_not_member() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![Pubkey::new_unique()],
+            threshold: 1,
+            proposals_count: 0,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilProposeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilPropose {
+                action: CouncilAction::GameClose { game_id: 1 },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6027)")]
+    async fn test_council_propose_err_invalid_rotation() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 0,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilProposeAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::CouncilPropose {
+                action: CouncilAction::RotateMembers {
+                    members: vec![member.pubkey()],
+                    threshold: 2,
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Council, CouncilAction, CouncilProposal},
+};
+
+#[derive(Accounts)]
+pub struct CouncilRotateMembersAccounts<'info> {
+    pub member: Signer<'info>,
+    #[account(mut,seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(mut,close=proposer,seeds=[b"CouncilProposal".as_ref(),&proposal_pda.id.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+}
+
+// replaces the members and the threshold with the ones of a proposal approved by the current members
+pub fn council_rotate_members(ctx: Context<CouncilRotateMembersAccounts>) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // check proposer
+    require_keys_eq!(
+        ctx.accounts.proposer.key(),
+        ctx.accounts.proposal_pda.proposer,
+        BettingError::NoAuthority
+    );
+    // check proposal
+    let (members, threshold) = match &ctx.accounts.proposal_pda.action {
+        CouncilAction::RotateMembers { members, threshold } => (members.clone(), *threshold),
+        _ => return err!(BettingError::InvalidProposal),
+    };
+    require!(
+        ctx.accounts.council_pda.is_approved(&ctx.accounts.proposal_pda),
+        BettingError::ProposalNotApproved
+    );
+    // update council pda
+    ctx.accounts.council_pda.members = members;
+    ctx.accounts.council_pda.threshold = threshold;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{Council, CouncilAction, CouncilProposal};
+
+    #[tokio::test]
+    async fn test_council_rotate_members_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let new_member = Pubkey::new_unique();
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey(), Pubkey::new_unique()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::RotateMembers {
+                members: vec![member.pubkey(), new_member],
+                threshold: 2,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilRotateMembersAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+            })
+            .args(crate::instruction::CouncilRotateMembers)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the council pda should be updated
+        let council_account = banks_client.get_account(council_pda).await.unwrap().unwrap();
+        let council_state = Council::try_deserialize(&mut council_account.data.as_slice()).unwrap();
+        assert_eq!(council_state.members, vec![member.pubkey(), new_member]);
+        assert_eq!(council_state.threshold, 2);
+        // the proposal pda should be closed
+        assert!(banks_client.get_account(proposal_pda).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6029)")]
+    async fn test_council_rotate_members_err_not_approved() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let new_member = Pubkey::new_unique();
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey(), Pubkey::new_unique()],
+            threshold: 2,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::RotateMembers {
+                members: vec![member.pubkey(), new_member],
+                threshold: 2,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilRotateMembersAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+            })
+            .args(crate::instruction::CouncilRotateMembers)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6028)")]
+    async fn test_council_rotate_members_err_wrong_proposal() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameClose { game_id: 1 },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilRotateMembersAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+            })
+            .args(crate::instruction::CouncilRotateMembers)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
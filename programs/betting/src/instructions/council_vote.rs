@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Council, CouncilProposal},
+};
+
+#[derive(Accounts)]
+pub struct CouncilVoteAccounts<'info> {
+    pub member: Signer<'info>,
+    #[account(seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(mut,seeds=[b"CouncilProposal".as_ref(),&proposal_pda.id.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+}
+
+pub fn council_vote(ctx: Context<CouncilVoteAccounts>) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // update proposal pda
+    ctx.accounts
+        .proposal_pda
+        .vote(&ctx.accounts.council_pda, ctx.accounts.member.key());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{Council, CouncilAction, CouncilProposal};
+
+    #[tokio::test]
+    async fn test_council_vote_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let proposer = Pubkey::new_unique();
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey(), proposer],
+            threshold: 2,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer,
+            action: CouncilAction::GameClose { game_id: 1 },
+            votes: vec![proposer],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilVoteAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+            })
+            .args(crate::instruction::CouncilVote)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the vote should be added to the proposal pda
+        let proposal_account = banks_client.get_account(proposal_pda).await.unwrap().unwrap();
+        let proposal_state = CouncilProposal::try_deserialize(&mut proposal_account.data.as_slice()).unwrap();
+        assert_eq!(proposal_state.votes, vec![proposer, member.pubkey()]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_council_vote_err_not_member() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+        let proposer = Pubkey::new_unique();
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![proposer],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer,
+            action: CouncilAction::GameClose { game_id: 1 },
+            votes: vec![proposer],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::CouncilVoteAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+            })
+            .args(crate::instruction::CouncilVote)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -1,16 +1,49 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::OPERATOR_PUBKEY, error::BettingError, state::Game};
+use crate::{
+    error::BettingError,
+    state::{Council, CouncilAction, CouncilProposal, Game},
+};
 
 #[derive(Accounts)]
 pub struct GameCloseAccounts<'info> {
-    #[account(mut,address=OPERATOR_PUBKEY)]
-    pub operator: Signer<'info>,
-    #[account(mut,close=operator,seeds=[b"Game".as_ref(),&game_pda.game_id.to_le_bytes()],bump)]
+    #[account(mut)]
+    pub member: Signer<'info>,
+    #[account(seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(mut,close=proposer,seeds=[b"CouncilProposal".as_ref(),&proposal_pda.id.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut,close=member,seeds=[b"Game".as_ref(),&game_pda.game_id.to_le_bytes()],bump)]
     pub game_pda: Account<'info, Game>,
 }
 
 pub fn game_close(ctx: Context<GameCloseAccounts>) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // check proposer
+    require_keys_eq!(
+        ctx.accounts.proposer.key(),
+        ctx.accounts.proposal_pda.proposer,
+        BettingError::NoAuthority
+    );
+    // check proposal
+    require!(
+        ctx.accounts.proposal_pda.action
+            == CouncilAction::GameClose {
+                game_id: ctx.accounts.game_pda.game_id
+            },
+        BettingError::InvalidProposal
+    );
+    require!(
+        ctx.accounts.council_pda.is_approved(&ctx.accounts.proposal_pda),
+        BettingError::ProposalNotApproved
+    );
     require!(
         ctx.accounts.game_pda.books_count == 0,
         BettingError::UnsettledBooksRemaining
@@ -20,33 +53,26 @@ pub fn game_close(ctx: Context<GameCloseAccounts>) -> Result<()> {
 
 #[cfg(test)]
 mod test {
-    use std::rc::Rc;
+    use std::{collections::BTreeMap, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::AccountSerialize;
-    use home::home_dir;
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
-        account::Account,
-        native_token::LAMPORTS_PER_SOL,
-        pubkey::Pubkey,
-        rent::Rent,
-        signature::{read_keypair_file, Keypair},
-        signer::Signer,
-        transaction::Transaction,
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
     };
 
-    use crate::state::Game;
+    use crate::state::{Council, CouncilAction, CouncilProposal, Game};
 
     #[tokio::test]
     async fn test_game_close_success() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
-        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
-        let operator = read_keypair_file(key_file_path).unwrap();
+        let member = Keypair::new();
         program_test.add_account(
-            operator.pubkey(),
+            member.pubkey(),
             Account {
                 lamports: LAMPORTS_PER_SOL,
                 ..Default::default()
@@ -58,8 +84,7 @@ mod test {
         let home_team_id: u32 = 1;
         let away_team_id: u32 = 1;
         let kickoff: i64 = 1;
-        let (game_pda, _) =
-            Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
         let game_pda_state = Game {
             game_id,
             league_id,
@@ -67,6 +92,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -80,6 +109,45 @@ mod test {
             },
         );
 
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameClose { game_id },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -90,9 +158,12 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .signer(&operator)
+            .signer(&member)
             .accounts(crate::accounts::GameCloseAccounts {
-                operator: operator.pubkey(),
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
                 game_pda,
             })
             .args(crate::instruction::GameClose)
@@ -101,24 +172,25 @@ mod test {
         let tx = Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
-            &[&payer, &operator],
+            &[&payer, &member],
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the game pda account should be closed
+        // the game pda account and the proposal pda should be closed
         assert!(banks_client.get_account(game_pda).await.unwrap().is_none());
+        assert!(banks_client.get_account(proposal_pda).await.unwrap().is_none());
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(2012)")] // ConstraintAddress
-    async fn test_game_close_err_wrong_operator() {
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_game_close_err_wrong_member() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
-        let operator = Keypair::new();
+        let member = Keypair::new();
         program_test.add_account(
-            operator.pubkey(),
+            member.pubkey(),
             Account {
                 lamports: LAMPORTS_PER_SOL,
                 ..Default::default()
@@ -130,8 +202,7 @@ mod test {
         let home_team_id: u32 = 1;
         let away_team_id: u32 = 1;
         let kickoff: i64 = 1;
-        let (game_pda, _) =
-            Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
         let game_pda_state = Game {
             game_id,
             league_id,
@@ -139,6 +210,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -152,6 +227,45 @@ mod test {
             },
         );
 
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![Pubkey::new_unique()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameClose { game_id },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -162,9 +276,12 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .signer(&operator)
+            .signer(&member)
             .accounts(crate::accounts::GameCloseAccounts {
-                operator: operator.pubkey(),
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
                 game_pda,
             })
             .args(crate::instruction::GameClose)
@@ -173,7 +290,7 @@ mod test {
         let tx = Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
-            &[&payer, &operator],
+            &[&payer, &member],
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
@@ -188,10 +305,9 @@ mod test {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
-        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
-        let operator = read_keypair_file(key_file_path).unwrap();
+        let member = Keypair::new();
         program_test.add_account(
-            operator.pubkey(),
+            member.pubkey(),
             Account {
                 lamports: LAMPORTS_PER_SOL,
                 ..Default::default()
@@ -203,8 +319,7 @@ mod test {
         let home_team_id: u32 = 1;
         let away_team_id: u32 = 1;
         let kickoff: i64 = 1;
-        let (game_pda, _) =
-            Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
         let game_pda_state = Game {
             game_id,
             league_id,
@@ -212,6 +327,10 @@ mod test {
             away_team_id,
             kickoff,
             books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
         };
         let mut game_pda_data: Vec<u8> = Vec::new();
         game_pda_state.try_serialize(&mut game_pda_data).unwrap();
@@ -225,6 +344,45 @@ mod test {
             },
         );
 
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameClose { game_id },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -235,9 +393,12 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .signer(&operator)
+            .signer(&member)
             .accounts(crate::accounts::GameCloseAccounts {
-                operator: operator.pubkey(),
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
                 game_pda,
             })
             .args(crate::instruction::GameClose)
@@ -246,7 +407,7 @@ mod test {
         let tx = Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
-            &[&payer, &operator],
+            &[&payer, &member],
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
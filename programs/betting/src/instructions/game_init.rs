@@ -1,17 +1,30 @@
+use std::collections::BTreeMap;
+
 use anchor_lang::prelude::*;
 
-use crate::{constants::OPERATOR_PUBKEY, state::Game};
+use crate::{
+    error::BettingError,
+    state::{Council, CouncilAction, CouncilProposal, Game},
+};
 
 #[derive(Accounts)]
 #[instruction(game_id:u32)]
 pub struct GameInitAccounts<'info> {
-    #[account(mut,address=OPERATOR_PUBKEY)]
-    pub operator: Signer<'info>,
-    #[account(init,payer=operator,space=Game::INIT_SPACE,seeds=[b"Game".as_ref(),&game_id.to_le_bytes()],bump)]
+    #[account(mut)]
+    pub member: Signer<'info>,
+    #[account(seeds=[b"Council".as_ref()],bump)]
+    pub council_pda: Account<'info, Council>,
+    #[account(mut,close=proposer,seeds=[b"CouncilProposal".as_ref(),&proposal_pda.id.to_le_bytes()],bump)]
+    pub proposal_pda: Account<'info, CouncilProposal>,
+    /// CHECK: will be checked in the instruction
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(init,payer=member,space=Game::INIT_SPACE,seeds=[b"Game".as_ref(),&game_id.to_le_bytes()],bump)]
     pub game_pda: Account<'info, Game>,
     pub system_program: Program<'info, System>,
 }
 
+// the game must be exactly the one of a proposal approved by the council
 pub fn game_init(
     ctx: Context<GameInitAccounts>,
     game_id: u32,
@@ -20,6 +33,33 @@ pub fn game_init(
     away_team_id: u32,
     kickoff: i64,
 ) -> Result<()> {
+    // check member
+    require!(
+        ctx.accounts.council_pda.is_member(&ctx.accounts.member.key()),
+        BettingError::NoAuthority
+    );
+    // check proposer
+    require_keys_eq!(
+        ctx.accounts.proposer.key(),
+        ctx.accounts.proposal_pda.proposer,
+        BettingError::NoAuthority
+    );
+    // check proposal
+    require!(
+        ctx.accounts.proposal_pda.action
+            == CouncilAction::GameInit {
+                game_id,
+                league_id,
+                home_team_id,
+                away_team_id,
+                kickoff,
+            },
+        BettingError::InvalidProposal
+    );
+    require!(
+        ctx.accounts.council_pda.is_approved(&ctx.accounts.proposal_pda),
+        BettingError::ProposalNotApproved
+    );
     ctx.accounts.game_pda.set_inner(Game {
         game_id,
         league_id,
@@ -27,6 +67,10 @@ pub fn game_init(
         away_team_id,
         kickoff,
         books_count: 0,
+        final_score: None,
+        concluded_at: None,
+        reveal_starts_at: None,
+        score_reports: BTreeMap::new(),
     });
     Ok(())
 }
@@ -36,30 +80,23 @@ mod test {
     use std::rc::Rc;
 
     use anchor_client::RequestBuilder;
-    use anchor_lang::AccountDeserialize;
-    use home::home_dir;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
-        account::Account,
-        native_token::LAMPORTS_PER_SOL,
-        pubkey::Pubkey,
-        signature::{read_keypair_file, Keypair},
-        signer::Signer,
-        system_program,
-        transaction::Transaction,
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::state::Game;
+    use crate::state::{Council, CouncilAction, CouncilProposal, Game};
 
     #[tokio::test]
     async fn test_game_init_success() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
-        let key_file_path = home_dir().unwrap().join(".config/solana/id.json");
-        let operator = read_keypair_file(key_file_path).unwrap();
+        let member = Keypair::new();
         program_test.add_account(
-            operator.pubkey(),
+            member.pubkey(),
             Account {
                 lamports: LAMPORTS_PER_SOL,
                 ..Default::default()
@@ -71,8 +108,52 @@ mod test {
         let home_team_id: u32 = 1;
         let away_team_id: u32 = 1;
         let kickoff: i64 = 1;
-        let (game_pda, _) =
-            Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameInit {
+                game_id,
+                league_id,
+                home_team_id,
+                away_team_id,
+                kickoff,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
 
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
@@ -84,9 +165,12 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .signer(&operator)
+            .signer(&member)
             .accounts(crate::accounts::GameInitAccounts {
-                operator: operator.pubkey(),
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
                 game_pda,
                 system_program: system_program::id(),
             })
@@ -102,7 +186,7 @@ mod test {
         let tx = Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
-            &[&payer, &operator],
+            &[&payer, &member],
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
@@ -116,17 +200,19 @@ mod test {
         assert_eq!(game_state.away_team_id, 1);
         assert_eq!(game_state.kickoff, 1);
         assert_eq!(game_state.books_count, 0);
+        // the proposal pda should be closed
+        assert!(banks_client.get_account(proposal_pda).await.unwrap().is_none());
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(2012)")] // ConstraintAddress
-    async fn test_game_init_err_wrong_operator() {
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_game_init_err_wrong_member() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
-        let operator = Keypair::new();
+        let member = Keypair::new();
         program_test.add_account(
-            operator.pubkey(),
+            member.pubkey(),
             Account {
                 lamports: LAMPORTS_PER_SOL,
                 ..Default::default()
@@ -138,8 +224,166 @@ mod test {
         let home_team_id: u32 = 1;
         let away_team_id: u32 = 1;
         let kickoff: i64 = 1;
-        let (game_pda, _) =
-            Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![Pubkey::new_unique()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameInit {
+                game_id,
+                league_id,
+                home_team_id,
+                away_team_id,
+                kickoff,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&member)
+            .accounts(crate::accounts::GameInitAccounts {
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
+                game_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::GameInit {
+                game_id,
+                league_id,
+                home_team_id,
+                away_team_id,
+                kickoff,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &member],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the game pda account should be created
+        let game_account = banks_client.get_account(game_pda).await.unwrap().unwrap();
+        let game_state = Game::try_deserialize(&mut game_account.data.as_slice()).unwrap();
+        assert_eq!(game_state.game_id, 1);
+        assert_eq!(game_state.league_id, 1);
+        assert_eq!(game_state.home_team_id, 1);
+        assert_eq!(game_state.away_team_id, 1);
+        assert_eq!(game_state.kickoff, 1);
+        assert_eq!(game_state.books_count, 0);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6028)")]
+    async fn test_game_init_err_wrong_proposal() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let member = Keypair::new();
+        program_test.add_account(
+            member.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let league_id: u32 = 1;
+        let home_team_id: u32 = 1;
+        let away_team_id: u32 = 1;
+        let kickoff: i64 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+
+        let (council_pda, _) = Pubkey::find_program_address(&[b"Council".as_ref()], &program_id);
+        let council_pda_state = Council {
+            members: vec![member.pubkey()],
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_pda_data: Vec<u8> = Vec::new();
+        council_pda_state.try_serialize(&mut council_pda_data).unwrap();
+        council_pda_data.resize(Council::INIT_SPACE, 0);
+        program_test.add_account(
+            council_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Council::INIT_SPACE),
+                data: council_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let (proposal_pda, _) =
+            Pubkey::find_program_address(&[b"CouncilProposal".as_ref(), &0_u32.to_le_bytes()], &program_id);
+        let proposal_pda_state = CouncilProposal {
+            id: 0,
+            proposer: member.pubkey(),
+            action: CouncilAction::GameInit {
+                game_id,
+                league_id,
+                home_team_id,
+                away_team_id,
+                kickoff: kickoff + 1,
+            },
+            votes: vec![member.pubkey()],
+        };
+        let mut proposal_pda_data: Vec<u8> = Vec::new();
+        proposal_pda_state.try_serialize(&mut proposal_pda_data).unwrap();
+        proposal_pda_data.resize(CouncilProposal::INIT_SPACE, 0);
+        program_test.add_account(
+            proposal_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(CouncilProposal::INIT_SPACE),
+                data: proposal_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
 
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
@@ -151,9 +395,12 @@ mod test {
             anchor_client::RequestNamespace::Global,
         );
         let instructions = rb
-            .signer(&operator)
+            .signer(&member)
             .accounts(crate::accounts::GameInitAccounts {
-                operator: operator.pubkey(),
+                member: member.pubkey(),
+                council_pda,
+                proposal_pda,
+                proposer: member.pubkey(),
                 game_pda,
                 system_program: system_program::id(),
             })
@@ -169,7 +416,7 @@ mod test {
         let tx = Transaction::new_signed_with_payer(
             &instructions,
             Some(&payer.pubkey()),
-            &[&payer, &operator],
+            &[&payer, &member],
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
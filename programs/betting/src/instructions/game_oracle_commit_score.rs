@@ -0,0 +1,592 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::{
+    constants::{ORACLE_COMMIT_WINDOW, ORACLE_REVEAL_WINDOW},
+    error::BettingError,
+    state::{Book, Config, Game, Oracle, ScoreReport},
+};
+
+#[derive(Accounts)]
+pub struct GameOracleCommitScoreAccounts<'info> {
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"Game".as_ref(),&game_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(seeds=[b"Book".as_ref(),&game_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, Book>,
+    #[account(seeds=[b"Oracle".as_ref(),book_pda.key().as_ref(),oracle.key().as_ref()],bump)]
+    pub oracle_pda: Account<'info, Oracle>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+// the commitment is the hash of the keys of the game and the oracle, the score and a salt, see
+// ScoreReport::commitment, it can be replaced until the commit phase ends
+pub fn game_oracle_commit_score(ctx: Context<GameOracleCommitScoreAccounts>, commitment: [u8; 32]) -> Result<()> {
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.game_pda.concluded_at.is_none()
+            || ctx.accounts.game_pda.concluded_at.unwrap() + ctx.accounts.config_pda.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // check commit window, another round starts if the reveal phase ended without a final score
+    match ctx.accounts.game_pda.reveal_starts_at {
+        Some(t) if t > now => {}
+        Some(t) if t + ORACLE_REVEAL_WINDOW <= now && ctx.accounts.game_pda.final_score.is_none() => {
+            ctx.accounts.game_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+        Some(_) => {
+            return err!(BettingError::NotInWindow);
+        }
+        None => {
+            ctx.accounts.game_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+    }
+    // a score can only be revealed once
+    if let Some(r) = ctx.accounts.game_pda.score_reports.get(ctx.accounts.oracle.key) {
+        require!(r.score.is_none(), BettingError::NotInWindow);
+    }
+    // the oracle commits with its stake in a book of the game
+    let stake = ctx.accounts.oracle_pda.stake;
+    // update game pda
+    let previous_report = ctx.accounts.game_pda.score_reports.insert(
+        ctx.accounts.oracle.key(),
+        ScoreReport {
+            stake,
+            score: None,
+            commitment: Some(commitment),
+        },
+    );
+    if previous_report.is_none() {
+        // realloc
+        let game_pda_account_info = ctx.accounts.game_pda.to_account_info();
+        let game_pda_space = ctx.accounts.game_pda.current_space();
+        game_pda_account_info.realloc(game_pda_space, false)?;
+        // rent
+        let min_rent = Rent::get()?.minimum_balance(game_pda_space);
+        if game_pda_account_info.lamports() < min_rent {
+            let diff = min_rent - game_pda_account_info.lamports();
+            let rent_transfer_cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.oracle.to_account_info(),
+                    to: ctx.accounts.game_pda.to_account_info(),
+                },
+            );
+            system_program::transfer(rent_transfer_cpi_context, diff)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{BetType, Book, BookParams, Config, Game, Oracle, Quorum, Score, ScoreReport};
+
+    #[tokio::test]
+    async fn test_game_oracle_commit_score_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleCommitScoreAccounts {
+                config_pda,
+                oracle: oracle.pubkey(),
+                game_pda,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::GameOracleCommitScore {
+                commitment: ScoreReport::commitment(&game_pda, &oracle.pubkey(), Score { home: 2, away: 1 }, &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the score report should be added to the game pda and the score should stay hidden
+        let game_account = banks_client.get_account(game_pda).await.unwrap().unwrap();
+        let game_state = Game::try_deserialize(&mut game_account.data.as_slice()).unwrap();
+        assert_eq!(game_state.score_reports[&oracle.pubkey()].stake, 1000000 * 100);
+        assert_eq!(
+            game_state.score_reports[&oracle.pubkey()].commitment,
+            Some(ScoreReport::commitment(
+                &game_pda,
+                &oracle.pubkey(),
+                Score { home: 2, away: 1 },
+                &[1; 32]
+            ))
+        );
+        assert!(game_state.score_reports[&oracle.pubkey()].score.is_none());
+        // the commit phase should be started
+        assert!(game_state.reveal_starts_at.is_some());
+        assert!(game_state.final_score.is_none());
+        assert!(game_state.concluded_at.is_none());
+        // the game pda should be rent exempt
+        assert_eq!(
+            game_account.lamports,
+            Rent::default().minimum_balance(game_state.current_space())
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(3012)")]
+    async fn test_game_oracle_commit_score_err_oracle_did_not_opt_in() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleCommitScoreAccounts {
+                config_pda,
+                oracle: oracle.pubkey(),
+                game_pda,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::GameOracleCommitScore {
+                commitment: ScoreReport::commitment(&game_pda, &oracle.pubkey(), Score { home: 2, away: 1 }, &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_game_oracle_commit_score_err_commit_phase_ended() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = BetType::One { handicap: 0 };
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = Book {
+            total_oracle_stake: 1000000 * 100,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 1,
+            oracle_stakes: [1000000 * 100, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let (book_oracle_pda, _) = Pubkey::find_program_address(
+            &[b"Oracle".as_ref(), book_pda.as_ref(), oracle.pubkey().as_ref()],
+            &program_id,
+        );
+        let book_oracle_pda_state = Oracle {
+            book: book_pda,
+            oracle: oracle.pubkey(),
+            stake: 1000000 * 100,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        };
+        let mut book_oracle_pda_data: Vec<u8> = Vec::new();
+        book_oracle_pda_state.try_serialize(&mut book_oracle_pda_data).unwrap();
+        program_test.add_account(
+            book_oracle_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Oracle::INIT_SPACE),
+                data: book_oracle_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleCommitScoreAccounts {
+                config_pda,
+                oracle: oracle.pubkey(),
+                game_pda,
+                book_pda,
+                oracle_pda: book_oracle_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::GameOracleCommitScore {
+                commitment: ScoreReport::commitment(&game_pda, &oracle.pubkey(), Score { home: 2, away: 1 }, &[1; 32]),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::ORACLE_REVEAL_WINDOW,
+    error::BettingError,
+    state::{Config, Game, Score, ScoreReport},
+};
+
+#[derive(Accounts)]
+pub struct GameOracleRevealScoreAccounts<'info> {
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"Game".as_ref(),&game_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+}
+
+pub fn game_oracle_reveal_score(
+    ctx: Context<GameOracleRevealScoreAccounts>,
+    score: Score,
+    salt: [u8; 32],
+) -> Result<()> {
+    // check reveal window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        matches!(ctx.accounts.game_pda.reveal_starts_at, Some(t) if t <= now && t + ORACLE_REVEAL_WINDOW > now),
+        BettingError::NotInWindow
+    );
+    // the final score can no longer change once the oracle update window passed
+    require!(
+        ctx.accounts.game_pda.concluded_at.is_none()
+            || ctx.accounts.game_pda.concluded_at.unwrap() + ctx.accounts.config_pda.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // update score report
+    let commitment = ScoreReport::commitment(&ctx.accounts.game_pda.key(), ctx.accounts.oracle.key, score, &salt);
+    match ctx.accounts.game_pda.score_reports.get_mut(ctx.accounts.oracle.key) {
+        Some(r) => {
+            require!(r.commitment == Some(commitment), BettingError::InvalidCommitment);
+            r.score = Some(score);
+            r.commitment = None;
+        }
+        None => {
+            return err!(BettingError::UserDidNotOptIn);
+        }
+    }
+    // update game pda
+    let aggregated_score = ctx.accounts.game_pda.aggregated_score();
+    if ctx.accounts.game_pda.final_score != aggregated_score {
+        ctx.accounts.game_pda.final_score = aggregated_score;
+        if aggregated_score.is_some() {
+            ctx.accounts.game_pda.concluded_at = Some(now);
+        } else {
+            ctx.accounts.game_pda.concluded_at = None;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{Config, Game, Score, ScoreReport};
+
+    #[tokio::test]
+    async fn test_game_oracle_reveal_score_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            score_reports: BTreeMap::from([
+                (
+                    Pubkey::new_unique(),
+                    ScoreReport {
+                        stake: 1000000 * 100,
+                        score: Some(Score { home: 2, away: 1 }),
+                        commitment: None,
+                    },
+                ),
+                (
+                    Pubkey::new_unique(),
+                    ScoreReport {
+                        stake: 1000000 * 100,
+                        score: Some(Score { home: 2, away: 1 }),
+                        commitment: None,
+                    },
+                ),
+                (
+                    oracle.pubkey(),
+                    ScoreReport {
+                        stake: 1000000 * 100,
+                        score: None,
+                        commitment: Some(ScoreReport::commitment(
+                            &game_pda,
+                            &oracle.pubkey(),
+                            Score { home: 2, away: 1 },
+                            &[1; 32],
+                        )),
+                    },
+                ),
+            ]),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleRevealScoreAccounts {
+                oracle: oracle.pubkey(),
+                game_pda,
+                config_pda,
+            })
+            .args(crate::instruction::GameOracleRevealScore {
+                score: Score { home: 2, away: 1 },
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the game should be concluded once enough oracles revealed the score
+        let game_account = banks_client.get_account(game_pda).await.unwrap().unwrap();
+        let game_state = Game::try_deserialize(&mut game_account.data.as_slice()).unwrap();
+        assert_eq!(
+            game_state.score_reports[&oracle.pubkey()].score,
+            Some(Score { home: 2, away: 1 })
+        );
+        assert!(game_state.score_reports[&oracle.pubkey()].commitment.is_none());
+        assert_eq!(game_state.final_score, Some(Score { home: 2, away: 1 }));
+        assert!(game_state.concluded_at.is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6024)")]
+    async fn test_game_oracle_reveal_score_err_invalid_commitment() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            score_reports: BTreeMap::from([(
+                oracle.pubkey(),
+                ScoreReport {
+                    stake: 1000000 * 100,
+                    score: None,
+                    commitment: Some(ScoreReport::commitment(
+                        &game_pda,
+                        &oracle.pubkey(),
+                        Score { home: 2, away: 1 },
+                        &[1; 32],
+                    )),
+                },
+            )]),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleRevealScoreAccounts {
+                oracle: oracle.pubkey(),
+                game_pda,
+                config_pda,
+            })
+            .args(crate::instruction::GameOracleRevealScore {
+                score: Score { home: 1, away: 2 },
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_game_oracle_reveal_score_err_window_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: Some(0),
+            score_reports: BTreeMap::from([(
+                oracle.pubkey(),
+                ScoreReport {
+                    stake: 1000000 * 100,
+                    score: None,
+                    commitment: Some(ScoreReport::commitment(
+                        &game_pda,
+                        &oracle.pubkey(),
+                        Score { home: 2, away: 1 },
+                        &[1; 32],
+                    )),
+                },
+            )]),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(game_pda_state.current_space()),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::GameOracleRevealScoreAccounts {
+                oracle: oracle.pubkey(),
+                game_pda,
+                config_pda,
+            })
+            .args(crate::instruction::GameOracleRevealScore {
+                score: Score { home: 2, away: 1 },
+                salt: [1; 32],
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -1,41 +1,115 @@
+pub mod book_bettor_amend_bet;
+pub mod book_bettor_cancel_all_bets;
 pub mod book_bettor_cancel_bet;
-pub mod book_bettor_cancel_dispute;
 pub mod book_bettor_dispute;
 pub mod book_bettor_opt_int;
 pub mod book_bettor_place_bet;
 pub mod book_bettor_settle;
 pub mod book_close;
+pub mod book_council_resolve_dispute;
+pub mod book_derive_outcome;
+pub mod book_disputer_settle;
+pub mod book_expire_dispute;
+pub mod book_expire_quorum;
 pub mod book_init;
 pub mod book_initiator_settle;
 pub mod book_match_bets;
-pub mod book_operator_resolve_dispute;
 pub mod book_oracle_add_stake;
+pub mod book_oracle_commit_outcome;
 pub mod book_oracle_opt_in;
+pub mod book_oracle_reveal_outcome;
 pub mod book_oracle_settle;
-pub mod book_oracle_update_outcome;
+pub mod book_oracle_suspend;
+pub mod book_page_close;
+pub mod book_page_init;
+pub mod book_purge_expired_bets;
+pub mod config_init;
+pub mod config_update;
+pub mod council_init;
+pub mod council_propose;
+pub mod council_rotate_members;
+pub mod council_vote;
 pub mod game_close;
 pub mod game_init;
+pub mod game_oracle_commit_score;
+pub mod game_oracle_reveal_score;
+pub mod multi_book_bettor_cancel_bet;
+pub mod multi_book_bettor_dispute;
+pub mod multi_book_bettor_opt_in;
+pub mod multi_book_bettor_place_bet;
+pub mod multi_book_bettor_settle;
+pub mod multi_book_close;
+pub mod multi_book_council_resolve_dispute;
+pub mod multi_book_expire_dispute;
+pub mod multi_book_init;
+pub mod multi_book_initiator_settle;
+pub mod multi_book_match_bets;
+pub mod multi_book_oracle_commit_outcome;
+pub mod multi_book_oracle_opt_in;
+pub mod multi_book_oracle_reveal_outcome;
+pub mod multi_book_oracle_settle;
+pub mod parlay_cancel;
+pub mod parlay_init;
+pub mod parlay_match;
+pub mod parlay_settle;
 pub mod user_account_close;
 pub mod user_account_init;
 pub mod user_account_shrink;
 
+pub use book_bettor_amend_bet::*;
+pub use book_bettor_cancel_all_bets::*;
 pub use book_bettor_cancel_bet::*;
-pub use book_bettor_cancel_dispute::*;
 pub use book_bettor_dispute::*;
 pub use book_bettor_opt_int::*;
 pub use book_bettor_place_bet::*;
 pub use book_bettor_settle::*;
 pub use book_close::*;
+pub use book_council_resolve_dispute::*;
+pub use book_derive_outcome::*;
+pub use book_disputer_settle::*;
+pub use book_expire_dispute::*;
+pub use book_expire_quorum::*;
 pub use book_init::*;
 pub use book_initiator_settle::*;
 pub use book_match_bets::*;
-pub use book_operator_resolve_dispute::*;
 pub use book_oracle_add_stake::*;
+pub use book_oracle_commit_outcome::*;
 pub use book_oracle_opt_in::*;
+pub use book_oracle_reveal_outcome::*;
 pub use book_oracle_settle::*;
-pub use book_oracle_update_outcome::*;
+pub use book_oracle_suspend::*;
+pub use book_page_close::*;
+pub use book_page_init::*;
+pub use book_purge_expired_bets::*;
+pub use config_init::*;
+pub use config_update::*;
+pub use council_init::*;
+pub use council_propose::*;
+pub use council_rotate_members::*;
+pub use council_vote::*;
 pub use game_close::*;
 pub use game_init::*;
+pub use game_oracle_commit_score::*;
+pub use game_oracle_reveal_score::*;
+pub use multi_book_bettor_cancel_bet::*;
+pub use multi_book_bettor_dispute::*;
+pub use multi_book_bettor_opt_in::*;
+pub use multi_book_bettor_place_bet::*;
+pub use multi_book_bettor_settle::*;
+pub use multi_book_close::*;
+pub use multi_book_council_resolve_dispute::*;
+pub use multi_book_expire_dispute::*;
+pub use multi_book_init::*;
+pub use multi_book_initiator_settle::*;
+pub use multi_book_match_bets::*;
+pub use multi_book_oracle_commit_outcome::*;
+pub use multi_book_oracle_opt_in::*;
+pub use multi_book_oracle_reveal_outcome::*;
+pub use multi_book_oracle_settle::*;
+pub use parlay_cancel::*;
+pub use parlay_init::*;
+pub use parlay_match::*;
+pub use parlay_settle::*;
 pub use user_account_close::*;
 pub use user_account_init::*;
 pub use user_account_shrink::*;
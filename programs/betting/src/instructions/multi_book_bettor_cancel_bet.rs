@@ -0,0 +1,433 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    error::BettingError,
+    state::{BetDirection, MultiBook},
+};
+
+#[derive(Accounts)]
+pub struct MultiBookBettorCancelBetAccounts<'info> {
+    pub bettor: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=bettor)]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn multi_book_bettor_cancel_bet(
+    ctx: Context<MultiBookBettorCancelBetAccounts>,
+    outcome: u8,
+    bet_id: u64,
+    bet_direction: BetDirection,
+) -> Result<()> {
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // get the bet
+    let outcome_bets = match ctx.accounts.book_pda.outcomes.get_mut(outcome as usize) {
+        Some(o) => o,
+        None => {
+            return err!(BettingError::InvalidOutcome);
+        }
+    };
+    let bets = if let BetDirection::For = bet_direction {
+        &mut outcome_bets.bets_for
+    } else {
+        &mut outcome_bets.bets_against
+    };
+    let bet = if let Ok(index) = bets.binary_search_by_key(&bet_id, |b| b.id) {
+        bets.remove(index).unwrap()
+    } else {
+        return err!(BettingError::NotFound);
+    };
+    // check bettor
+    require_keys_eq!(bet.bettor, ctx.accounts.bettor.key(), BettingError::NoAuthority);
+    // update book pda
+    ctx.accounts.book_pda.wager_total -= bet.wager;
+    ctx.accounts.book_pda.positions.entry(bet.bettor).and_modify(|p| {
+        p.active_bets_count -= 1;
+        p.wager -= bet.wager;
+    });
+
+    // return the wager
+    let wager_return_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.book_ata.to_account_info(),
+            to: ctx.accounts.bettor_token_account.to_account_info(),
+            authority: ctx.accounts.book_pda.to_account_info(),
+        },
+    );
+    let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
+    let book_pda_signer_seeds = &[
+        b"MultiBook".as_ref(),
+        &ctx.accounts.book_pda.game_id.to_le_bytes(),
+        bet_type_vec.as_slice(),
+        &[*ctx.bumps.get("book_pda").unwrap()],
+    ];
+    anchor_spl::token::transfer(
+        wager_return_cpi_context.with_signer(&[book_pda_signer_seeds]),
+        bet.wager,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+    };
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+        signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{
+        AggregationRule, Bet, BetDirection, BookParams, MultiBetType, MultiBook, MultiPosition, OutcomeBets, Quorum,
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_bettor_cancel_bet_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 1,
+            wager_total: 1000000 * 20,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![
+                OutcomeBets::default(),
+                OutcomeBets {
+                    payout_total: 0,
+                    bets_for: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xb0, 0x04, 0x00, 0x00]),
+                        bettor: bettor.pubkey(),
+                        wager: 1000000 * 20,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                    bets_against: VecDeque::new(),
+                },
+                OutcomeBets::default(),
+            ],
+            positions: BTreeMap::from([(
+                bettor.pubkey(),
+                MultiPosition {
+                    active_bets_count: 1,
+                    bets_count: 1,
+                    wager: 1000000 * 20,
+                    ..MultiPosition::new(3)
+                },
+            )]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorCancelBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::MultiBookBettorCancelBet {
+                outcome: 1,
+                bet_id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xb0, 0x04, 0x00, 0x00]),
+                bet_direction: BetDirection::For,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the wager should be returned
+        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 20);
+        // the bet should be removed from the queue of the outcome
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(book_state.outcomes[1].bets_for.is_empty());
+        assert_eq!(book_state.wager_total, 0);
+        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 0);
+        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 0);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_multi_book_bettor_cancel_bet_err_wrong_bettor() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 1,
+            wager_total: 1000000 * 20,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![
+                OutcomeBets::default(),
+                OutcomeBets {
+                    payout_total: 0,
+                    bets_for: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xb0, 0x04, 0x00, 0x00]),
+                        bettor: Pubkey::new_unique(),
+                        wager: 1000000 * 20,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                    bets_against: VecDeque::new(),
+                },
+                OutcomeBets::default(),
+            ],
+            positions: BTreeMap::from([(
+                Pubkey::new_unique(),
+                MultiPosition {
+                    active_bets_count: 1,
+                    bets_count: 1,
+                    wager: 1000000 * 20,
+                    ..MultiPosition::new(3)
+                },
+            )]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 1000000 * 20,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorCancelBetAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::MultiBookBettorCancelBet {
+                outcome: 1,
+                bet_id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xb0, 0x04, 0x00, 0x00]),
+                bet_direction: BetDirection::For,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
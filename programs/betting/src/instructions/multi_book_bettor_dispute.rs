@@ -0,0 +1,354 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{error::BettingError, state::MultiBook};
+
+#[derive(Accounts)]
+pub struct MultiBookBettorDisputeAccounts<'info> {
+    pub bettor: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=bettor)]
+    pub bettor_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn multi_book_bettor_dispute(ctx: Context<MultiBookBettorDisputeAccounts>, stake: u64) -> Result<()> {
+    // check stake
+    require!(
+        stake >= ctx.accounts.book_pda.params.min_bettor_dispute_stake,
+        BettingError::MinTokenAmountNotMet
+    );
+    // check time window
+    require!(ctx.accounts.book_pda.concluded_at.is_some(), BettingError::NotInWindow);
+    let now = Clock::get()?.unix_timestamp;
+    let concluded_at = ctx.accounts.book_pda.concluded_at.unwrap();
+    require!(
+        concluded_at + ctx.accounts.book_pda.params.oracle_update_window < now
+            && now < concluded_at + ctx.accounts.book_pda.params.bettor_dispute_window,
+        BettingError::NotInWindow
+    );
+
+    // transfer stake
+    let stake_transfer_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.bettor_token_account.to_account_info(),
+            to: ctx.accounts.book_ata.to_account_info(),
+            authority: ctx.accounts.bettor.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(stake_transfer_cpi_context, stake)?;
+
+    // update book pda
+    match ctx.accounts.book_pda.positions.get_mut(ctx.accounts.bettor.key) {
+        Some(p) => {
+            p.dispute_stake += stake;
+            ctx.accounts.book_pda.total_dispute_stake += stake;
+        }
+        None => {
+            return err!(BettingError::UserDidNotOptIn);
+        }
+    };
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+        signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::ORACLE_UPDATE_WINDOW,
+        state::{
+            AggregationRule, BookParams, MultiBetOutcome, MultiBetType, MultiBook, MultiPosition, OutcomeBets, Quorum,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_bettor_dispute_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: Some(chrono::Utc::now().timestamp() - ORACLE_UPDATE_WINDOW - 60),
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(0)),
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorDisputeAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::MultiBookBettorDispute { stake: 1000000 * 10 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the dispute stake should be added to the position
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 1000000 * 10);
+        assert_eq!(book_state.total_dispute_stake, 1000000 * 10);
+        // the stake should be transferred to the book ata
+        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(book_ata).await.unwrap();
+        assert_eq!(book_ata_account_state.amount, 1000000 * 10);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_multi_book_bettor_dispute_err_not_concluded() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let bettor_ata = anchor_spl::associated_token::get_associated_token_address(&bettor.pubkey(), &USDC);
+        let bettor_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: bettor.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut bettor_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(bettor_ata_state, &mut bettor_ata_data).unwrap();
+        program_test.add_account(
+            bettor_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(bettor_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(0)),
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+        let book_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: book_pda,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut book_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(book_ata_state, &mut book_ata_data).unwrap();
+        program_test.add_account(
+            book_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(book_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorDisputeAccounts {
+                bettor: bettor.pubkey(),
+                bettor_token_account: bettor_ata,
+                book_pda,
+                book_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .args(crate::instruction::MultiBookBettorDispute { stake: 1000000 * 10 })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,345 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::{
+    error::BettingError,
+    state::{MultiBook, MultiPosition, UserAccount},
+};
+
+#[derive(Accounts)]
+pub struct MultiBookBettorOptInAccounts<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+    #[account(mut,seeds=[b"UserAccount".as_ref(),bettor.key().as_ref()],bump)]
+    pub bettor_user_account: Account<'info, UserAccount>,
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn multi_book_bettor_opt_in(ctx: Context<MultiBookBettorOptInAccounts>) -> Result<()> {
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // update bettor user account
+    match ctx
+        .accounts
+        .bettor_user_account
+        .books_bet_on
+        .binary_search(&ctx.accounts.book_pda.key())
+    {
+        Ok(_) => {
+            return err!(BettingError::UserAlreadyOptIn);
+        }
+        Err(index) => {
+            ctx.accounts
+                .bettor_user_account
+                .books_bet_on
+                .insert(index, ctx.accounts.book_pda.key());
+            let bettor_user_account_info = ctx.accounts.bettor_user_account.to_account_info();
+            let user_account_space = ctx.accounts.bettor_user_account.current_space();
+            let user_account_min_rent = Rent::get()?.minimum_balance(user_account_space);
+            bettor_user_account_info.realloc(user_account_space, false)?;
+            if bettor_user_account_info.lamports() < user_account_min_rent {
+                let diff = user_account_min_rent - bettor_user_account_info.lamports();
+                let user_account_rent_transfer_cpi_context = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bettor.to_account_info(),
+                        to: ctx.accounts.bettor_user_account.to_account_info(),
+                    },
+                );
+                system_program::transfer(user_account_rent_transfer_cpi_context, diff)?;
+            }
+        }
+    }
+
+    // update book pda
+    if ctx.accounts.book_pda.positions.contains_key(ctx.accounts.bettor.key) {
+        return err!(BettingError::UserAlreadyOptIn);
+    } else {
+        // create position
+        let outcomes_count = ctx.accounts.book_pda.outcomes_count;
+        ctx.accounts
+            .book_pda
+            .positions
+            .insert(ctx.accounts.bettor.key(), MultiPosition::new(outcomes_count));
+        // realloc
+        let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
+        let book_pda_space = ctx.accounts.book_pda.current_space();
+        book_pda_account_info.realloc(book_pda_space, false)?;
+
+        // transfer rent, the size of the position depends on the number of outcomes
+        let position_rent = Rent::get()?.minimum_balance(32 + MultiPosition::space(outcomes_count));
+        let position_rent_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bettor.to_account_info(),
+                to: ctx.accounts.book_pda.to_account_info(),
+            },
+        );
+        system_program::transfer(position_rent_transfer_cpi_context, position_rent)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+    };
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{
+        AggregationRule, BookParams, MultiBetType, MultiBook, MultiPosition, OutcomeBets, Quorum, UserAccount,
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_bettor_opt_in_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (bettor_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), bettor.pubkey().as_ref()], &program_id);
+        let bettor_pda_state = UserAccount {
+            authority: bettor.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut bettor_pda_data: Vec<u8> = Vec::new();
+        bettor_pda_state.try_serialize(&mut bettor_pda_data).unwrap();
+        program_test.add_account(
+            bettor_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(bettor_pda_state.current_space()),
+                data: bettor_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorOptInAccounts {
+                bettor: bettor.pubkey(),
+                bettor_user_account: bettor_pda,
+                book_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::MultiBookBettorOptIn)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be added to the user account
+        let bettor_user_account = banks_client.get_account(bettor_pda).await.unwrap().unwrap();
+        let bettor_user_account_state = UserAccount::try_deserialize(&mut bettor_user_account.data.as_slice()).unwrap();
+        assert!(bettor_user_account_state.books_bet_on.contains(&book_pda));
+        // a position with payouts for every outcome should be created
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.positions[&bettor.pubkey()].payouts_for, vec![0, 0, 0]);
+        assert_eq!(book_state.positions[&bettor.pubkey()].payouts_against, vec![0, 0, 0]);
+        // the rent for the position should be paid by the bettor
+        assert_eq!(
+            book_account.lamports,
+            Rent::default().minimum_balance(book_pda_state.current_space())
+                + Rent::default().minimum_balance(32 + MultiPosition::space(3))
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6003)")]
+    async fn test_multi_book_bettor_opt_in_err_already_opt_in() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor = Keypair::new();
+        program_test.add_account(
+            bettor.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (bettor_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), bettor.pubkey().as_ref()], &program_id);
+        let bettor_pda_state = UserAccount {
+            authority: bettor.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::from([book_pda]),
+        };
+        let mut bettor_pda_data: Vec<u8> = Vec::new();
+        bettor_pda_state.try_serialize(&mut bettor_pda_data).unwrap();
+        program_test.add_account(
+            bettor_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(bettor_pda_state.current_space()),
+                data: bettor_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&bettor)
+            .accounts(crate::accounts::MultiBookBettorOptInAccounts {
+                bettor: bettor.pubkey(),
+                bettor_user_account: bettor_pda,
+                book_pda,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::MultiBookBettorOptIn)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &bettor],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
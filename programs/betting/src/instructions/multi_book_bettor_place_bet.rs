@@ -1,70 +1,95 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
     mint::USDC,
     token::{Token, TokenAccount},
 };
 
-use crate::{constants::BETTOR_DISPUTE_WINDOW, error::BettingError, state::Book};
+use crate::{
+    constants::RENT_PER_BET,
+    error::BettingError,
+    state::{Bet, BetDirection, Game, MultiBook},
+};
 
 #[derive(Accounts)]
-pub struct BookBettorCancelDisputeAccounts<'info> {
+pub struct MultiBookBettorPlaceBetAccounts<'info> {
+    #[account(mut)]
     pub bettor: Signer<'info>,
     #[account(mut,token::mint=USDC,token::authority=bettor)]
     pub bettor_token_account: Account<'info, TokenAccount>,
-    #[account(mut,seeds=[b"Book".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
-    pub book_pda: Account<'info, Book>,
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
     #[account(mut,associated_token::mint=USDC,associated_token::authority=book_pda)]
     pub book_ata: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn book_bettor_cancel_dispute(ctx: Context<BookBettorCancelDisputeAccounts>) -> Result<()> {
-    // check if in dispute window
-    require!(ctx.accounts.book_pda.concluded_at.is_some(), BettingError::NotInWindow);
+pub fn multi_book_bettor_place_bet(
+    ctx: Context<MultiBookBettorPlaceBetAccounts>,
+    outcome: u8,
+    odds: u32,
+    wager: u64,
+    bet_direction: BetDirection,
+) -> Result<()> {
+    // check condition
+    require!(
+        ctx.accounts.book_pda.positions.contains_key(ctx.accounts.bettor.key),
+        BettingError::UserDidNotOptIn
+    );
+    require!(
+        outcome < ctx.accounts.book_pda.outcomes_count,
+        BettingError::InvalidOutcome
+    );
+    require!(
+        wager >= ctx.accounts.book_pda.params.min_bet_amount,
+        BettingError::MinTokenAmountNotMet
+    );
+    require!(odds as u64 > Bet::ODDS_PRECISION, BettingError::InvalidOdds);
+    // check window
     let now = Clock::get()?.unix_timestamp;
-    let concluded_at = ctx.accounts.book_pda.concluded_at.unwrap();
-    require!(now < concluded_at + BETTOR_DISPUTE_WINDOW, BettingError::NotInWindow);
-
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // multi-books close at kickoff
+    require!(now < ctx.accounts.game_pda.kickoff, BettingError::NotInWindow);
+    // transfer wager
+    let wager_transfer_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.bettor_token_account.to_account_info(),
+            to: ctx.accounts.book_ata.to_account_info(),
+            authority: ctx.accounts.bettor.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(wager_transfer_cpi_context, wager)?;
     // update book pda
-    match ctx.accounts.book_pda.positions.get_mut(ctx.accounts.bettor.key) {
-        Some(p) => {
-            let dispute_stake = p.dispute_stake;
-            p.dispute_stake = 0;
-            ctx.accounts.book_pda.total_dispute_stake -= dispute_stake;
-            // return stake
-            let stake_transfer_cpi_context = CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: ctx.accounts.book_ata.to_account_info(),
-                    to: ctx.accounts.bettor_token_account.to_account_info(),
-                    authority: ctx.accounts.book_pda.to_account_info(),
-                },
-            );
-            let bet_type_vec = ctx.accounts.book_pda.bet_type.try_to_vec().unwrap();
-            let book_pda_signer_seeds = &[
-                b"Book".as_ref(),
-                &ctx.accounts.book_pda.game_id.to_le_bytes(),
-                bet_type_vec.as_slice(),
-                &[*ctx.bumps.get("book_pda").unwrap()],
-            ];
-            anchor_spl::token::transfer(
-                stake_transfer_cpi_context.with_signer(&[book_pda_signer_seeds]),
-                dispute_stake,
-            )?;
-        }
-        None => {
-            return err!(BettingError::UserDidNotOptIn);
-        }
-    }
+    ctx.accounts
+        .book_pda
+        .new_bet(outcome, odds, wager, ctx.accounts.bettor.key(), bet_direction, now)?;
+
+    // realloc
+    let book_pda_account_info = ctx.accounts.book_pda.to_account_info();
+    book_pda_account_info.realloc(ctx.accounts.book_pda.current_space(), false)?;
+    // transfer rent for bet
+    let rent_transfer_cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.bettor.to_account_info(),
+            to: ctx.accounts.book_pda.to_account_info(),
+        },
+    );
+    system_program::transfer(rent_transfer_cpi_context, RENT_PER_BET)?;
+
     Ok(())
 }
 
 #[cfg(test)]
 mod test {
-    use std::{
-        collections::{BTreeMap, VecDeque},
-        rc::Rc,
-    };
+    use std::{collections::BTreeMap, rc::Rc};
 
     use anchor_client::RequestBuilder;
     use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
@@ -72,16 +97,15 @@ mod test {
     use solana_program_test::{tokio, ProgramTest};
     use solana_sdk::{
         account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
-        signature::Keypair, signer::Signer, transaction::Transaction,
+        signature::Keypair, signer::Signer, system_program, transaction::Transaction,
     };
 
-    use crate::{
-        constants::BETTOR_DISPUTE_WINDOW,
-        state::{BetType, Book, Position},
+    use crate::state::{
+        AggregationRule, BetDirection, BookParams, Game, MultiBetType, MultiBook, MultiPosition, OutcomeBets, Quorum,
     };
 
     #[tokio::test]
-    async fn test_book_bettor_cancel_dispute_success() {
+    async fn test_multi_book_bettor_place_bet_success() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -115,44 +139,39 @@ mod test {
         );
 
         let game_id = 1_u32;
-        let bet_type = BetType::One { handicap: 0 };
+        let bet_type = MultiBetType::CorrectScore;
         let (book_pda, _) = Pubkey::find_program_address(
             &[
-                b"Book".as_ref(),
+                b"MultiBook".as_ref(),
                 &game_id.to_le_bytes(),
                 bet_type.try_to_vec().unwrap().as_slice(),
             ],
             &program_id,
         );
-        let book_pda_state = Book {
-            total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+        let book_pda_state = MultiBook {
             game_id,
             initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
             bets_count: 0,
             wager_total: 0,
-            payout_for_total: 0,
-            payout_against_total: 0,
             dealt_wager: 0,
-            bet_type,
-            total_dispute_stake: 1000000 * 20,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
             dispute_resolution_result: None,
-            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW + 60),
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
             oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 1000000 * 20,
-                },
-            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -170,7 +189,7 @@ mod test {
         let book_ata_state = anchor_spl::token::spl_token::state::Account {
             mint: USDC,
             owner: book_pda,
-            amount: 1000000 * 100,
+            amount: 0,
             state: anchor_spl::token::spl_token::state::AccountState::Initialized,
             ..Default::default()
         };
@@ -186,6 +205,31 @@ mod test {
             },
         );
 
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -197,14 +241,21 @@ mod test {
         );
         let instructions = rb
             .signer(&bettor)
-            .accounts(crate::accounts::BookBettorCancelDisputeAccounts {
+            .accounts(crate::accounts::MultiBookBettorPlaceBetAccounts {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::MultiBookBettorPlaceBet {
+                outcome: 2,
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::Against,
             })
-            .args(crate::instruction::BookBettorCancelDispute)
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -215,24 +266,31 @@ mod test {
         );
         banks_client.process_transaction(tx).await.unwrap();
 
-        // the dispute stake should be returned to the bettor token account
+        // wager should be transferred out from the bettor token account
         let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 120);
-        // the book pda should be updated
+        assert_eq!(bettor_token_account_state.amount, 1000000 * 80);
+        // the bet should be added to the queue of the outcome
         let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
-        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.total_dispute_stake, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 0);
-        // the dispute stake should be transferred from the book ata
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.bets_count, 1);
+        assert_eq!(book_state.wager_total, 1000000 * 20);
+        assert!(book_state.outcomes[0].bets_against.is_empty());
+        assert_eq!(book_state.outcomes[2].bets_against.len(), 1);
+        assert_eq!(book_state.outcomes[2].bets_against[0].bettor, bettor.pubkey());
+        assert_eq!(book_state.outcomes[2].bets_against[0].odds(), 1200);
+        assert_eq!(book_state.outcomes[2].bets_against[0].wager, 1000000 * 20);
+        assert_eq!(book_state.positions[&bettor.pubkey()].active_bets_count, 1);
+        assert_eq!(book_state.positions[&bettor.pubkey()].wager, 1000000 * 20);
+        // wager should be transferred to the book ata
         let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
             banks_client.get_packed_account_data(book_ata).await.unwrap();
-        assert_eq!(book_ata_account_state.amount, 1000000 * 80);
+        assert_eq!(book_ata_account_state.amount, 1000000 * 20);
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6006)")]
-    async fn test_book_bettor_cancel_dispute_err_out_of_dispute_window() {
+    #[should_panic(expected = "Custom(6010)")]
+    async fn test_multi_book_bettor_place_bet_err_invalid_outcome() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -266,44 +324,39 @@ mod test {
         );
 
         let game_id = 1_u32;
-        let bet_type = BetType::One { handicap: 0 };
+        let bet_type = MultiBetType::CorrectScore;
         let (book_pda, _) = Pubkey::find_program_address(
             &[
-                b"Book".as_ref(),
+                b"MultiBook".as_ref(),
                 &game_id.to_le_bytes(),
                 bet_type.try_to_vec().unwrap().as_slice(),
             ],
             &program_id,
         );
-        let book_pda_state = Book {
-            total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+        let book_pda_state = MultiBook {
             game_id,
             initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
             bets_count: 0,
             wager_total: 0,
-            payout_for_total: 0,
-            payout_against_total: 0,
             dealt_wager: 0,
-            bet_type,
-            total_dispute_stake: 1000000 * 20,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
             dispute_resolution_result: None,
-            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - 60),
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
             oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                bettor.pubkey(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 1000000 * 20,
-                },
-            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -321,7 +374,7 @@ mod test {
         let book_ata_state = anchor_spl::token::spl_token::state::Account {
             mint: USDC,
             owner: book_pda,
-            amount: 1000000 * 100,
+            amount: 0,
             state: anchor_spl::token::spl_token::state::AccountState::Initialized,
             ..Default::default()
         };
@@ -337,6 +390,31 @@ mod test {
             },
         );
 
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -348,14 +426,21 @@ mod test {
         );
         let instructions = rb
             .signer(&bettor)
-            .accounts(crate::accounts::BookBettorCancelDisputeAccounts {
+            .accounts(crate::accounts::MultiBookBettorPlaceBetAccounts {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::MultiBookBettorPlaceBet {
+                outcome: 3,
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::Against,
             })
-            .args(crate::instruction::BookBettorCancelDispute)
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -365,25 +450,11 @@ mod test {
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
-
-        // the dispute stake should be returned to the bettor token account
-        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
-            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 120);
-        // the book pda should be updated
-        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
-        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.total_dispute_stake, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 0);
-        // the dispute stake should be transferred from the book ata
-        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
-            banks_client.get_packed_account_data(book_ata).await.unwrap();
-        assert_eq!(book_ata_account_state.amount, 1000000 * 80);
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Custom(6004)")]
-    async fn test_book_bettor_cancel_dispute_err_user_did_not_opt_in() {
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_multi_book_bettor_place_bet_err_kickoff() {
         let program_id = crate::id();
         let mut program_test = ProgramTest::new("betting", program_id, None);
 
@@ -417,44 +488,39 @@ mod test {
         );
 
         let game_id = 1_u32;
-        let bet_type = BetType::One { handicap: 0 };
+        let bet_type = MultiBetType::CorrectScore;
         let (book_pda, _) = Pubkey::find_program_address(
             &[
-                b"Book".as_ref(),
+                b"MultiBook".as_ref(),
                 &game_id.to_le_bytes(),
                 bet_type.try_to_vec().unwrap().as_slice(),
             ],
             &program_id,
         );
-        let book_pda_state = Book {
-            total_oracle_stake: 0,
-            aggregated_oracle_outcome: None,
+        let book_pda_state = MultiBook {
             game_id,
             initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
             bets_count: 0,
             wager_total: 0,
-            payout_for_total: 0,
-            payout_against_total: 0,
             dealt_wager: 0,
-            bet_type,
-            total_dispute_stake: 1000000 * 20,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
             dispute_resolution_result: None,
-            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW + 60),
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
             oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::from([(
-                Pubkey::new_unique(),
-                Position {
-                    active_bets_count: 0,
-                    bets_count: 0,
-                    payout_for: 0,
-                    payout_against: 0,
-                    wager: 0,
-                    dealt_wager: 0,
-                    dispute_stake: 1000000 * 20,
-                },
-            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([(bettor.pubkey(), MultiPosition::new(3))]),
         };
         let mut book_pda_data: Vec<u8> = Vec::new();
         book_pda_state.try_serialize(&mut book_pda_data).unwrap();
@@ -472,7 +538,7 @@ mod test {
         let book_ata_state = anchor_spl::token::spl_token::state::Account {
             mint: USDC,
             owner: book_pda,
-            amount: 1000000 * 100,
+            amount: 0,
             state: anchor_spl::token::spl_token::state::AccountState::Initialized,
             ..Default::default()
         };
@@ -488,6 +554,31 @@ mod test {
             },
         );
 
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() - 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
         let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
 
         let rb = RequestBuilder::from(
@@ -499,14 +590,21 @@ mod test {
         );
         let instructions = rb
             .signer(&bettor)
-            .accounts(crate::accounts::BookBettorCancelDisputeAccounts {
+            .accounts(crate::accounts::MultiBookBettorPlaceBetAccounts {
                 bettor: bettor.pubkey(),
                 bettor_token_account: bettor_ata,
                 book_pda,
+                game_pda,
                 book_ata,
                 token_program: anchor_spl::token::ID,
+                system_program: system_program::id(),
+            })
+            .args(crate::instruction::MultiBookBettorPlaceBet {
+                outcome: 2,
+                odds: 1200,
+                wager: 1000000 * 20,
+                bet_direction: BetDirection::Against,
             })
-            .args(crate::instruction::BookBettorCancelDispute)
             .instructions()
             .unwrap();
         let tx = Transaction::new_signed_with_payer(
@@ -516,19 +614,5 @@ mod test {
             recent_blockhash,
         );
         banks_client.process_transaction(tx).await.unwrap();
-
-        // the dispute stake should be returned to the bettor token account
-        let bettor_token_account_state: anchor_spl::token::spl_token::state::Account =
-            banks_client.get_packed_account_data(bettor_ata).await.unwrap();
-        assert_eq!(bettor_token_account_state.amount, 1000000 * 120);
-        // the book pda should be updated
-        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
-        let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
-        assert_eq!(book_state.total_dispute_stake, 0);
-        assert_eq!(book_state.positions[&bettor.pubkey()].dispute_stake, 0);
-        // the dispute stake should be transferred from the book ata
-        let book_ata_account_state: anchor_spl::token::spl_token::state::Account =
-            banks_client.get_packed_account_data(book_ata).await.unwrap();
-        assert_eq!(book_ata_account_state.amount, 1000000 * 80);
     }
 }
@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{MultiBetOutcome, MultiBook},
+};
+
+#[derive(Accounts)]
+pub struct MultiBookExpireDisputeAccounts<'info> {
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+}
+
+// anyone can cancel the book once the council missed the deadline to resolve the dispute, the bettors are refunded and
+// every oracle and dispute stake is returned
+pub fn multi_book_expire_dispute(ctx: Context<MultiBookExpireDisputeAccounts>) -> Result<()> {
+    // check if in dispute
+    require!(ctx.accounts.book_pda.total_dispute_stake > 0, BettingError::NoAuthority);
+    // check if the council has not resolved it yet
+    require!(
+        ctx.accounts.book_pda.dispute_resolution_result.is_none(),
+        BettingError::NoAuthority
+    );
+    // must have passed the resolution deadline
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        matches!(ctx.accounts.book_pda.resolution_deadline(), Some(t) if t < now),
+        BettingError::NotInWindow
+    );
+    // update book pda
+    ctx.accounts.book_pda.dispute_resolution_result = Some(MultiBetOutcome::Cancel);
+    ctx.accounts.book_pda.dispute_timed_out = true;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::{BETTOR_DISPUTE_WINDOW, COUNCIL_RESOLUTION_WINDOW},
+        state::{AggregationRule, BookParams, MultiBetOutcome, MultiBetType, MultiBook, OutcomeBets, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_expire_dispute_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 1000000 * 10,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - COUNCIL_RESOLUTION_WINDOW - 30),
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(0)),
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::MultiBookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::MultiBookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the book pda should be updated
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.final_outcome(), Some(MultiBetOutcome::Cancel));
+        assert!(book_state.dispute_timed_out);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_multi_book_expire_dispute_err_deadline_not_passed() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 1000000 * 10,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - COUNCIL_RESOLUTION_WINDOW + 30),
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(0)),
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::MultiBookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::MultiBookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6000)")]
+    async fn test_multi_book_expire_dispute_err_already_resolved() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 1000000 * 10,
+            total_oracle_stake: 0,
+            dispute_resolution_result: Some(MultiBetOutcome::Winner(1)),
+            dispute_timed_out: false,
+            concluded_at: Some(chrono::Utc::now().timestamp() - BETTOR_DISPUTE_WINDOW - COUNCIL_RESOLUTION_WINDOW - 30),
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(0)),
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::MultiBookExpireDisputeAccounts { book_pda })
+            .args(crate::instruction::MultiBookExpireDispute {})
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
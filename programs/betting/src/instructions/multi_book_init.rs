@@ -0,0 +1,572 @@
+use std::collections::BTreeMap;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    mint::USDC,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::{
+    error::BettingError,
+    state::{AggregationRule, BookParams, Config, Game, MultiBetType, MultiBook, OutcomeBets, Quorum, UserAccount},
+};
+
+#[derive(Accounts)]
+#[instruction(bet_type:MultiBetType,outcomes_count:u8)]
+pub struct MultiBookInitAccounts<'info> {
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    #[account(mut,seeds=[b"UserAccount".as_ref(),initiator.key().as_ref()],bump)]
+    pub initiator_user_account: Account<'info, UserAccount>,
+    #[account(mut,seeds=[b"Game".as_ref(),&game_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+    #[account(init,payer=initiator,space=MultiBook::init_space(outcomes_count),seeds=[b"MultiBook".as_ref(),&game_pda.game_id.to_le_bytes(),bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    #[account(init,payer=initiator,associated_token::mint=usdc_mint,associated_token::authority=book_pda)]
+    pub book_ata: Account<'info, TokenAccount>,
+    #[account(address=USDC)]
+    pub usdc_mint: Account<'info, Mint>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn multi_book_init(
+    ctx: Context<MultiBookInitAccounts>,
+    bet_type: MultiBetType,
+    outcomes_count: u8,
+    quorum: Quorum,
+) -> Result<()> {
+    // check outcomes
+    require!(outcomes_count >= 2, BettingError::InvalidOutcome);
+    // check quorum, the outcomes of a multi book have no order to take the median of
+    require!(
+        quorum.is_valid() && quorum.rule != AggregationRule::Median,
+        BettingError::InvalidQuorum
+    );
+    // update initiator user account
+    ctx.accounts.initiator_user_account.books_initialized += 1;
+    // update game pda
+    ctx.accounts.game_pda.books_count += 1;
+    // init book pda
+    ctx.accounts.book_pda.set_inner(MultiBook {
+        game_id: ctx.accounts.game_pda.game_id,
+        initiator: ctx.accounts.initiator.key(),
+        bet_type,
+        outcomes_count,
+        bets_count: 0,
+        wager_total: 0,
+        dealt_wager: 0,
+        total_dispute_stake: 0,
+        total_oracle_stake: 0,
+        dispute_resolution_result: None,
+        dispute_timed_out: false,
+        concluded_at: None,
+        aggregated_oracle_outcome: None,
+        reveal_starts_at: None,
+        params: BookParams::from_config(&ctx.accounts.config_pda),
+        quorum,
+        oracles: BTreeMap::new(),
+        outcomes: vec![OutcomeBets::default(); outcomes_count as usize],
+        positions: BTreeMap::new(),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+        str::FromStr,
+    };
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey, rent::Rent,
+        signature::Keypair, signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::state::{AggregationRule, BookParams, Config, Game, MultiBetType, MultiBook, Quorum, UserAccount};
+
+    #[tokio::test]
+    async fn test_multi_book_init_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::MultiBookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::MultiBookInit {
+                bet_type,
+                outcomes_count: 3,
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the user account should be updated
+        let initiator_user_account = banks_client.get_account(initiator_pda).await.unwrap().unwrap();
+        let initiator_user_account_state =
+            UserAccount::try_deserialize(&mut initiator_user_account.data.as_slice()).unwrap();
+        assert_eq!(initiator_user_account_state.books_initialized, 1);
+        // the game pda account should be updated
+        let game_account = banks_client.get_account(game_pda).await.unwrap().unwrap();
+        let game_state = Game::try_deserialize(&mut game_account.data.as_slice()).unwrap();
+        assert_eq!(game_state.books_count, 1);
+        // the book pda account should be created with a pair of queues for every outcome
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        assert_eq!(book_account.data.len(), MultiBook::init_space(3));
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(book_state.game_id, game_id);
+        assert_eq!(book_state.initiator, initiator.pubkey());
+        assert_eq!(book_state.bet_type, bet_type);
+        assert_eq!(book_state.outcomes_count, 3);
+        assert_eq!(book_state.outcomes.len(), 3);
+        assert!(book_state.outcomes[0].bets_for.is_empty());
+        assert!(book_state.outcomes[0].bets_against.is_empty());
+        assert!(book_state.oracles.is_empty());
+        assert!(book_state.positions.is_empty());
+        assert!(book_state.concluded_at.is_none());
+        assert_eq!(book_state.params, BookParams::from_config(&Config::default()));
+        assert_eq!(book_state.quorum.min_oracles, 3);
+        assert_eq!(
+            book_state.quorum.rule,
+            AggregationRule::Supermajority { threshold: 6000 }
+        );
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6010)")]
+    async fn test_multi_book_init_err_single_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::MultiBookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::MultiBookInit {
+                bet_type,
+                outcomes_count: 1,
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Supermajority { threshold: 6000 },
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6025)")]
+    async fn test_multi_book_init_err_median_quorum() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let initiator = Keypair::new();
+        program_test.add_account(
+            initiator.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let (initiator_pda, _) =
+            Pubkey::find_program_address(&[b"UserAccount".as_ref(), initiator.pubkey().as_ref()], &program_id);
+        let initiator_pda_state = UserAccount {
+            authority: initiator.pubkey(),
+            books_initialized: 0,
+            books_oracled: VecDeque::new(),
+            books_bet_on: VecDeque::new(),
+        };
+        let mut initiator_pda_data: Vec<u8> = Vec::new();
+        initiator_pda_state.try_serialize(&mut initiator_pda_data).unwrap();
+        program_test.add_account(
+            initiator_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(initiator_pda_state.current_space()),
+                data: initiator_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+
+        let book_ata = anchor_spl::associated_token::get_associated_token_address(&book_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&initiator)
+            .accounts(crate::accounts::MultiBookInitAccounts {
+                initiator: initiator.pubkey(),
+                initiator_user_account: initiator_pda,
+                game_pda,
+                book_pda,
+                book_ata,
+                usdc_mint: USDC,
+                config_pda,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .args(crate::instruction::MultiBookInit {
+                bet_type,
+                outcomes_count: 3,
+                quorum: Quorum {
+                    min_oracles: 3,
+                    min_stake_ratio: 1000,
+                    rule: AggregationRule::Median,
+                },
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &initiator],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
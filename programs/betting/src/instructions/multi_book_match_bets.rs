@@ -0,0 +1,357 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    events::BetsMatched,
+    state::{Game, MultiBook},
+};
+
+#[derive(Accounts)]
+pub struct MultiBookMatchBetsAccounts<'info> {
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+    #[account(seeds=[b"Game".as_ref(),&book_pda.game_id.to_le_bytes()],bump)]
+    pub game_pda: Account<'info, Game>,
+}
+
+pub fn multi_book_match_bets(ctx: Context<MultiBookMatchBetsAccounts>, outcome: u8, max_matches: u32) -> Result<()> {
+    // check outcome
+    require!(
+        outcome < ctx.accounts.book_pda.outcomes_count,
+        BettingError::InvalidOutcome
+    );
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // multi-books close at kickoff
+    require!(now < ctx.accounts.game_pda.kickoff, BettingError::NotInWindow);
+    // match bets, a deep book is matched across several transactions
+    let (matches_count, has_more) = ctx.accounts.book_pda.match_bets(outcome, max_matches)?;
+
+    emit!(BetsMatched {
+        book: ctx.accounts.book_pda.key(),
+        matches_count,
+        has_more,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+    };
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, pubkey::Pubkey, rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{
+        AggregationRule, Bet, BookParams, Game, MultiBetType, MultiBook, MultiPosition, OutcomeBets, Quorum,
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_match_bets_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 2,
+            wager_total: 1000000 * 10 + 1000000 * 50,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![
+                OutcomeBets::default(),
+                OutcomeBets {
+                    payout_total: 0,
+                    bets_for: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xa0, 0x0f, 0x00, 0x00]),
+                        bettor: bettor_for,
+                        wager: 1000000 * 10,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                    bets_against: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x01, 0x00, 0x00, 0x00, 0xe2, 0x04, 0x00, 0x00]),
+                        bettor: bettor_against,
+                        wager: 1000000 * 50,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                },
+                OutcomeBets::default(),
+            ],
+            positions: BTreeMap::from([
+                (
+                    bettor_for,
+                    MultiPosition {
+                        active_bets_count: 1,
+                        bets_count: 1,
+                        wager: 1000000 * 10,
+                        ..MultiPosition::new(3)
+                    },
+                ),
+                (
+                    bettor_against,
+                    MultiPosition {
+                        active_bets_count: 1,
+                        bets_count: 1,
+                        wager: 1000000 * 50,
+                        ..MultiPosition::new(3)
+                    },
+                ),
+            ]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::MultiBookMatchBetsAccounts { book_pda, game_pda })
+            .args(crate::instruction::MultiBookMatchBets {
+                outcome: 1,
+                max_matches: 10,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the bet for at 4.0 is filled completely, the bet against at 1.25 deals the other 30 of the payout of 40
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(book_state.outcomes[1].bets_for.is_empty());
+        assert_eq!(book_state.outcomes[1].bets_against[0].wager, 1000000 * 20);
+        assert_eq!(book_state.outcomes[1].payout_total, 1000000 * 40);
+        assert_eq!(book_state.dealt_wager, 1000000 * 40);
+        assert_eq!(book_state.positions[&bettor_for].payouts_for, vec![0, 1000000 * 40, 0]);
+        assert_eq!(book_state.positions[&bettor_for].active_bets_count, 0);
+        assert_eq!(
+            book_state.positions[&bettor_against].payouts_against,
+            vec![0, 1000000 * 40, 0]
+        );
+        assert_eq!(book_state.positions[&bettor_against].dealt_wager, 1000000 * 30);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6010)")]
+    async fn test_multi_book_match_bets_err_invalid_outcome() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 2,
+            wager_total: 1000000 * 10 + 1000000 * 50,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![
+                OutcomeBets::default(),
+                OutcomeBets {
+                    payout_total: 0,
+                    bets_for: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0xa0, 0x0f, 0x00, 0x00]),
+                        bettor: bettor_for,
+                        wager: 1000000 * 10,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                    bets_against: VecDeque::from([Bet {
+                        id: u64::from_le_bytes([0x01, 0x00, 0x00, 0x00, 0xe2, 0x04, 0x00, 0x00]),
+                        bettor: bettor_against,
+                        wager: 1000000 * 50,
+                        placed_at: 0,
+                        expires_at: 0,
+                    }]),
+                },
+                OutcomeBets::default(),
+            ],
+            positions: BTreeMap::from([
+                (
+                    bettor_for,
+                    MultiPosition {
+                        active_bets_count: 1,
+                        bets_count: 1,
+                        wager: 1000000 * 10,
+                        ..MultiPosition::new(3)
+                    },
+                ),
+                (
+                    bettor_against,
+                    MultiPosition {
+                        active_bets_count: 1,
+                        bets_count: 1,
+                        wager: 1000000 * 50,
+                        ..MultiPosition::new(3)
+                    },
+                ),
+            ]),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 1,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::MultiBookMatchBetsAccounts { book_pda, game_pda })
+            .args(crate::instruction::MultiBookMatchBets {
+                outcome: 3,
+                max_matches: 10,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
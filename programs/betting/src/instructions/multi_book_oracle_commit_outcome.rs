@@ -0,0 +1,507 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ORACLE_COMMIT_WINDOW, ORACLE_REVEAL_WINDOW},
+    error::BettingError,
+    state::MultiBook,
+};
+
+#[derive(Accounts)]
+pub struct MultiBookOracleCommitOutcomeAccounts<'info> {
+    pub oracle: Signer<'info>,
+    #[account(mut,seeds=[b"MultiBook".as_ref(),&book_pda.game_id.to_le_bytes(),book_pda.bet_type.try_to_vec().unwrap().as_slice()],bump)]
+    pub book_pda: Account<'info, MultiBook>,
+}
+
+// see book_oracle_commit_outcome, the commitment is MultiOracle::commitment
+pub fn multi_book_oracle_commit_outcome(
+    ctx: Context<MultiBookOracleCommitOutcomeAccounts>,
+    commitment: [u8; 32],
+) -> Result<()> {
+    // check oracle update window
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.book_pda.concluded_at.is_none()
+            || ctx.accounts.book_pda.concluded_at.unwrap() + ctx.accounts.book_pda.params.oracle_update_window > now,
+        BettingError::NotInWindow
+    );
+    // check commit window, another round starts if the reveal phase ended without a quorum
+    match ctx.accounts.book_pda.reveal_starts_at {
+        Some(t) if t > now => {}
+        Some(t) if t + ORACLE_REVEAL_WINDOW <= now && ctx.accounts.book_pda.aggregated_oracle_outcome.is_none() => {
+            ctx.accounts.book_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+        Some(_) => {
+            return err!(BettingError::NotInWindow);
+        }
+        None => {
+            ctx.accounts.book_pda.reveal_starts_at = Some(now + ORACLE_COMMIT_WINDOW);
+        }
+    }
+    // update oracle
+    match ctx.accounts.book_pda.oracles.get_mut(ctx.accounts.oracle.key) {
+        Some(o) => {
+            // an outcome can only be revealed once
+            require!(!o.revealed, BettingError::NotInWindow);
+            o.commitment = Some(commitment);
+        }
+        None => {
+            return err!(BettingError::UserDidNotOptIn);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent, signature::Keypair,
+        signer::Signer, transaction::Transaction,
+    };
+
+    use crate::state::{
+        AggregationRule, BookParams, MultiBetOutcome, MultiBetType, MultiBook, MultiOracle, OutcomeBets, Quorum,
+    };
+
+    #[tokio::test]
+    async fn test_multi_book_oracle_commit_outcome_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 1000000 * 100 * 1,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::from([(
+                oracle.pubkey(),
+                MultiOracle {
+                    stake: 1000000 * 100,
+                    outcome: None,
+                    commitment: None,
+                    revealed: false,
+                },
+            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::MultiBookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::MultiBookOracleCommitOutcome {
+                commitment: MultiOracle::commitment(
+                    &book_pda,
+                    &oracle.pubkey(),
+                    Some(MultiBetOutcome::Winner(2)),
+                    &[1; 32],
+                ),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the commit phase should be started and the outcome should stay hidden
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert_eq!(
+            book_state.oracles[&oracle.pubkey()].commitment,
+            Some(MultiOracle::commitment(
+                &book_pda,
+                &oracle.pubkey(),
+                Some(MultiBetOutcome::Winner(2)),
+                &[1; 32]
+            ))
+        );
+        assert!(book_state.oracles[&oracle.pubkey()].outcome.is_none());
+        assert!(book_state.reveal_starts_at.is_some());
+        assert!(book_state.aggregated_oracle_outcome.is_none());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_multi_book_oracle_commit_outcome_err_commit_phase_ended() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 1000000 * 100 * 1,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: Some(chrono::Utc::now().timestamp() - 60),
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::from([(
+                oracle.pubkey(),
+                MultiOracle {
+                    stake: 1000000 * 100,
+                    outcome: None,
+                    commitment: None,
+                    revealed: false,
+                },
+            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::MultiBookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::MultiBookOracleCommitOutcome {
+                commitment: MultiOracle::commitment(
+                    &book_pda,
+                    &oracle.pubkey(),
+                    Some(MultiBetOutcome::Winner(2)),
+                    &[1; 32],
+                ),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_multi_book_oracle_commit_outcome_restart() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 1000000 * 100 * 1,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: Some(0),
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::from([(
+                oracle.pubkey(),
+                MultiOracle {
+                    stake: 1000000 * 100,
+                    outcome: None,
+                    commitment: None,
+                    revealed: false,
+                },
+            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::MultiBookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::MultiBookOracleCommitOutcome {
+                commitment: MultiOracle::commitment(
+                    &book_pda,
+                    &oracle.pubkey(),
+                    Some(MultiBetOutcome::Winner(2)),
+                    &[1; 32],
+                ),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // a new round should be started since the last one ended without a quorum
+        let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+        let book_state = MultiBook::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+        assert!(book_state.reveal_starts_at.unwrap() > chrono::Utc::now().timestamp());
+        assert!(book_state.oracles[&oracle.pubkey()].commitment.is_some());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6004)")]
+    async fn test_multi_book_oracle_commit_outcome_err_not_opted_in() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let oracle = Keypair::new();
+        program_test.add_account(
+            oracle.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let game_id = 1_u32;
+        let bet_type = MultiBetType::CorrectScore;
+        let (book_pda, _) = Pubkey::find_program_address(
+            &[
+                b"MultiBook".as_ref(),
+                &game_id.to_le_bytes(),
+                bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book_pda_state = MultiBook {
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bet_type,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 1000000 * 100 * 1,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::from([(
+                Pubkey::new_unique(),
+                MultiOracle {
+                    stake: 1000000 * 100,
+                    outcome: None,
+                    commitment: None,
+                    revealed: false,
+                },
+            )]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_pda_data: Vec<u8> = Vec::new();
+        book_pda_state.try_serialize(&mut book_pda_data).unwrap();
+        program_test.add_account(
+            book_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book_pda_state.current_space()),
+                data: book_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&oracle)
+            .accounts(crate::accounts::MultiBookOracleCommitOutcomeAccounts {
+                oracle: oracle.pubkey(),
+                book_pda,
+            })
+            .args(crate::instruction::MultiBookOracleCommitOutcome {
+                commitment: MultiOracle::commitment(
+                    &book_pda,
+                    &oracle.pubkey(),
+                    Some(MultiBetOutcome::Winner(2)),
+                    &[1; 32],
+                ),
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &oracle],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
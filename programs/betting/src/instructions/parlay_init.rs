@@ -0,0 +1,908 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    mint::USDC,
+    token::{Mint, Token, TokenAccount},
+};
+
+use crate::{
+    constants::MAX_PARLAY_LEGS,
+    error::BettingError,
+    state::{Bet, Config, Parlay, ParlayLeg},
+};
+
+#[derive(Accounts)]
+#[instruction(parlay_id:u64,legs:Vec<ParlayLeg>)]
+pub struct ParlayInitAccounts<'info> {
+    #[account(mut)]
+    pub backer: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=backer)]
+    pub backer_token_account: Account<'info, TokenAccount>,
+    #[account(init,payer=backer,space=Parlay::space(legs.len()),seeds=[b"Parlay".as_ref(),backer.key().as_ref(),&parlay_id.to_le_bytes()],bump)]
+    pub parlay_pda: Account<'info, Parlay>,
+    #[account(init,payer=backer,associated_token::mint=usdc_mint,associated_token::authority=parlay_pda)]
+    pub parlay_ata: Account<'info, TokenAccount>,
+    #[account(address=USDC)]
+    pub usdc_mint: Account<'info, Mint>,
+    #[account(seeds=[b"Config".as_ref()],bump)]
+    pub config_pda: Account<'info, Config>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// the leg books are passed as writable remaining accounts in the order of the legs, followed by their games in the same
+// order
+pub fn parlay_init(ctx: Context<ParlayInitAccounts>, parlay_id: u64, legs: Vec<ParlayLeg>, wager: u64) -> Result<()> {
+    // check condition
+    require!(
+        wager >= ctx.accounts.config_pda.min_bet_amount,
+        BettingError::MinTokenAmountNotMet
+    );
+    require!(
+        !legs.is_empty() && legs.len() <= MAX_PARLAY_LEGS,
+        BettingError::InvalidLegs
+    );
+    for (i, leg) in legs.iter().enumerate() {
+        require!(leg.odds as u64 > Bet::ODDS_PRECISION, BettingError::InvalidOdds);
+        // at most one leg per book
+        require!(legs[..i].iter().all(|l| l.book != leg.book), BettingError::InvalidLegs);
+    }
+    // init parlay pda
+    ctx.accounts.parlay_pda.set_inner(Parlay {
+        parlay_id,
+        backer: ctx.accounts.backer.key(),
+        layer: None,
+        wager,
+        liability: 0,
+        bettor_payout_rate: ctx.accounts.config_pda.bettor_payout_rate,
+        legs,
+    });
+    let max_payout = ctx.accounts.parlay_pda.max_payout()?;
+    ctx.accounts.parlay_pda.liability = max_payout - wager;
+    // check window
+    let now = Clock::get()?.unix_timestamp;
+    let (book_accounts, game_accounts) = ctx
+        .remaining_accounts
+        .split_at(ctx.accounts.parlay_pda.legs.len().min(ctx.remaining_accounts.len()));
+    let books = ctx.accounts.parlay_pda.leg_books(book_accounts)?;
+    let games = Parlay::leg_games(&books, game_accounts)?;
+    Parlay::check_legs_open(&books, &games, now)?;
+    // update leg books
+    for mut book in books {
+        book.parlays_count += 1;
+        book.exit(ctx.program_id)?;
+    }
+    // transfer wager
+    let wager_transfer_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.backer_token_account.to_account_info(),
+            to: ctx.accounts.parlay_ata.to_account_info(),
+            authority: ctx.accounts.backer.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(wager_transfer_cpi_context, wager)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc, str::FromStr};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, system_program, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::BETTOR_PAYOUT_RATE,
+        state::{BetDirection, BetType, Book, BookParams, Config, Game, Parlay, ParlayLeg, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_parlay_init_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let backer = Keypair::new();
+        program_test.add_account(
+            backer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let backer_ata = anchor_spl::associated_token::get_associated_token_address(&backer.pubkey(), &USDC);
+        let backer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: backer.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut backer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(backer_ata_state, &mut backer_ata_data).unwrap();
+        program_test.add_account(
+            backer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(backer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[b"Parlay".as_ref(), backer.pubkey().as_ref(), &parlay_id.to_le_bytes()],
+            &program_id,
+        );
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&backer)
+            .accounts(crate::accounts::ParlayInitAccounts {
+                config_pda,
+                backer: backer.pubkey(),
+                backer_token_account: backer_ata,
+                parlay_pda,
+                parlay_ata,
+                usdc_mint: USDC,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .args(crate::instruction::ParlayInit {
+                parlay_id,
+                legs: vec![
+                    ParlayLeg {
+                        book: book1_pda,
+                        bet_direction: BetDirection::For,
+                        odds: 1500,
+                    },
+                    ParlayLeg {
+                        book: book2_pda,
+                        bet_direction: BetDirection::Against,
+                        odds: 2000,
+                    },
+                ],
+                wager: 1000000 * 10,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &backer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the parlay pda account should be created with the liability of the combined odds
+        let parlay_account = banks_client.get_account(parlay_pda).await.unwrap().unwrap();
+        assert_eq!(parlay_account.data.len(), Parlay::space(2));
+        let parlay_state = Parlay::try_deserialize(&mut parlay_account.data.as_slice()).unwrap();
+        assert_eq!(parlay_state.backer, backer.pubkey());
+        assert_eq!(parlay_state.layer, None);
+        assert_eq!(parlay_state.wager, 1000000 * 10);
+        assert_eq!(parlay_state.liability, 1000000 * 20);
+        assert_eq!(parlay_state.bettor_payout_rate, BETTOR_PAYOUT_RATE);
+        assert_eq!(parlay_state.legs.len(), 2);
+        // the wager should be transferred to the parlay ata
+        let parlay_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(parlay_ata).await.unwrap();
+        assert_eq!(parlay_ata_state.amount, 1000000 * 10);
+        let backer_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(backer_ata).await.unwrap();
+        assert_eq!(backer_ata_state.amount, 1000000 * 90);
+        // the leg books should be updated
+        for book_pda in [book1_pda, book2_pda] {
+            let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+            let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+            assert_eq!(book_state.parlays_count, 1);
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6011)")]
+    async fn test_parlay_init_err_duplicate_legs() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let backer = Keypair::new();
+        program_test.add_account(
+            backer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let backer_ata = anchor_spl::associated_token::get_associated_token_address(&backer.pubkey(), &USDC);
+        let backer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: backer.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut backer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(backer_ata_state, &mut backer_ata_data).unwrap();
+        program_test.add_account(
+            backer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(backer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[b"Parlay".as_ref(), backer.pubkey().as_ref(), &parlay_id.to_le_bytes()],
+            &program_id,
+        );
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&backer)
+            .accounts(crate::accounts::ParlayInitAccounts {
+                config_pda,
+                backer: backer.pubkey(),
+                backer_token_account: backer_ata,
+                parlay_pda,
+                parlay_ata,
+                usdc_mint: USDC,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .args(crate::instruction::ParlayInit {
+                parlay_id,
+                legs: vec![
+                    ParlayLeg {
+                        book: book1_pda,
+                        bet_direction: BetDirection::For,
+                        odds: 1500,
+                    },
+                    ParlayLeg {
+                        book: book1_pda,
+                        bet_direction: BetDirection::Against,
+                        odds: 2000,
+                    },
+                ],
+                wager: 1000000 * 10,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &backer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6006)")]
+    async fn test_parlay_init_err_after_kickoff() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let backer = Keypair::new();
+        program_test.add_account(
+            backer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let backer_ata = anchor_spl::associated_token::get_associated_token_address(&backer.pubkey(), &USDC);
+        let backer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: backer.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut backer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(backer_ata_state, &mut backer_ata_data).unwrap();
+        program_test.add_account(
+            backer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(backer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[b"Parlay".as_ref(), backer.pubkey().as_ref(), &parlay_id.to_le_bytes()],
+            &program_id,
+        );
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+
+        let usdc_mint_state = anchor_spl::token::spl_token::state::Mint {
+            supply: u64::MAX,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        let mut usdc_mint_data = [0_u8; 82];
+        anchor_spl::token::spl_token::state::Mint::pack(usdc_mint_state, &mut usdc_mint_data).unwrap();
+        program_test.add_account(
+            USDC,
+            Account {
+                lamports: Rent::default().minimum_balance(82),
+                data: Vec::from(usdc_mint_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (config_pda, _) = Pubkey::find_program_address(&[b"Config".as_ref()], &program_id);
+        let mut config_pda_data: Vec<u8> = Vec::new();
+        Config::default().try_serialize(&mut config_pda_data).unwrap();
+        program_test.add_account(
+            config_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Config::INIT_SPACE),
+                data: config_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&backer)
+            .accounts(crate::accounts::ParlayInitAccounts {
+                config_pda,
+                backer: backer.pubkey(),
+                backer_token_account: backer_ata,
+                parlay_pda,
+                parlay_ata,
+                usdc_mint: USDC,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                system_program: system_program::id(),
+                rent: Pubkey::from_str("SysvarRent111111111111111111111111111111111").unwrap(),
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .args(crate::instruction::ParlayInit {
+                parlay_id,
+                legs: vec![
+                    ParlayLeg {
+                        book: book1_pda,
+                        bet_direction: BetDirection::For,
+                        odds: 1500,
+                    },
+                    ParlayLeg {
+                        book: book2_pda,
+                        bet_direction: BetDirection::Against,
+                        odds: 2000,
+                    },
+                ],
+                wager: 1000000 * 10,
+            })
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &backer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
@@ -0,0 +1,604 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{error::BettingError, state::Parlay};
+
+#[derive(Accounts)]
+pub struct ParlayMatchAccounts<'info> {
+    pub layer: Signer<'info>,
+    #[account(mut,token::mint=USDC,token::authority=layer)]
+    pub layer_token_account: Account<'info, TokenAccount>,
+    #[account(mut,seeds=[b"Parlay".as_ref(),parlay_pda.backer.as_ref(),&parlay_pda.parlay_id.to_le_bytes()],bump)]
+    pub parlay_pda: Account<'info, Parlay>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=parlay_pda)]
+    pub parlay_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// the leg books are passed as remaining accounts in the order of the legs, followed by their games in the same order
+pub fn parlay_match(ctx: Context<ParlayMatchAccounts>) -> Result<()> {
+    // check condition
+    require!(
+        ctx.accounts.parlay_pda.layer.is_none(),
+        BettingError::ParlayAlreadyMatched
+    );
+    // check window, a layer must not match a parlay whose legs are under way
+    let now = Clock::get()?.unix_timestamp;
+    let (book_accounts, game_accounts) = ctx
+        .remaining_accounts
+        .split_at(ctx.accounts.parlay_pda.legs.len().min(ctx.remaining_accounts.len()));
+    let books = ctx.accounts.parlay_pda.leg_books(book_accounts)?;
+    let games = Parlay::leg_games(&books, game_accounts)?;
+    Parlay::check_legs_open(&books, &games, now)?;
+    // transfer liability
+    let liability_transfer_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::Transfer {
+            from: ctx.accounts.layer_token_account.to_account_info(),
+            to: ctx.accounts.parlay_ata.to_account_info(),
+            authority: ctx.accounts.layer.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(liability_transfer_cpi_context, ctx.accounts.parlay_pda.liability)?;
+    // update parlay pda
+    ctx.accounts.parlay_pda.layer = Some(ctx.accounts.layer.key());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::BTreeMap, rc::Rc};
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::BETTOR_PAYOUT_RATE,
+        state::{BetDirection, BetType, Book, BookParams, Game, Parlay, ParlayLeg, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_parlay_match_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let layer = Keypair::new();
+        program_test.add_account(
+            layer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let layer_ata = anchor_spl::associated_token::get_associated_token_address(&layer.pubkey(), &USDC);
+        let layer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: layer.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut layer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(layer_ata_state, &mut layer_ata_data).unwrap();
+        program_test.add_account(
+            layer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(layer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Parlay".as_ref(),
+                Pubkey::new_unique().as_ref(),
+                &parlay_id.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let parlay_pda_state = Parlay {
+            parlay_id,
+            backer: Pubkey::new_unique(),
+            layer: None,
+            wager: 1000000 * 10,
+            liability: 1000000 * 20,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            legs: vec![
+                ParlayLeg {
+                    book: book1_pda,
+                    bet_direction: BetDirection::For,
+                    odds: 1500,
+                },
+                ParlayLeg {
+                    book: book2_pda,
+                    bet_direction: BetDirection::Against,
+                    odds: 2000,
+                },
+            ],
+        };
+        let mut parlay_pda_data: Vec<u8> = Vec::new();
+        parlay_pda_state.try_serialize(&mut parlay_pda_data).unwrap();
+        program_test.add_account(
+            parlay_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Parlay::space(2)),
+                data: parlay_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+        let parlay_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: parlay_pda,
+            amount: 1000000 * 10,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut parlay_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(parlay_ata_state, &mut parlay_ata_data).unwrap();
+        program_test.add_account(
+            parlay_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(parlay_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&layer)
+            .accounts(crate::accounts::ParlayMatchAccounts {
+                layer: layer.pubkey(),
+                layer_token_account: layer_ata,
+                parlay_pda,
+                parlay_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .args(crate::instruction::ParlayMatch)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &layer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the parlay pda account should be matched
+        let parlay_account = banks_client.get_account(parlay_pda).await.unwrap().unwrap();
+        let parlay_state = Parlay::try_deserialize(&mut parlay_account.data.as_slice()).unwrap();
+        assert_eq!(parlay_state.layer, Some(layer.pubkey()));
+        // the liability should be transferred to the parlay ata
+        let parlay_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(parlay_ata).await.unwrap();
+        assert_eq!(parlay_ata_state.amount, 1000000 * 30);
+        let layer_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(layer_ata).await.unwrap();
+        assert_eq!(layer_ata_state.amount, 1000000 * 80);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6012)")]
+    async fn test_parlay_match_err_already_matched() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let layer = Keypair::new();
+        program_test.add_account(
+            layer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let layer_ata = anchor_spl::associated_token::get_associated_token_address(&layer.pubkey(), &USDC);
+        let layer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: layer.pubkey(),
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut layer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(layer_ata_state, &mut layer_ata_data).unwrap();
+        program_test.add_account(
+            layer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(layer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+        let (game_pda, _) = Pubkey::find_program_address(&[b"Game".as_ref(), &game_id.to_le_bytes()], &program_id);
+        let game_pda_state = Game {
+            game_id,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: chrono::Utc::now().timestamp() + 60 * 60,
+            books_count: 2,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_pda_data: Vec<u8> = Vec::new();
+        game_pda_state.try_serialize(&mut game_pda_data).unwrap();
+        program_test.add_account(
+            game_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Game::INIT_SPACE),
+                data: game_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: None,
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Parlay".as_ref(),
+                Pubkey::new_unique().as_ref(),
+                &parlay_id.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        let parlay_pda_state = Parlay {
+            parlay_id,
+            backer: Pubkey::new_unique(),
+            layer: Some(Pubkey::new_unique()),
+            wager: 1000000 * 10,
+            liability: 1000000 * 20,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            legs: vec![
+                ParlayLeg {
+                    book: book1_pda,
+                    bet_direction: BetDirection::For,
+                    odds: 1500,
+                },
+                ParlayLeg {
+                    book: book2_pda,
+                    bet_direction: BetDirection::Against,
+                    odds: 2000,
+                },
+            ],
+        };
+        let mut parlay_pda_data: Vec<u8> = Vec::new();
+        parlay_pda_state.try_serialize(&mut parlay_pda_data).unwrap();
+        program_test.add_account(
+            parlay_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Parlay::space(2)),
+                data: parlay_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+        let parlay_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: parlay_pda,
+            amount: 1000000 * 10,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut parlay_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(parlay_ata_state, &mut parlay_ata_data).unwrap();
+        program_test.add_account(
+            parlay_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(parlay_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .signer(&layer)
+            .accounts(crate::accounts::ParlayMatchAccounts {
+                layer: layer.pubkey(),
+                layer_token_account: layer_ata,
+                parlay_pda,
+                parlay_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .accounts(AccountMeta::new_readonly(game_pda, false))
+            .args(crate::instruction::ParlayMatch)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer, &layer],
+            recent_blockhash,
+        );
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
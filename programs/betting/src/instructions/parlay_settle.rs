@@ -0,0 +1,698 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    mint::USDC,
+    token::{Token, TokenAccount},
+};
+
+use crate::{
+    constants::OPERATOR_TOKEN_ACCOUNT,
+    error::BettingError,
+    state::{BetOutcome, Parlay},
+};
+
+#[derive(Accounts)]
+pub struct ParlaySettleAccounts<'info> {
+    /// CHECK: checked by the parlay pda seeds
+    #[account(mut)]
+    pub backer: UncheckedAccount<'info>,
+    #[account(mut,token::mint=USDC,token::authority=backer)]
+    pub backer_token_account: Account<'info, TokenAccount>,
+    #[account(mut,token::mint=USDC)]
+    pub layer_token_account: Account<'info, TokenAccount>,
+    #[account(mut,token::mint=USDC,address=OPERATOR_TOKEN_ACCOUNT)]
+    pub operator_token_account: Account<'info, TokenAccount>,
+    #[account(mut,close=backer,seeds=[b"Parlay".as_ref(),backer.key().as_ref(),&parlay_pda.parlay_id.to_le_bytes()],bump)]
+    pub parlay_pda: Account<'info, Parlay>,
+    #[account(mut,associated_token::mint=USDC,associated_token::authority=parlay_pda)]
+    pub parlay_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// the leg books are passed as writable remaining accounts in the order of the legs
+pub fn parlay_settle(ctx: Context<ParlaySettleAccounts>) -> Result<()> {
+    // must be matched
+    let layer = match ctx.accounts.parlay_pda.layer {
+        Some(layer) => layer,
+        None => {
+            return err!(BettingError::ParlayNotMatched);
+        }
+    };
+    require_keys_eq!(ctx.accounts.layer_token_account.owner, layer, BettingError::NoAuthority);
+    // every leg must have a final outcome past the dispute window
+    let now = Clock::get()?.unix_timestamp;
+    let mut outcomes = Vec::new();
+    for mut book in ctx.accounts.parlay_pda.leg_books(ctx.remaining_accounts)? {
+        require!(
+            book.concluded_at.is_some()
+                && book.concluded_at.unwrap() + book.params.bettor_dispute_window < now
+                && !book.is_in_dispute(now),
+            BettingError::NotInWindow
+        );
+        match book.final_outcome() {
+            Some(outcome) => outcomes.push(outcome),
+            None => {
+                return err!(BettingError::NoResultYet);
+            }
+        }
+        book.parlays_count -= 1;
+        book.exit(ctx.program_id)?;
+    }
+    // calculate usdc to transfer
+    let parlay = &ctx.accounts.parlay_pda;
+    let pot = parlay.wager + parlay.liability;
+    let (backer_usdc, layer_usdc) = if outcomes.iter().all(|o| *o == BetOutcome::Cancel) {
+        // refund both sides if every leg is void
+        (parlay.wager, parlay.liability)
+    } else {
+        let payout = parlay.payout(&outcomes)?;
+        (
+            payout * parlay.bettor_payout_rate / 10000,
+            (pot - payout) * parlay.bettor_payout_rate / 10000,
+        )
+    };
+    // transfer usdc
+    let parlay_id_bytes = parlay.parlay_id.to_le_bytes();
+    let parlay_pda_signer_seeds = &[
+        b"Parlay".as_ref(),
+        parlay.backer.as_ref(),
+        parlay_id_bytes.as_ref(),
+        &[*ctx.bumps.get("parlay_pda").unwrap()],
+    ];
+    for (to, amount) in [
+        (ctx.accounts.backer_token_account.to_account_info(), backer_usdc),
+        (ctx.accounts.layer_token_account.to_account_info(), layer_usdc),
+        (
+            ctx.accounts.operator_token_account.to_account_info(),
+            pot - backer_usdc - layer_usdc,
+        ),
+    ] {
+        let usdc_transfer_cpi_context = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.parlay_ata.to_account_info(),
+                to,
+                authority: ctx.accounts.parlay_pda.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer(
+            usdc_transfer_cpi_context.with_signer(&[parlay_pda_signer_seeds]),
+            amount,
+        )?;
+    }
+    // close parlay ata
+    let parlay_ata_close_cpi_context = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        anchor_spl::token::CloseAccount {
+            account: ctx.accounts.parlay_ata.to_account_info(),
+            destination: ctx.accounts.backer.to_account_info(),
+            authority: ctx.accounts.parlay_pda.to_account_info(),
+        },
+    );
+    anchor_spl::token::close_account(parlay_ata_close_cpi_context.with_signer(&[parlay_pda_signer_seeds]))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use anchor_client::RequestBuilder;
+    use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorSerialize};
+    use anchor_spl::mint::USDC;
+    use solana_program_test::{tokio, ProgramTest};
+    use solana_sdk::{
+        account::Account, instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack, pubkey::Pubkey,
+        rent::Rent, signature::Keypair, signer::Signer, transaction::Transaction,
+    };
+
+    use crate::{
+        constants::{BETTOR_PAYOUT_RATE, OPERATOR_PUBKEY, OPERATOR_TOKEN_ACCOUNT},
+        state::{BetDirection, BetOutcome, BetType, Book, BookParams, Parlay, ParlayLeg, Quorum},
+    };
+
+    #[tokio::test]
+    async fn test_parlay_settle_success() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let backer = Keypair::new();
+        program_test.add_account(
+            backer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let backer_ata = anchor_spl::associated_token::get_associated_token_address(&backer.pubkey(), &USDC);
+        let backer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: backer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut backer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(backer_ata_state, &mut backer_ata_data).unwrap();
+        program_test.add_account(
+            backer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(backer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let layer = Pubkey::new_unique();
+        let layer_ata = anchor_spl::associated_token::get_associated_token_address(&layer, &USDC);
+        let layer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: layer,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut layer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(layer_ata_state, &mut layer_ata_data).unwrap();
+        program_test.add_account(
+            layer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(layer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let operator_token_account_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: OPERATOR_PUBKEY,
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut operator_token_account_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(
+            operator_token_account_state,
+            &mut operator_token_account_data,
+        )
+        .unwrap();
+        program_test.add_account(
+            OPERATOR_TOKEN_ACCOUNT,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(operator_token_account_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::For),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Cancel),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[b"Parlay".as_ref(), backer.pubkey().as_ref(), &parlay_id.to_le_bytes()],
+            &program_id,
+        );
+        let parlay_pda_state = Parlay {
+            parlay_id,
+            backer: backer.pubkey(),
+            layer: Some(layer),
+            wager: 1000000 * 10,
+            liability: 1000000 * 20,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            legs: vec![
+                ParlayLeg {
+                    book: book1_pda,
+                    bet_direction: BetDirection::For,
+                    odds: 1500,
+                },
+                ParlayLeg {
+                    book: book2_pda,
+                    bet_direction: BetDirection::Against,
+                    odds: 2000,
+                },
+            ],
+        };
+        let mut parlay_pda_data: Vec<u8> = Vec::new();
+        parlay_pda_state.try_serialize(&mut parlay_pda_data).unwrap();
+        program_test.add_account(
+            parlay_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Parlay::space(2)),
+                data: parlay_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+        let parlay_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: parlay_pda,
+            amount: 1000000 * 30,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut parlay_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(parlay_ata_state, &mut parlay_ata_data).unwrap();
+        program_test.add_account(
+            parlay_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(parlay_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::ParlaySettleAccounts {
+                backer: backer.pubkey(),
+                backer_token_account: backer_ata,
+                layer_token_account: layer_ata,
+                operator_token_account: OPERATOR_TOKEN_ACCOUNT,
+                parlay_pda,
+                parlay_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .args(crate::instruction::ParlaySettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        // the parlay pda account and ata should be closed
+        assert!(banks_client.get_account(parlay_pda).await.unwrap().is_none());
+        assert!(banks_client.get_account(parlay_ata).await.unwrap().is_none());
+        // the void leg drops out, the backer wins 1.5 times the wager and the layer keeps the rest of the pot
+        let backer_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(backer_ata).await.unwrap();
+        assert_eq!(backer_ata_state.amount, 1000000 * 15 * 99 / 100);
+        let layer_ata_state: anchor_spl::token::spl_token::state::Account =
+            banks_client.get_packed_account_data(layer_ata).await.unwrap();
+        assert_eq!(layer_ata_state.amount, 1000000 * 15 * 99 / 100);
+        let op_token_account_state: anchor_spl::token::spl_token::state::Account = banks_client
+            .get_packed_account_data(OPERATOR_TOKEN_ACCOUNT)
+            .await
+            .unwrap();
+        assert_eq!(op_token_account_state.amount, 1000000 * 100 + 1000000 * 30 / 100);
+        // the leg books should be updated
+        for book_pda in [book1_pda, book2_pda] {
+            let book_account = banks_client.get_account(book_pda).await.unwrap().unwrap();
+            let book_state = Book::try_deserialize(&mut book_account.data.as_slice()).unwrap();
+            assert_eq!(book_state.parlays_count, 0);
+        }
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Custom(6013)")]
+    async fn test_parlay_settle_err_not_matched() {
+        let program_id = crate::id();
+        let mut program_test = ProgramTest::new("betting", program_id, None);
+
+        let backer = Keypair::new();
+        program_test.add_account(
+            backer.pubkey(),
+            Account {
+                lamports: LAMPORTS_PER_SOL,
+                ..Default::default()
+            },
+        );
+
+        let backer_ata = anchor_spl::associated_token::get_associated_token_address(&backer.pubkey(), &USDC);
+        let backer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: backer.pubkey(),
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut backer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(backer_ata_state, &mut backer_ata_data).unwrap();
+        program_test.add_account(
+            backer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(backer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let layer = Pubkey::new_unique();
+        let layer_ata = anchor_spl::associated_token::get_associated_token_address(&layer, &USDC);
+        let layer_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: layer,
+            amount: 0,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut layer_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(layer_ata_state, &mut layer_ata_data).unwrap();
+        program_test.add_account(
+            layer_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(layer_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let operator_token_account_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: OPERATOR_PUBKEY,
+            amount: 1000000 * 100,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut operator_token_account_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(
+            operator_token_account_state,
+            &mut operator_token_account_data,
+        )
+        .unwrap();
+        program_test.add_account(
+            OPERATOR_TOKEN_ACCOUNT,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(operator_token_account_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let game_id: u32 = 1;
+
+        let book1_bet_type = BetType::One { handicap: 0 };
+        let (book1_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book1_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book1_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::For),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book1_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0, 0, 0, 0, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book1_pda_data: Vec<u8> = Vec::new();
+        book1_pda_state.try_serialize(&mut book1_pda_data).unwrap();
+        program_test.add_account(
+            book1_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book1_pda_state.current_space()),
+                data: book1_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let book2_bet_type = BetType::Two { handicap: 0 };
+        let (book2_pda, _) = Pubkey::find_program_address(
+            &[
+                b"Book".as_ref(),
+                &game_id.to_le_bytes(),
+                book2_bet_type.try_to_vec().unwrap().as_slice(),
+            ],
+            &program_id,
+        );
+        let book2_pda_state = Book {
+            total_oracle_stake: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Cancel),
+            game_id,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: book2_bet_type,
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 1,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+        };
+        let mut book2_pda_data: Vec<u8> = Vec::new();
+        book2_pda_state.try_serialize(&mut book2_pda_data).unwrap();
+        program_test.add_account(
+            book2_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(book2_pda_state.current_space()),
+                data: book2_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_id: u64 = 1;
+        let (parlay_pda, _) = Pubkey::find_program_address(
+            &[b"Parlay".as_ref(), backer.pubkey().as_ref(), &parlay_id.to_le_bytes()],
+            &program_id,
+        );
+        let parlay_pda_state = Parlay {
+            parlay_id,
+            backer: backer.pubkey(),
+            layer: None,
+            wager: 1000000 * 10,
+            liability: 1000000 * 20,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            legs: vec![
+                ParlayLeg {
+                    book: book1_pda,
+                    bet_direction: BetDirection::For,
+                    odds: 1500,
+                },
+                ParlayLeg {
+                    book: book2_pda,
+                    bet_direction: BetDirection::Against,
+                    odds: 2000,
+                },
+            ],
+        };
+        let mut parlay_pda_data: Vec<u8> = Vec::new();
+        parlay_pda_state.try_serialize(&mut parlay_pda_data).unwrap();
+        program_test.add_account(
+            parlay_pda,
+            Account {
+                lamports: Rent::default().minimum_balance(Parlay::space(2)),
+                data: parlay_pda_data,
+                owner: program_id,
+                ..Default::default()
+            },
+        );
+
+        let parlay_ata = anchor_spl::associated_token::get_associated_token_address(&parlay_pda, &USDC);
+        let parlay_ata_state = anchor_spl::token::spl_token::state::Account {
+            mint: USDC,
+            owner: parlay_pda,
+            amount: 1000000 * 30,
+            state: anchor_spl::token::spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        let mut parlay_ata_data = [0_u8; 165];
+        anchor_spl::token::spl_token::state::Account::pack(parlay_ata_state, &mut parlay_ata_data).unwrap();
+        program_test.add_account(
+            parlay_ata,
+            Account {
+                lamports: Rent::default().minimum_balance(165),
+                data: Vec::from(parlay_ata_data),
+                owner: anchor_spl::token::ID,
+                ..Default::default()
+            },
+        );
+
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let rb = RequestBuilder::from(
+            program_id,
+            "",
+            Rc::new(Keypair::new()),
+            None,
+            anchor_client::RequestNamespace::Global,
+        );
+        let instructions = rb
+            .accounts(crate::accounts::ParlaySettleAccounts {
+                backer: backer.pubkey(),
+                backer_token_account: backer_ata,
+                layer_token_account: layer_ata,
+                operator_token_account: OPERATOR_TOKEN_ACCOUNT,
+                parlay_pda,
+                parlay_ata,
+                token_program: anchor_spl::token::ID,
+            })
+            .accounts(AccountMeta::new(book1_pda, false))
+            .accounts(AccountMeta::new(book2_pda, false))
+            .args(crate::instruction::ParlaySettle)
+            .instructions()
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(&instructions, Some(&payer.pubkey()), &[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+    }
+}
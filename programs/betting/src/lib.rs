@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 
@@ -22,6 +23,24 @@ pub mod betting {
     pub fn user_account_shrink(ctx: Context<UserAccountShrinkAccounts>) -> Result<()> {
         instructions::user_account_shrink(ctx)
     }
+    pub fn config_init(ctx: Context<ConfigInitAccounts>, config: Config) -> Result<()> {
+        instructions::config_init(ctx, config)
+    }
+    pub fn config_update(ctx: Context<ConfigUpdateAccounts>, config: Config) -> Result<()> {
+        instructions::config_update(ctx, config)
+    }
+    pub fn council_init(ctx: Context<CouncilInitAccounts>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::council_init(ctx, members, threshold)
+    }
+    pub fn council_propose(ctx: Context<CouncilProposeAccounts>, action: CouncilAction) -> Result<()> {
+        instructions::council_propose(ctx, action)
+    }
+    pub fn council_vote(ctx: Context<CouncilVoteAccounts>) -> Result<()> {
+        instructions::council_vote(ctx)
+    }
+    pub fn council_rotate_members(ctx: Context<CouncilRotateMembersAccounts>) -> Result<()> {
+        instructions::council_rotate_members(ctx)
+    }
     pub fn game_init(
         ctx: Context<GameInitAccounts>,
         game_id: u32,
@@ -35,12 +54,39 @@ pub mod betting {
     pub fn game_close(ctx: Context<GameCloseAccounts>) -> Result<()> {
         instructions::game_close(ctx)
     }
-    pub fn book_init(ctx: Context<BookInitAccounts>, bet_type: BetType) -> Result<()> {
-        instructions::book_init(ctx, bet_type)
+    pub fn game_oracle_commit_score(ctx: Context<GameOracleCommitScoreAccounts>, commitment: [u8; 32]) -> Result<()> {
+        instructions::game_oracle_commit_score(ctx, commitment)
+    }
+    pub fn game_oracle_reveal_score(
+        ctx: Context<GameOracleRevealScoreAccounts>,
+        score: Score,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::game_oracle_reveal_score(ctx, score, salt)
+    }
+    pub fn book_init(
+        ctx: Context<BookInitAccounts>,
+        bet_type: BetType,
+        in_play_bet_delay: Option<i64>,
+        quorum: Quorum,
+        params: Option<BookParams>,
+    ) -> Result<()> {
+        instructions::book_init(ctx, bet_type, in_play_bet_delay, quorum, params)
     }
     pub fn book_close(ctx: Context<BookCloseAccounts>) -> Result<()> {
         instructions::book_close(ctx)
     }
+    pub fn book_page_init(
+        ctx: Context<BookPageInitAccounts>,
+        bet_direction: BetDirection,
+        odds: u32,
+        index: u16,
+    ) -> Result<()> {
+        instructions::book_page_init(ctx, bet_direction, odds, index)
+    }
+    pub fn book_page_close(ctx: Context<BookPageCloseAccounts>) -> Result<()> {
+        instructions::book_page_close(ctx)
+    }
     pub fn book_bettor_opt_int(ctx: Context<BookBettorOptInAccounts>) -> Result<()> {
         instructions::book_bettor_opt_int(ctx)
     }
@@ -49,8 +95,10 @@ pub mod betting {
         odds: u32,
         wager: u64,
         bet_direction: BetDirection,
+        order_type: OrderType,
+        expiry: Option<BetExpiry>,
     ) -> Result<()> {
-        instructions::book_bettor_place_bet(ctx, odds, wager, bet_direction)
+        instructions::book_bettor_place_bet(ctx, odds, wager, bet_direction, order_type, expiry)
     }
     pub fn book_bettor_cancel_bet(
         ctx: Context<BookBettorCancelBetAccounts>,
@@ -59,8 +107,23 @@ pub mod betting {
     ) -> Result<()> {
         instructions::book_bettor_cancel_bet(ctx, bet_id, bet_direction)
     }
-    pub fn book_match_bets(ctx: Context<BookMatchBetsAccounts>) -> Result<()> {
-        instructions::book_match_bets(ctx)
+    pub fn book_bettor_cancel_all_bets(ctx: Context<BookBettorCancelAllBetsAccounts>, filter: BetFilter) -> Result<()> {
+        instructions::book_bettor_cancel_all_bets(ctx, filter)
+    }
+    pub fn book_bettor_amend_bet(
+        ctx: Context<BookBettorAmendBetAccounts>,
+        bet_id: u64,
+        bet_direction: BetDirection,
+        odds: u32,
+        wager: u64,
+    ) -> Result<()> {
+        instructions::book_bettor_amend_bet(ctx, bet_id, bet_direction, odds, wager)
+    }
+    pub fn book_match_bets(ctx: Context<BookMatchBetsAccounts>, max_matches: u32) -> Result<()> {
+        instructions::book_match_bets(ctx, max_matches)
+    }
+    pub fn book_purge_expired_bets(ctx: Context<BookPurgeExpiredBetsAccounts>) -> Result<()> {
+        instructions::book_purge_expired_bets(ctx)
     }
     pub fn book_oracle_opt_in(ctx: Context<BookOracleOptInAccounts>, stake: u64) -> Result<()> {
         instructions::book_oracle_opt_in(ctx, stake)
@@ -68,31 +131,136 @@ pub mod betting {
     pub fn book_oracle_add_stake(ctx: Context<BookOracleAddStakeAccounts>, stake: u64) -> Result<()> {
         instructions::book_oracle_add_stake(ctx, stake)
     }
-    pub fn book_oracle_update_outcome(
-        ctx: Context<BookOracleUpdateOutcomeAccounts>,
-        bet_outcome: Option<BetOutcome>,
+    pub fn book_oracle_commit_outcome(
+        ctx: Context<BookOracleCommitOutcomeAccounts>,
+        commitment: [u8; 32],
     ) -> Result<()> {
-        instructions::book_oracle_update_outcome(ctx, bet_outcome)
+        instructions::book_oracle_commit_outcome(ctx, commitment)
     }
-    pub fn book_bettor_dispute(ctx: Context<BookBettorDisputeAccounts>, stake: u64) -> Result<()> {
-        instructions::book_bettor_dispute(ctx, stake)
+    pub fn book_oracle_reveal_outcome(
+        ctx: Context<BookOracleRevealOutcomeAccounts>,
+        bet_outcome: Option<BetOutcome>,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::book_oracle_reveal_outcome(ctx, bet_outcome, salt)
     }
-    pub fn book_bettor_cancel_dispute(ctx: Context<BookBettorCancelDisputeAccounts>) -> Result<()> {
-        instructions::book_bettor_cancel_dispute(ctx)
+    pub fn book_derive_outcome(ctx: Context<BookDeriveOutcomeAccounts>) -> Result<()> {
+        instructions::book_derive_outcome(ctx)
     }
-    pub fn book_operator_resolve_dispute(
-        ctx: Context<BookOperatorResolveDisputeAccounts>,
+    pub fn book_bettor_dispute(
+        ctx: Context<BookBettorDisputeAccounts>,
         bet_outcome: BetOutcome,
+        evidence: [u8; 32],
+        bond: u64,
     ) -> Result<()> {
-        instructions::book_operator_resolve_dispute(ctx, bet_outcome)
+        instructions::book_bettor_dispute(ctx, bet_outcome, evidence, bond)
+    }
+    pub fn book_council_resolve_dispute(ctx: Context<BookCouncilResolveDisputeAccounts>) -> Result<()> {
+        instructions::book_council_resolve_dispute(ctx)
+    }
+    pub fn book_expire_dispute(ctx: Context<BookExpireDisputeAccounts>) -> Result<()> {
+        instructions::book_expire_dispute(ctx)
+    }
+    pub fn book_expire_quorum(ctx: Context<BookExpireQuorumAccounts>) -> Result<()> {
+        instructions::book_expire_quorum(ctx)
     }
     pub fn book_bettor_settle(ctx: Context<BookBettorSettleAccounts>) -> Result<()> {
         instructions::book_bettor_settle(ctx)
     }
+    pub fn book_disputer_settle(ctx: Context<BookDisputerSettleAccounts>) -> Result<()> {
+        instructions::book_disputer_settle(ctx)
+    }
     pub fn book_oracle_settle(ctx: Context<BookOracleSettleAccounts>) -> Result<()> {
         instructions::book_oracle_settle(ctx)
     }
+    pub fn book_oracle_suspend(ctx: Context<BookOracleSuspendAccounts>) -> Result<()> {
+        instructions::book_oracle_suspend(ctx)
+    }
     pub fn book_initiator_settle(ctx: Context<BookInitiatorSettleAccounts>) -> Result<()> {
         instructions::book_initiator_settle(ctx)
     }
+    pub fn multi_book_init(
+        ctx: Context<MultiBookInitAccounts>,
+        bet_type: MultiBetType,
+        outcomes_count: u8,
+        quorum: Quorum,
+    ) -> Result<()> {
+        instructions::multi_book_init(ctx, bet_type, outcomes_count, quorum)
+    }
+    pub fn multi_book_close(ctx: Context<MultiBookCloseAccounts>) -> Result<()> {
+        instructions::multi_book_close(ctx)
+    }
+    pub fn multi_book_bettor_opt_in(ctx: Context<MultiBookBettorOptInAccounts>) -> Result<()> {
+        instructions::multi_book_bettor_opt_in(ctx)
+    }
+    pub fn multi_book_bettor_place_bet(
+        ctx: Context<MultiBookBettorPlaceBetAccounts>,
+        outcome: u8,
+        odds: u32,
+        wager: u64,
+        bet_direction: BetDirection,
+    ) -> Result<()> {
+        instructions::multi_book_bettor_place_bet(ctx, outcome, odds, wager, bet_direction)
+    }
+    pub fn multi_book_bettor_cancel_bet(
+        ctx: Context<MultiBookBettorCancelBetAccounts>,
+        outcome: u8,
+        bet_id: u64,
+        bet_direction: BetDirection,
+    ) -> Result<()> {
+        instructions::multi_book_bettor_cancel_bet(ctx, outcome, bet_id, bet_direction)
+    }
+    pub fn multi_book_match_bets(
+        ctx: Context<MultiBookMatchBetsAccounts>,
+        outcome: u8,
+        max_matches: u32,
+    ) -> Result<()> {
+        instructions::multi_book_match_bets(ctx, outcome, max_matches)
+    }
+    pub fn multi_book_oracle_opt_in(ctx: Context<MultiBookOracleOptInAccounts>, stake: u64) -> Result<()> {
+        instructions::multi_book_oracle_opt_in(ctx, stake)
+    }
+    pub fn multi_book_oracle_commit_outcome(
+        ctx: Context<MultiBookOracleCommitOutcomeAccounts>,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::multi_book_oracle_commit_outcome(ctx, commitment)
+    }
+    pub fn multi_book_oracle_reveal_outcome(
+        ctx: Context<MultiBookOracleRevealOutcomeAccounts>,
+        bet_outcome: Option<MultiBetOutcome>,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::multi_book_oracle_reveal_outcome(ctx, bet_outcome, salt)
+    }
+    pub fn multi_book_bettor_dispute(ctx: Context<MultiBookBettorDisputeAccounts>, stake: u64) -> Result<()> {
+        instructions::multi_book_bettor_dispute(ctx, stake)
+    }
+    pub fn multi_book_council_resolve_dispute(ctx: Context<MultiBookCouncilResolveDisputeAccounts>) -> Result<()> {
+        instructions::multi_book_council_resolve_dispute(ctx)
+    }
+    pub fn multi_book_expire_dispute(ctx: Context<MultiBookExpireDisputeAccounts>) -> Result<()> {
+        instructions::multi_book_expire_dispute(ctx)
+    }
+    pub fn multi_book_bettor_settle(ctx: Context<MultiBookBettorSettleAccounts>) -> Result<()> {
+        instructions::multi_book_bettor_settle(ctx)
+    }
+    pub fn multi_book_oracle_settle(ctx: Context<MultiBookOracleSettleAccounts>) -> Result<()> {
+        instructions::multi_book_oracle_settle(ctx)
+    }
+    pub fn multi_book_initiator_settle(ctx: Context<MultiBookInitiatorSettleAccounts>) -> Result<()> {
+        instructions::multi_book_initiator_settle(ctx)
+    }
+    pub fn parlay_init(ctx: Context<ParlayInitAccounts>, parlay_id: u64, legs: Vec<ParlayLeg>, wager: u64) -> Result<()> {
+        instructions::parlay_init(ctx, parlay_id, legs, wager)
+    }
+    pub fn parlay_cancel(ctx: Context<ParlayCancelAccounts>) -> Result<()> {
+        instructions::parlay_cancel(ctx)
+    }
+    pub fn parlay_match(ctx: Context<ParlayMatchAccounts>) -> Result<()> {
+        instructions::parlay_match(ctx)
+    }
+    pub fn parlay_settle(ctx: Context<ParlaySettleAccounts>) -> Result<()> {
+        instructions::parlay_settle(ctx)
+    }
 }
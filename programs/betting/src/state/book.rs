@@ -1,10 +1,22 @@
 use std::{
     cmp::Ordering,
-    collections::{BTreeMap, VecDeque},
+    ops::{Deref, DerefMut},
 };
 
 use anchor_lang::prelude::*;
 
+use crate::{
+    constants::{
+        COUNCIL_RESOLUTION_WINDOW, DISPUTE_BOND_CAP, DISPUTE_ROUND_WINDOW, MAX_BOOK_BETTOR_DISPUTE_WINDOW,
+        MAX_BOOK_INITIATOR_REWARD_SHARE, MAX_DISPUTE_ROUNDS, MAX_QUORUM_ORACLES, MAX_QUORUM_STAKE_RATIO,
+        MIN_BOOK_BETTOR_DISPUTE_STAKE, MIN_BOOK_BET_AMOUNT, MIN_BOOK_ORACLE_STAKE, MIN_BOOK_ORACLE_UPDATE_WINDOW,
+        MIN_QUORUM_ORACLES, MIN_QUORUM_STAKE_RATIO, MIN_QUORUM_SUPERMAJORITY, ORACLE_REVEAL_WINDOW,
+        SLASHED_STAKE_DISPUTERS_SHARE,
+    },
+    error::BettingError,
+    state::{BookPage, Config, Position},
+};
+
 #[account]
 pub struct Book {
     pub game_id: u32,
@@ -19,11 +31,41 @@ pub struct Book {
     pub total_oracle_stake: u64,
     pub dispute_resolution_result: Option<BetOutcome>,
     pub concluded_at: Option<i64>,
+    // the end of the commit phase of the oracles, set by the first commit
+    pub reveal_starts_at: Option<i64>,
     pub aggregated_oracle_outcome: Option<BetOutcome>,
-    pub oracles: BTreeMap<Pubkey, Oracle>,
-    pub bets_for: VecDeque<Bet>,
-    pub bets_against: VecDeque<Bet>,
-    pub positions: BTreeMap<Pubkey, Position>,
+    // none for pre-match books, which close at kickoff
+    pub in_play: Option<InPlay>,
+    // the number of unsettled parlays with a leg on the book
+    pub parlays_count: u32,
+    // the number of unsettled oracles, the oracles live in their own pdas
+    pub oracles_count: u32,
+    // the stake of the oracles behind each revealed outcome in the order of ORACLE_OUTCOMES, stake which is not revealed
+    // yet counts as none, kept up to date on every change so the aggregated outcome does not need the oracles
+    pub oracle_stakes: [u64; 6],
+    pub revealed_oracles_count: u32,
+    // the quorum was not met by the deadline and the book was cancelled, every stake is returned
+    pub quorum_timed_out: bool,
+    // the book can only conclude on the oracles once the quorum is met
+    pub quorum: Quorum,
+    // the minimums, the windows and the initiator reward share of the book, which the instructions read instead of the
+    // config
+    pub params: BookParams,
+    // the latest round of the dispute, none until the book is disputed
+    pub dispute: Option<Dispute>,
+    // the bonds of the dispute rounds behind each outcome in the order of BetOutcome
+    pub dispute_bonds: [u64; 5],
+    // the number of unsettled dispute rounds, the rounds live in their own pdas
+    pub disputes_count: u32,
+    // the odds of the price levels with resting bets in order of priority, the bets rest in the book pages
+    pub levels_for: Vec<u32>,
+    pub levels_against: Vec<u32>,
+    // the price levels whose bets overflowed into more than one page, any other level lives in its first page
+    pub level_pages: Vec<LevelPages>,
+    // the price levels with bets placed in play which are still in their bet delay, a suspension only needs their pages
+    pub delayed_levels: Vec<DelayedLevel>,
+    // the number of unsettled positions, the positions live in their own pdas
+    pub positions_count: u32,
 }
 impl Book {
     pub const INIT_SPACE: usize = 8
@@ -42,182 +84,780 @@ impl Book {
         + 1
         + 8
         + 1
+        + 8
+        + 1
         + BetOutcome::INIT_SPACE
+        + 1
+        + InPlay::INIT_SPACE
+        + 4
+        + 4
+        + 8 * 6
+        + 4
+        + 1
+        + Quorum::INIT_SPACE
+        + BookParams::INIT_SPACE
+        + 1
+        + Dispute::INIT_SPACE
+        + 8 * 5
+        + 4
+        + 4
         + 4
         + 4
         + 4
         + 4;
     pub fn current_space(&self) -> usize {
         Self::INIT_SPACE
-            + (32 + Oracle::INIT_SPACE) * self.oracles.len()
-            + Bet::INIT_SPACE * (self.bets_for.len() + self.bets_against.len())
-            + (32 + Position::INIT_SPACE) * self.positions.len()
+            + 4 * (self.levels_for.len() + self.levels_against.len())
+            + self.level_pages.iter().map(|l| l.space()).sum::<usize>()
+            + DelayedLevel::INIT_SPACE * self.delayed_levels.len()
     }
-    pub fn aggregated_outcome(&self) -> Option<BetOutcome> {
-        if self.oracles.is_empty() {
-            None
+    // an escalated dispute waits for the council, otherwise the latest round stands once its window passes
+    pub fn final_outcome(&self) -> Option<BetOutcome> {
+        match self.dispute {
+            None => self.aggregated_oracle_outcome,
+            Some(d) if d.escalated => self.dispute_resolution_result,
+            Some(d) => Some(d.outcome),
+        }
+    }
+    // the latest round of the dispute can still be countered
+    pub fn is_in_dispute(&self, now: i64) -> bool {
+        matches!(self.dispute, Some(d) if !d.escalated && now <= d.ends_at)
+    }
+    // the deadline of the council to resolve an escalated dispute
+    pub fn resolution_deadline(&self) -> Option<i64> {
+        match self.dispute {
+            Some(d) if d.escalated => Some(d.ends_at + COUNCIL_RESOLUTION_WINDOW),
+            _ => None,
+        }
+    }
+    // the council missed the deadline and the book was cancelled, every stake and bond is returned
+    pub fn is_dispute_timed_out(&self) -> bool {
+        matches!(self.dispute, Some(d) if d.timed_out)
+    }
+    // the book was cancelled on a missed deadline, either of the quorum or of the council
+    pub fn is_timed_out(&self) -> bool {
+        self.quorum_timed_out || self.is_dispute_timed_out()
+    }
+    pub fn next_dispute_round(&self) -> u8 {
+        self.dispute.map_or(1, |d| d.round + 1)
+    }
+    // a new round must propose another outcome than the one it counters and bond at least twice as much, the dispute
+    // falls through to the council after MAX_DISPUTE_ROUNDS rounds or once the bond reaches DISPUTE_BOND_CAP
+    pub fn new_dispute_round(&mut self, outcome: BetOutcome, bond: u64, now: i64) -> Result<u8> {
+        require!(self.bet_type.is_valid_outcome(outcome), BettingError::InvalidOutcome);
+        let round = self.next_dispute_round();
+        match self.dispute {
+            None => {
+                require!(
+                    self.aggregated_oracle_outcome != Some(outcome),
+                    BettingError::InvalidDispute
+                );
+                require!(
+                    bond >= self.params.min_bettor_dispute_stake,
+                    BettingError::MinTokenAmountNotMet
+                );
+            }
+            Some(d) => {
+                require!(self.is_in_dispute(now), BettingError::NotInWindow);
+                require!(d.outcome != outcome, BettingError::InvalidDispute);
+                require!(bond >= d.bond * 2, BettingError::MinTokenAmountNotMet);
+            }
+        }
+        self.dispute = Some(Dispute {
+            round,
+            outcome,
+            bond,
+            ends_at: now + DISPUTE_ROUND_WINDOW,
+            escalated: round >= MAX_DISPUTE_ROUNDS || bond >= DISPUTE_BOND_CAP,
+            timed_out: false,
+        });
+        self.dispute_bonds[outcome as usize] += bond;
+        self.total_dispute_stake += bond;
+        self.disputes_count += 1;
+        Ok(round)
+    }
+    // the fee kept from the bettor payouts, refunded stakes are not charged
+    pub fn total_profit(&self, final_outcome: BetOutcome) -> u64 {
+        let total_profit = self.dealt_wager * (10000 - self.params.bettor_payout_rate) / 10000;
+        match final_outcome {
+            BetOutcome::For | BetOutcome::Against => total_profit,
+            BetOutcome::HalfFor | BetOutcome::HalfAgainst => total_profit / 2,
+            BetOutcome::Cancel => 0,
+        }
+    }
+    // the stake of the oracles which revealed the final outcome
+    pub fn correct_oracle_stake(&self, final_outcome: BetOutcome) -> u64 {
+        self.oracle_stakes[Self::oracle_outcome_index(Some(final_outcome))]
+    }
+    // the bonds of the dispute rounds which proposed the final outcome
+    pub fn winning_dispute_bonds(&self, final_outcome: BetOutcome) -> u64 {
+        self.dispute_bonds[final_outcome as usize]
+    }
+    // the disputers were right when the dispute overturned the oracles in favour of an outcome they proposed
+    pub fn is_dispute_successful(&self) -> bool {
+        match self.final_outcome() {
+            Some(o) => Some(o) != self.aggregated_oracle_outcome && self.winning_dispute_bonds(o) > 0,
+            None => false,
+        }
+    }
+    // splits the bonds of the losing dispute rounds into the shares of the correct oracles and of the winning
+    // disputers, the winning disputers take all of it and the correct oracles only when no round proposed the final
+    // outcome, paid pro rata by stake
+    pub fn lost_dispute_bonds_shares(&self, final_outcome: BetOutcome) -> (u64, u64) {
+        if self.is_timed_out() {
+            return (0, 0);
+        }
+        let winning_dispute_bonds = self.winning_dispute_bonds(final_outcome);
+        let lost_dispute_bonds = self.total_dispute_stake - winning_dispute_bonds;
+        if winning_dispute_bonds > 0 {
+            (0, lost_dispute_bonds)
+        } else if self.correct_oracle_stake(final_outcome) > 0 {
+            (lost_dispute_bonds, 0)
         } else {
-            let mut map: BTreeMap<Option<BetOutcome>, u64> = BTreeMap::new();
-
-            for o in self.oracles.values() {
-                *map.entry(o.outcome).or_insert(0) += o.stake;
+            (0, 0)
+        }
+    }
+    // splits the stake of the wrong and the unrevealed oracles into the shares of the correct oracles and of the
+    // successful disputers, a side without anyone to pay leaves its share to the other, paid pro rata by stake
+    pub fn slashed_stake_shares(&self, final_outcome: BetOutcome) -> (u64, u64) {
+        if self.is_timed_out() {
+            return (0, 0);
+        }
+        let correct_oracle_stake = self.correct_oracle_stake(final_outcome);
+        let slashed_stake = self.total_oracle_stake - correct_oracle_stake;
+        let disputers_share = match (self.is_dispute_successful(), correct_oracle_stake > 0) {
+            (true, true) => slashed_stake * SLASHED_STAKE_DISPUTERS_SHARE / 10000,
+            (true, false) => slashed_stake,
+            (false, _) => 0,
+        };
+        let oracles_share = if correct_oracle_stake > 0 {
+            slashed_stake - disputers_share
+        } else {
+            0
+        };
+        (oracles_share, disputers_share)
+    }
+    // the part of an amount shared pro rata by stake, in u128 since both can be large
+    pub fn pro_rata_share(amount: u64, stake: u64, total_stake: u64) -> Result<u64> {
+        let share = (amount as u128)
+            .checked_mul(stake as u128)
+            .and_then(|x| x.checked_div(total_stake as u128))
+            .ok_or(BettingError::MathOverflow)?;
+        u64::try_from(share).map_err(|_| error!(BettingError::MathOverflow))
+    }
+    pub const ORACLE_OUTCOMES: [Option<BetOutcome>; 6] = [
+        None,
+        Some(BetOutcome::For),
+        Some(BetOutcome::Cancel),
+        Some(BetOutcome::Against),
+        Some(BetOutcome::HalfFor),
+        Some(BetOutcome::HalfAgainst),
+    ];
+    fn oracle_outcome_index(outcome: Option<BetOutcome>) -> usize {
+        Self::ORACLE_OUTCOMES.iter().position(|o| *o == outcome).unwrap()
+    }
+    fn oracle_stake_mut(&mut self, outcome: Option<BetOutcome>) -> &mut u64 {
+        &mut self.oracle_stakes[Self::oracle_outcome_index(outcome)]
+    }
+    pub fn add_oracle_stake(&mut self, outcome: Option<BetOutcome>, stake: u64) {
+        *self.oracle_stake_mut(outcome) += stake;
+    }
+    pub fn remove_oracle_stake(&mut self, outcome: Option<BetOutcome>, stake: u64) {
+        *self.oracle_stake_mut(outcome) -= stake;
+    }
+    // the outcome of the revealed oracles under the aggregation rule of the quorum, none until the quorum is met,
+    // unrevealed stake abstains
+    pub fn aggregated_outcome(&self) -> Option<BetOutcome> {
+        let revealed_stake = self.revealed_oracle_stake();
+        if revealed_stake == 0
+            || self.revealed_oracles_count < self.quorum.min_oracles
+            || (revealed_stake as u128) * 10000 < (self.dealt_wager as u128) * (self.quorum.min_stake_ratio as u128)
+        {
+            return None;
+        }
+        let (outcome, threshold) = match self.quorum.rule {
+            AggregationRule::Majority => (self.leading_outcome().0, MIN_QUORUM_SUPERMAJORITY),
+            AggregationRule::Supermajority { threshold } => (self.leading_outcome().0, threshold),
+            AggregationRule::Median => (self.median_outcome(revealed_stake)?, MIN_QUORUM_SUPERMAJORITY),
+        };
+        // whatever the rule, the outcome needs at least the minimum supermajority of the revealed stake, so exact ties
+        // never conclude
+        let stake = self.oracle_stakes[Self::oracle_outcome_index(Some(outcome))];
+        (stake as u128 * 10000 >= revealed_stake as u128 * threshold.max(MIN_QUORUM_SUPERMAJORITY) as u128)
+            .then_some(outcome)
+    }
+    fn median_outcome(&self, revealed_stake: u64) -> Option<BetOutcome> {
+        let mut cumulative_stake = 0;
+        for outcome in Self::MEDIAN_OUTCOMES {
+            cumulative_stake += self.oracle_stakes[Self::oracle_outcome_index(Some(outcome))];
+            match (cumulative_stake * 2).cmp(&revealed_stake) {
+                Ordering::Greater => return Some(outcome),
+                // the median falls between two outcomes, fall back to cancel
+                Ordering::Equal => return Some(BetOutcome::Cancel),
+                Ordering::Less => {}
             }
-
-            let mut vec = Vec::from_iter(map);
-            vec.sort_by_key(|kv| kv.1);
-
-            vec[vec.len() - 1].0
         }
+        None
+    }
+    // the outcomes from the least to the most favourable for the for side
+    const MEDIAN_OUTCOMES: [BetOutcome; 5] = [
+        BetOutcome::Against,
+        BetOutcome::HalfAgainst,
+        BetOutcome::Cancel,
+        BetOutcome::HalfFor,
+        BetOutcome::For,
+    ];
+    fn revealed_oracle_stake(&self) -> u64 {
+        self.oracle_stakes.iter().skip(1).sum()
+    }
+    fn leading_outcome(&self) -> (BetOutcome, u64) {
+        let mut index = 1;
+        for i in 2..self.oracle_stakes.len() {
+            if self.oracle_stakes[i] > self.oracle_stakes[index] {
+                index = i;
+            }
+        }
+        (Self::ORACLE_OUTCOMES[index].unwrap(), self.oracle_stakes[index])
+    }
+    // the stakes of the oracles are final once the reveal phase starts, later stake could follow the revealed outcomes
+    pub fn is_oracle_stake_locked(&self, now: i64) -> bool {
+        self.concluded_at.is_some() || matches!(self.reveal_starts_at, Some(t) if t <= now)
+    }
+    pub fn is_suspended(&self, now: i64) -> bool {
+        matches!(self.in_play, Some(p) if p.suspended_until > now)
+    }
+    // bets placed in play can only be matched after the bet delay
+    pub fn is_accepted(&self, bet: &Bet, now: i64) -> Result<bool> {
+        let accepted_at = bet
+            .placed_at
+            .checked_add(self.in_play.map_or(0, |p| p.bet_delay))
+            .ok_or(BettingError::MathOverflow)?;
+        Ok(accepted_at <= now)
+    }
+    // a bet whose delay overflows is never accepted, so it is void on a suspension
+    fn is_delay_over(bet: &Bet, bet_delay: i64, now: i64) -> bool {
+        matches!(bet.placed_at.checked_add(bet_delay), Some(accepted_at) if accepted_at <= now)
+    }
+    // keeps the page of a bet placed in play listed as delayed until its bet delay has passed
+    fn add_delayed_level(&mut self, page: &BookPage, now: i64) -> Result<()> {
+        if let Some(in_play) = self.in_play {
+            let (bet_direction, odds, index) = (page.bet_direction(), page.odds, page.index);
+            self.delayed_levels.retain(|l| {
+                l.delayed_until > now && (l.bet_direction, l.odds, l.index) != (bet_direction, odds, index)
+            });
+            self.delayed_levels.push(DelayedLevel {
+                bet_direction,
+                odds,
+                index,
+                delayed_until: now.checked_add(in_play.bet_delay).ok_or(BettingError::MathOverflow)?,
+            });
+        }
+        Ok(())
     }
-    pub fn new_bet(&mut self, odds: u32, wager: u64, bettor: Pubkey, bet_direction: BetDirection) {
+    // suspends the market, bets still in their bet delay are void and their wagers are refunded on settlement, the
+    // pages of the delayed price levels and the positions of the bettors of the void bets must be passed
+    pub fn suspend<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        now: i64,
+        suspended_until: i64,
+    ) -> Result<()> {
+        self.delayed_levels.retain(|l| l.delayed_until > now);
+        for level in self.delayed_levels.iter() {
+            require!(
+                Self::find_page(pages, level.bet_direction, level.odds, level.index).is_some(),
+                BettingError::InvalidPages
+            );
+        }
+        let bet_delay = self.in_play.unwrap().bet_delay;
+        for page in pages.iter() {
+            for bet in page.bets().iter().filter(|b| !Self::is_delay_over(b, bet_delay, now)) {
+                require!(
+                    Self::find_position(positions, &bet.bettor).is_some(),
+                    BettingError::InvalidPositions
+                );
+            }
+        }
+        self.in_play.as_mut().unwrap().suspended_until = suspended_until;
+        for page in pages.iter_mut() {
+            page.retain(|b| {
+                let accepted = Self::is_delay_over(b, bet_delay, now);
+                if !accepted {
+                    let position = Self::find_position(positions, &b.bettor).unwrap();
+                    positions[position].active_bets_count -= 1;
+                }
+                accepted
+            });
+            self.update_level(page);
+        }
+        self.delayed_levels.clear();
+        Ok(())
+    }
+    pub fn levels(&self, bet_direction: BetDirection) -> &Vec<u32> {
+        match bet_direction {
+            BetDirection::For => &self.levels_for,
+            BetDirection::Against => &self.levels_against,
+        }
+    }
+    fn levels_mut(&mut self, bet_direction: BetDirection) -> &mut Vec<u32> {
+        match bet_direction {
+            BetDirection::For => &mut self.levels_for,
+            BetDirection::Against => &mut self.levels_against,
+        }
+    }
+    // keeps the price level of the page listed while any of its pages has resting bets
+    pub fn update_level(&mut self, page: &BookPage) {
+        self.update_page_len(page.bet_direction(), page.odds, page.index, page.len);
+    }
+    // unlists a page that is closed with resting bets, their wagers are refunded on settlement
+    pub fn remove_level(&mut self, page: &BookPage) {
+        self.update_page_len(page.bet_direction(), page.odds, page.index, 0);
+    }
+    fn update_page_len(&mut self, bet_direction: BetDirection, odds: u32, index: u16, len: u16) {
+        let listed = match self.find_level_pages(bet_direction, odds) {
+            Some(level) => {
+                let level_pages = &mut self.level_pages[level];
+                if index < level_pages.head || index > level_pages.tail() {
+                    return;
+                }
+                level_pages.lens[(index - level_pages.head) as usize] = len;
+                // the emptied pages at the front of the level are dropped
+                while level_pages.lens.len() > 1 && level_pages.lens[0] == 0 {
+                    level_pages.lens.remove(0);
+                    level_pages.head += 1;
+                }
+                let listed = level_pages.lens[0] > 0;
+                if !listed || (level_pages.head == 0 && level_pages.lens.len() == 1) {
+                    self.level_pages.remove(level);
+                }
+                listed
+            }
+            None if index == 0 => len > 0,
+            None => return,
+        };
+        let levels = self.levels_mut(bet_direction);
+        match (levels.binary_search(&odds), listed) {
+            (Err(index), true) => levels.insert(index, odds),
+            (Ok(index), false) => {
+                levels.remove(index);
+            }
+            _ => {}
+        }
+    }
+    fn find_level_pages(&self, bet_direction: BetDirection, odds: u32) -> Option<usize> {
+        self.level_pages
+            .iter()
+            .position(|l| l.bet_direction == bet_direction && l.odds == odds)
+    }
+    // the indexes of the pages of the price level with resting bets, in order of priority
+    fn page_indexes(&self, bet_direction: BetDirection, odds: u32) -> Vec<u16> {
+        match self.find_level_pages(bet_direction, odds) {
+            Some(level) => {
+                let level_pages = &self.level_pages[level];
+                (level_pages.head..)
+                    .zip(level_pages.lens.iter())
+                    .filter(|(_, len)| **len > 0)
+                    .map(|(index, _)| index)
+                    .collect()
+            }
+            None => vec![0],
+        }
+    }
+    // the page new bets of the price level rest in, once it is full the level overflows into its next page, which must
+    // then be passed too
+    fn tail_page<P: Deref<Target = BookPage>>(
+        &mut self,
+        pages: &[P],
+        bet_direction: BetDirection,
+        odds: u32,
+        freed_page: Option<usize>,
+    ) -> Result<usize> {
+        let level = self.find_level_pages(bet_direction, odds);
+        let tail = level.map_or(0, |l| self.level_pages[l].tail());
+        let page = Self::find_page(pages, bet_direction, odds, tail).ok_or(BettingError::InvalidPages)?;
+        if !pages[page].is_full() || freed_page == Some(page) {
+            return Ok(page);
+        }
+        let next_page = Self::find_page(pages, bet_direction, odds, tail + 1).ok_or(BettingError::PageFull)?;
+        require!(pages[next_page].len == 0, BettingError::InvalidPages);
+        match level {
+            Some(level) => self.level_pages[level].lens.push(0),
+            None => self.level_pages.push(LevelPages {
+                bet_direction,
+                odds,
+                head: 0,
+                lens: vec![pages[page].len, 0],
+            }),
+        }
+        Ok(next_page)
+    }
+    // the index of the page of the price level among the loaded pages
+    fn find_page<P: Deref<Target = BookPage>>(
+        pages: &[P],
+        bet_direction: BetDirection,
+        odds: u32,
+        index: u16,
+    ) -> Option<usize> {
+        pages
+            .iter()
+            .position(|p| p.odds == odds && p.index == index && p.bet_direction() == bet_direction)
+    }
+    // the page and the index of a resting bet among the loaded pages of its price level
+    fn find_bet_page<P: Deref<Target = BookPage>>(
+        pages: &[P],
+        bet_id: u64,
+        bet_direction: BetDirection,
+    ) -> Result<(usize, usize)> {
+        let odds = (bet_id >> 32) as u32;
+        let mut level_pages = pages
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.odds == odds && p.bet_direction() == bet_direction)
+            .peekable();
+        require!(level_pages.peek().is_some(), BettingError::InvalidPages);
+        level_pages
+            .find_map(|(page, p)| p.find(bet_id).map(|index| (page, index)))
+            .ok_or_else(|| BettingError::NotFound.into())
+    }
+    // the page and the index of the first live bet of the queue, the pages with resting bets are needed in order of
+    // priority up to the first one with a live bet
+    fn live_front<P: Deref<Target = BookPage>>(
+        &self,
+        pages: &[P],
+        bet_direction: BetDirection,
+        now: i64,
+    ) -> Result<Option<(usize, usize)>> {
+        for odds in self.levels(bet_direction) {
+            for page_index in self.page_indexes(bet_direction, *odds) {
+                let page =
+                    Self::find_page(pages, bet_direction, *odds, page_index).ok_or(BettingError::InvalidPages)?;
+                if let Some(index) = pages[page].live_front(now) {
+                    return Ok(Some((page, index)));
+                }
+            }
+        }
+        Ok(None)
+    }
+    // the index of the position of the bettor among the loaded positions
+    fn find_position<Q: Deref<Target = Position>>(positions: &[Q], bettor: &Pubkey) -> Option<usize> {
+        positions.iter().position(|p| p.bettor == *bettor)
+    }
+    pub fn find_bet<P: Deref<Target = BookPage>>(pages: &[P], bet_id: u64, bet_direction: BetDirection) -> Option<Bet> {
+        let (page, index) = Self::find_bet_page(pages, bet_id, bet_direction).ok()?;
+        Some(pages[page].bets[index])
+    }
+    fn bet(&self, odds: u32, wager: u64, bettor: Pubkey, now: i64, expires_at: i64) -> Bet {
         let mut id = [0_u8; 8];
         id[0..4].copy_from_slice(self.bets_count.to_le_bytes().as_slice());
         id[4..8].copy_from_slice(odds.to_le_bytes().as_slice());
-        let bet = Bet {
+        Bet {
             id: u64::from_le_bytes(id),
             bettor,
             wager,
-        };
-        match bet_direction {
-            BetDirection::For => match self.bets_for.binary_search_by_key(&bet.id, |b| b.id) {
-                Ok(_) => {
-                    panic!();
-                }
-                Err(index) => {
-                    self.bets_for.insert(index, bet);
-                }
-            },
-            BetDirection::Against => match self.bets_against.binary_search_by_key(&bet.id, |b| b.id) {
-                Ok(_) => {
-                    panic!();
-                }
-                Err(index) => {
-                    self.bets_against.insert(index, bet);
-                }
-            },
+            placed_at: now,
+            expires_at,
         }
+    }
+    // whether a bet with the given odds would be matched against the front of the opposite queue
+    pub fn crosses_front<P: Deref<Target = BookPage>>(
+        &self,
+        pages: &[P],
+        odds: u32,
+        bet_direction: BetDirection,
+        now: i64,
+    ) -> Result<bool> {
+        let bet = self.bet(odds, 0, Pubkey::default(), 0, 0);
+        Ok(match self.live_front(pages, bet_direction.opposite(), now)? {
+            Some((page, index)) => match bet_direction {
+                BetDirection::For => bet.crosses(&pages[page].bets[index]),
+                BetDirection::Against => pages[page].bets[index].crosses(&bet),
+            },
+            None => false,
+        })
+    }
+    // rests a new bet in the last page of its price level, returns the id of the bet
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_bet<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        odds: u32,
+        wager: u64,
+        bettor: Pubkey,
+        bet_direction: BetDirection,
+        now: i64,
+        expires_at: i64,
+    ) -> Result<u64> {
+        let position = Self::find_position(positions, &bettor).ok_or(BettingError::InvalidPositions)?;
+        let bet = self.bet(odds, wager, bettor, now, expires_at);
+        // a resting bet must always be priceable, or matching would fail on it
+        bet.payout()?;
+        let page = self.tail_page(pages, bet_direction, odds, None)?;
+        pages[page].push(bet);
+        self.update_level(&pages[page]);
+        self.add_delayed_level(&pages[page], now)?;
 
         self.bets_count += 1;
         self.wager_total += wager;
-        let position = self.positions.get_mut(&bettor).unwrap();
+        let position = &mut positions[position];
         position.bets_count += 1;
         position.wager += wager;
         position.active_bets_count += 1;
+        Ok(bet.id)
     }
-    pub fn match_bets(&mut self) -> bool {
-        let mut matched = false;
-        if self.dealt_wager >= self.payout_for_total && self.dealt_wager >= self.payout_against_total {
-            match self.payout_for_total.cmp(&self.payout_against_total) {
-                Ordering::Equal => {
-                    if !self.bets_for.is_empty()
-                        && !self.bets_against.is_empty()
-                        && self.bets_for.front().unwrap().odds_f() <= self.bets_against.front().unwrap().opposite_odds()
-                    {
-                        let mut bet_for = self.bets_for.pop_front().unwrap();
-                        let mut bet_against = self.bets_against.pop_front().unwrap();
-                        let mut against_payout = 0_u64;
-                        let mut against_dealt_wager = 0_u64;
-                        let mut for_payout = 0_u64;
-                        let mut for_dealt_wager = 0_u64;
-                        if bet_for.payout() <= bet_against.wager + bet_for.wager {
-                            let against_wager = bet_for.payout() - bet_for.wager;
-
-                            against_payout += bet_against.get_payout_by_wager(against_wager);
-                            against_dealt_wager += against_wager;
-                            bet_against.wager -= against_wager;
-
-                            for_payout += bet_for.payout();
-                            for_dealt_wager += bet_for.wager;
-                            bet_for.wager = 0;
-
-                            matched = true;
-                        } else if bet_against.payout() <= bet_for.wager + bet_against.wager {
-                            let for_wager = bet_against.payout() - bet_against.wager;
-
-                            for_payout += bet_for.get_payout_by_wager(for_wager);
-                            for_dealt_wager += for_wager;
-                            bet_for.wager -= for_wager;
-
-                            against_payout += bet_against.payout();
-                            against_dealt_wager += bet_against.wager;
-                            bet_against.wager = 0;
-
-                            matched = true;
-                        }
-                        self.positions.entry(bet_for.bettor).and_modify(|p| {
-                            p.payout_for += for_payout;
-                            p.dealt_wager += for_dealt_wager;
-
-                            self.payout_for_total += for_payout;
-                            self.dealt_wager += for_dealt_wager;
-
-                            if bet_for.wager > 0 {
-                                self.bets_for.push_front(bet_for);
-                            } else {
-                                p.active_bets_count -= 1;
-                            }
-                        });
-                        self.positions.entry(bet_against.bettor).and_modify(|p| {
-                            p.payout_against += against_payout;
-                            p.dealt_wager += against_dealt_wager;
-
-                            self.payout_against_total += against_payout;
-                            self.dealt_wager += against_dealt_wager;
-                            if bet_against.wager > 0 {
-                                self.bets_against.push_front(bet_against);
-                            } else {
-                                p.active_bets_count -= 1;
-                            }
-                        });
-                    }
-                }
-                Ordering::Greater => {
-                    if let Some(mut bet_against) = self.bets_against.pop_front() {
-                        let position_against = self.positions.get_mut(&bet_against.bettor).unwrap();
-                        let payout_diff = self.payout_for_total - self.payout_against_total;
-
-                        position_against.payout_against += payout_diff;
-                        let dealt_wager = bet_against.get_wager_by_payout(payout_diff);
-                        position_against.dealt_wager += dealt_wager;
-                        bet_against.wager -= dealt_wager;
-
-                        self.payout_against_total += payout_diff;
-                        self.dealt_wager += dealt_wager;
-
-                        matched = true;
-
-                        if bet_against.wager > 0 {
-                            self.bets_against.push_front(bet_against)
-                        } else {
-                            position_against.active_bets_count -= 1;
-                        }
-                    }
-                }
-                Ordering::Less => {
-                    if let Some(mut bet_for) = self.bets_for.pop_front() {
-                        let position_for = self.positions.get_mut(&bet_for.bettor).unwrap();
-                        let payout_diff = self.payout_against_total - self.payout_for_total;
-
-                        position_for.payout_for += payout_diff;
-                        let dealt_wager = bet_for.get_wager_by_payout(payout_diff);
-                        position_for.dealt_wager += dealt_wager;
-                        bet_for.wager -= dealt_wager;
-
-                        self.payout_for_total += payout_diff;
-                        self.dealt_wager += dealt_wager;
-
-                        matched = true;
-
-                        if bet_for.wager > 0 {
-                            self.bets_for.push_front(bet_for);
-                        } else {
-                            position_for.active_bets_count -= 1;
-                        }
-                    }
+    // removes a resting bet, returns the removed bet
+    pub fn cancel_bet<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        bet_id: u64,
+        bet_direction: BetDirection,
+    ) -> Result<Bet> {
+        let (page, index) = Self::find_bet_page(pages, bet_id, bet_direction)?;
+        let position =
+            Self::find_position(positions, &pages[page].bets[index].bettor).ok_or(BettingError::InvalidPositions)?;
+        let bet = pages[page].remove(index);
+        self.update_level(&pages[page]);
+
+        self.wager_total -= bet.wager;
+        let position = &mut positions[position];
+        position.active_bets_count -= 1;
+        position.wager -= bet.wager;
+        Ok(bet)
+    }
+    // removes the expired bets of the bettor from the given pages, returns the wager to refund
+    pub fn purge_expired_bets<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        bettor: &Pubkey,
+        now: i64,
+    ) -> Result<u64> {
+        self.remove_bets(pages, positions, bettor, |b, _| b.is_expired(now))
+    }
+    // removes the bets of the bettor selected by the filter from the given pages, returns the wager to refund
+    pub fn cancel_bets<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        bettor: &Pubkey,
+        filter: &BetFilter,
+    ) -> Result<u64> {
+        self.remove_bets(pages, positions, bettor, |b, bet_direction| {
+            filter.matches(b, bet_direction)
+        })
+    }
+    fn remove_bets<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        bettor: &Pubkey,
+        remove: impl Fn(&Bet, BetDirection) -> bool,
+    ) -> Result<u64> {
+        let position = Self::find_position(positions, bettor).ok_or(BettingError::InvalidPositions)?;
+        let mut removed_count = 0;
+        let mut removed_wager = 0;
+        for page in pages.iter_mut() {
+            let bet_direction = page.bet_direction();
+            page.retain(|b| {
+                let removed = b.bettor == *bettor && remove(b, bet_direction);
+                if removed {
+                    removed_count += 1;
+                    removed_wager += b.wager;
                 }
+                !removed
+            });
+            self.update_level(page);
+        }
+        self.wager_total -= removed_wager;
+        let position = &mut positions[position];
+        position.active_bets_count -= removed_count;
+        position.wager -= removed_wager;
+        Ok(removed_wager)
+    }
+    // changes the odds and wager of a resting bet, the bet keeps its place in the queue if only the wager goes down,
+    // otherwise it is requeued with a new id and its bet delay starts over, returns the id of the amended bet
+    #[allow(clippy::too_many_arguments)]
+    pub fn amend_bet<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        bet_id: u64,
+        bet_direction: BetDirection,
+        odds: u32,
+        wager: u64,
+        now: i64,
+    ) -> Result<u64> {
+        let (page, index) = Self::find_bet_page(pages, bet_id, bet_direction)?;
+        let previous_bet = pages[page].bets[index];
+        let position = Self::find_position(positions, &previous_bet.bettor).ok_or(BettingError::InvalidPositions)?;
+        self.bet(odds, wager, previous_bet.bettor, now, 0).payout()?;
+        let amended_bet_id = if odds == previous_bet.odds() && wager <= previous_bet.wager {
+            pages[page].bets[index].wager = wager;
+            bet_id
+        } else {
+            let amended_page = self.tail_page(pages, bet_direction, odds, Some(page))?;
+            pages[page].remove(index);
+            self.update_level(&pages[page]);
+            // the sequence of the book is bumped so the new id is unique, the bettor pays no rent for it
+            let bet = self.bet(odds, wager, previous_bet.bettor, now, previous_bet.expires_at);
+            self.bets_count += 1;
+            pages[amended_page].push(bet);
+            self.update_level(&pages[amended_page]);
+            self.add_delayed_level(&pages[amended_page], now)?;
+            bet.id
+        };
+
+        self.wager_total = self.wager_total + wager - previous_bet.wager;
+        let position = &mut positions[position];
+        position.wager = position.wager + wager - previous_bet.wager;
+        Ok(amended_bet_id)
+    }
+    // Matches the front bets of both queues up to `max_matches` times, skipping expired bets. Both sides of a fill are
+    // credited the same payout and their dealt wagers add up to it, so `payout_for_total == payout_against_total ==
+    // dealt_wager` holds after every fill, the crossing condition guarantees the counter side pays no more than its
+    // odds ask for.
+    // Returns the number of fills and whether matching stopped early, either at `max_matches` or at a price level
+    // whose page or a bettor whose position was not passed, matching resumes from the fronts next time.
+    pub fn match_bets<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        max_matches: u32,
+        now: i64,
+    ) -> Result<(u32, bool)> {
+        let mut matches_count = 0;
+        loop {
+            let ((page_for, index_for), (page_against, index_against)) = match (
+                self.live_front(pages, BetDirection::For, now),
+                self.live_front(pages, BetDirection::Against, now),
+            ) {
+                (Ok(Some(front_for)), Ok(Some(front_against))) => (front_for, front_against),
+                (Ok(None), _) | (_, Ok(None)) => return Ok((matches_count, false)),
+                _ => return Ok((matches_count, true)),
+            };
+            let mut bet_for = pages[page_for].bets[index_for];
+            let mut bet_against = pages[page_against].bets[index_against];
+            if !bet_for.crosses(&bet_against) {
+                return Ok((matches_count, false));
+            }
+            // the crossing front bets still in their bet delay are matched by a later call
+            if matches_count == max_matches
+                || !self.is_accepted(&bet_for, now)?
+                || !self.is_accepted(&bet_against, now)?
+                || Self::find_position(positions, &bet_for.bettor).is_none()
+                || Self::find_position(positions, &bet_against.bettor).is_none()
+            {
+                return Ok((matches_count, true));
+            }
+
+            self.fill(positions, &mut bet_for, &mut bet_against)?;
+
+            self.update_bet(positions, &mut pages[page_for], index_for, bet_for);
+            self.update_bet(positions, &mut pages[page_against], index_against, bet_against);
+            matches_count += 1;
+        }
+    }
+    // Matches a new bet against the opposite queue right away until it is filled or no longer crosses, the
+    // unmatched rest never rests in the book, neither do the resting bets whose positions were not passed. Returns the
+    // dealt wager and the payout of the bet.
+    #[allow(clippy::too_many_arguments)]
+    pub fn take_bet<P: DerefMut<Target = BookPage>, Q: DerefMut<Target = Position>>(
+        &mut self,
+        pages: &mut [P],
+        positions: &mut [Q],
+        odds: u32,
+        wager: u64,
+        bettor: Pubkey,
+        bet_direction: BetDirection,
+        now: i64,
+    ) -> Result<(u64, u64)> {
+        let position = Self::find_position(positions, &bettor).ok_or(BettingError::InvalidPositions)?;
+        let mut bet = self.bet(odds, wager, bettor, now, 0);
+        let mut payout = 0;
+        while bet.wager > 0 {
+            let (page, index) = match self.live_front(pages, bet_direction.opposite(), now) {
+                Ok(Some(front)) => front,
+                _ => break,
+            };
+            let mut counter_bet = pages[page].bets[index];
+            let crosses = match bet_direction {
+                BetDirection::For => bet.crosses(&counter_bet),
+                BetDirection::Against => counter_bet.crosses(&bet),
+            };
+            if !crosses
+                || !self.is_accepted(&counter_bet, now)?
+                || Self::find_position(positions, &counter_bet.bettor).is_none()
+            {
+                break;
             }
+            payout += match bet_direction {
+                BetDirection::For => self.fill(positions, &mut bet, &mut counter_bet)?,
+                BetDirection::Against => self.fill(positions, &mut counter_bet, &mut bet)?,
+            };
+            self.update_bet(positions, &mut pages[page], index, counter_bet);
+        }
+        let dealt_wager = wager - bet.wager;
+
+        self.bets_count += 1;
+        self.wager_total += dealt_wager;
+        let position = &mut positions[position];
+        position.bets_count += 1;
+        position.wager += dealt_wager;
+
+        Ok((dealt_wager, payout))
+    }
+    // writes a partly filled bet back to its page, or removes it once it is filled completely
+    fn update_bet<Q: DerefMut<Target = Position>>(
+        &mut self,
+        positions: &mut [Q],
+        page: &mut BookPage,
+        index: usize,
+        bet: Bet,
+    ) {
+        if bet.wager > 0 {
+            page.bets[index] = bet;
+        } else {
+            page.remove(index);
+            self.update_level(page);
+            let position = Self::find_position(positions, &bet.bettor).unwrap();
+            positions[position].active_bets_count -= 1;
         }
-        matched
+    }
+    // both sides of a fill are credited the same payout, returns the payout
+    fn fill<Q: DerefMut<Target = Position>>(
+        &mut self,
+        positions: &mut [Q],
+        bet_for: &mut Bet,
+        bet_against: &mut Bet,
+    ) -> Result<u64> {
+        let (payout, for_dealt_wager, against_dealt_wager) = bet_for.fill(bet_against)?;
+
+        self.payout_for_total = self
+            .payout_for_total
+            .checked_add(payout)
+            .ok_or(BettingError::MathOverflow)?;
+        self.payout_against_total = self
+            .payout_against_total
+            .checked_add(payout)
+            .ok_or(BettingError::MathOverflow)?;
+        self.dealt_wager = self
+            .dealt_wager
+            .checked_add(for_dealt_wager)
+            .and_then(|w| w.checked_add(against_dealt_wager))
+            .ok_or(BettingError::MathOverflow)?;
+
+        let position_for = Self::find_position(positions, &bet_for.bettor).unwrap();
+        let position_for = &mut positions[position_for];
+        position_for.payout_for += payout;
+        position_for.dealt_wager += for_dealt_wager;
+        let position_against = Self::find_position(positions, &bet_against.bettor).unwrap();
+        let position_against = &mut positions[position_against];
+        position_against.payout_against += payout;
+        position_against.dealt_wager += against_dealt_wager;
+
+        Ok(payout)
     }
 }
 
@@ -226,91 +866,350 @@ pub enum BetDirection {
     For,
     Against,
 }
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
-pub struct Oracle {
-    pub stake: u64,
-    pub outcome: Option<BetOutcome>,
+impl BetDirection {
+    pub fn opposite(&self) -> BetDirection {
+        match self {
+            BetDirection::For => BetDirection::Against,
+            BetDirection::Against => BetDirection::For,
+        }
+    }
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OrderType {
+    // rests in the book until matched by the crank
+    Limit,
+    // matches right away, the unmatched rest is refunded
+    ImmediateOrCancel,
+    // matches right away in full or fails
+    FillOrKill,
+    // rests in the book, fails if it would match right away
+    PostOnly,
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BetExpiry {
+    At(i64),
+    Kickoff,
 }
-impl Oracle {
-    pub const INIT_SPACE: usize = 8 + 1 + BetOutcome::INIT_SPACE;
+// selects resting bets by direction and by an inclusive odds range, unset fields match every bet
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BetFilter {
+    pub bet_direction: Option<BetDirection>,
+    pub min_odds: Option<u32>,
+    pub max_odds: Option<u32>,
 }
+impl BetFilter {
+    pub fn matches(&self, bet: &Bet, bet_direction: BetDirection) -> bool {
+        let odds = bet.odds();
+        !matches!(self.bet_direction, Some(d) if d != bet_direction)
+            && !matches!(self.min_odds, Some(m) if odds < m)
+            && !matches!(self.max_odds, Some(m) if odds > m)
+    }
+}
+// the minimum number of revealed oracles, the minimum revealed oracle stake in basis points of the dealt wager and how
+// the revealed outcomes are aggregated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quorum {
+    pub min_oracles: u32,
+    pub min_stake_ratio: u64,
+    pub rule: AggregationRule,
+}
+impl Quorum {
+    pub const INIT_SPACE: usize = 4 + 8 + AggregationRule::INIT_SPACE;
 
+    pub fn is_valid(&self) -> bool {
+        let is_valid_rule = match self.rule {
+            AggregationRule::Supermajority { threshold } => (MIN_QUORUM_SUPERMAJORITY..=10000).contains(&threshold),
+            AggregationRule::Majority | AggregationRule::Median => true,
+        };
+        (MIN_QUORUM_ORACLES..=MAX_QUORUM_ORACLES).contains(&self.min_oracles)
+            && (MIN_QUORUM_STAKE_RATIO..=MAX_QUORUM_STAKE_RATIO).contains(&self.min_stake_ratio)
+            && is_valid_rule
+    }
+}
+// the parameters of a book are the ones of the config unless the initiator overrides them within the global limits
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookParams {
+    pub min_bet_amount: u64,
+    pub min_oracle_stake: u64,
+    pub min_bettor_dispute_stake: u64,
+    pub oracle_update_window: i64,
+    pub bettor_dispute_window: i64,
+    // the fee split of the config, fixed for the life of the book
+    pub bettor_payout_rate: u64,
+    pub oracles_reward_share: u64,
+    pub initiator_reward_share: u64,
+}
+impl BookParams {
+    pub const INIT_SPACE: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            min_bet_amount: config.min_bet_amount,
+            min_oracle_stake: config.min_oracle_stake,
+            min_bettor_dispute_stake: config.min_bettor_dispute_stake,
+            oracle_update_window: config.oracle_update_window,
+            bettor_dispute_window: config.bettor_dispute_window,
+            bettor_payout_rate: config.bettor_payout_rate,
+            oracles_reward_share: config.oracles_reward_share,
+            initiator_reward_share: config.initiator_reward_share,
+        }
+    }
+    // the payout rate and the oracle reward are the ones of the config, the initiator reward is a share of the fee next
+    // to the oracle reward
+    pub fn is_valid(&self, config: &Config) -> bool {
+        self.min_bet_amount >= MIN_BOOK_BET_AMOUNT
+            && self.min_oracle_stake >= MIN_BOOK_ORACLE_STAKE
+            && self.min_bettor_dispute_stake >= MIN_BOOK_BETTOR_DISPUTE_STAKE
+            && self.oracle_update_window >= MIN_BOOK_ORACLE_UPDATE_WINDOW
+            && self.oracle_update_window >= ORACLE_REVEAL_WINDOW
+            && self.bettor_dispute_window > self.oracle_update_window
+            && self.bettor_dispute_window <= MAX_BOOK_BETTOR_DISPUTE_WINDOW
+            && self.initiator_reward_share <= MAX_BOOK_INITIATOR_REWARD_SHARE
+            && self.bettor_payout_rate == config.bettor_payout_rate
+            && self.oracles_reward_share == config.oracles_reward_share
+            && matches!(self.oracles_reward_share.checked_add(self.initiator_reward_share), Some(s) if s <= 10000)
+    }
+}
+impl Default for BookParams {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AggregationRule {
+    // the outcome with the most revealed stake
+    #[default]
+    Majority,
+    // the outcome with at least the threshold of the revealed stake in basis points
+    Supermajority {
+        threshold: u64,
+    },
+    // the stake weighted median of the revealed outcomes, ordered from against to for
+    Median,
+}
+impl AggregationRule {
+    pub const INIT_SPACE: usize = 1 + 8;
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dispute {
+    pub round: u8,
+    // the outcome proposed by the latest round and its bond
+    pub outcome: BetOutcome,
+    pub bond: u64,
+    // the end of the window to counter the latest round
+    pub ends_at: i64,
+    // escalated disputes are resolved by the council
+    pub escalated: bool,
+    // the council missed the resolution deadline
+    pub timed_out: bool,
+}
+impl Dispute {
+    pub const INIT_SPACE: usize = 1 + BetOutcome::INIT_SPACE + 8 + 8 + 1 + 1;
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DelayedLevel {
+    pub bet_direction: BetDirection,
+    pub odds: u32,
+    // the index of the page within the price level
+    pub index: u16,
+    // the end of the bet delay of the latest bet placed at the level
+    pub delayed_until: i64,
+}
+impl DelayedLevel {
+    pub const INIT_SPACE: usize = 1 + 4 + 2 + 8;
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LevelPages {
+    pub bet_direction: BetDirection,
+    pub odds: u32,
+    // the index of the first page with resting bets
+    pub head: u16,
+    // the number of resting bets of every page from the head on, new bets rest in the last one
+    pub lens: Vec<u16>,
+}
+impl LevelPages {
+    pub fn space(&self) -> usize {
+        1 + 4 + 2 + 4 + 2 * self.lens.len()
+    }
+    pub fn tail(&self) -> u16 {
+        self.head + self.lens.len() as u16 - 1
+    }
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InPlay {
+    // seconds a bet placed in play waits before it can be matched
+    pub bet_delay: i64,
+    pub suspended_until: i64,
+}
+impl InPlay {
+    pub const INIT_SPACE: usize = 8 + 8;
+}
+
+// handicaps are whole goals added to the home team's score,
+// total goal lines are counted in half goals, 5 means 2.5 goals,
+// asian handicap lines are counted in quarter goals added to the home team's score, -3 means -0.75
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BetType {
     One { handicap: i8 },
     X { handicap: i8 },
     Two { handicap: i8 },
+    OverUnder { line: u8 },
+    AsianHandicap { line: i8 },
 }
 impl BetType {
-    pub const INIT_SPACE: usize = 1;
+    pub const INIT_SPACE: usize = 1 + 1;
+
+    pub fn outcome(&self, home_score: u8, away_score: u8) -> BetOutcome {
+        let win_or_lose = |win: bool| if win { BetOutcome::For } else { BetOutcome::Against };
+        match *self {
+            BetType::One { handicap } => win_or_lose(home_score as i16 + handicap as i16 > away_score as i16),
+            BetType::X { handicap } => win_or_lose(home_score as i16 + handicap as i16 == away_score as i16),
+            BetType::Two { handicap } => win_or_lose((home_score as i16 + handicap as i16) < away_score as i16),
+            // over wins, whole goal lines are a push when the total lands on the line
+            BetType::OverUnder { line } => match (2 * (home_score as u16 + away_score as u16)).cmp(&(line as u16)) {
+                Ordering::Greater => BetOutcome::For,
+                Ordering::Equal => BetOutcome::Cancel,
+                Ordering::Less => BetOutcome::Against,
+            },
+            // the home team covering wins, quarter lines split the stake over the two neighbouring lines
+            BetType::AsianHandicap { line } => match 4 * (home_score as i16 - away_score as i16) + line as i16 {
+                0 => BetOutcome::Cancel,
+                1 => BetOutcome::HalfFor,
+                -1 => BetOutcome::HalfAgainst,
+                diff if diff > 0 => BetOutcome::For,
+                _ => BetOutcome::Against,
+            },
+        }
+    }
+    // any book can be cancelled, e.g. when the game is abandoned, only quarter asian handicap lines settle half
+    pub fn is_valid_outcome(&self, outcome: BetOutcome) -> bool {
+        match outcome {
+            BetOutcome::For | BetOutcome::Against | BetOutcome::Cancel => true,
+            BetOutcome::HalfFor | BetOutcome::HalfAgainst => {
+                matches!(*self, BetType::AsianHandicap { line } if line % 2 != 0)
+            }
+        }
+    }
 }
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BetOutcome {
     For,
     Cancel,
     Against,
+    // half of the stakes are settled as for, the other half is refunded
+    HalfFor,
+    // half of the stakes are settled as against, the other half is refunded
+    HalfAgainst,
 }
 impl BetOutcome {
     pub const INIT_SPACE: usize = 2;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[zero_copy]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
 pub struct Bet {
     pub id: u64,
     pub bettor: Pubkey,
     pub wager: u64,
+    pub placed_at: i64,
+    // unmatched bets lapse at this time, zero if they never do
+    pub expires_at: i64,
 }
 
 impl Bet {
-    pub const INIT_SPACE: usize = 8 + 32 + 8;
+    pub const INIT_SPACE: usize = 8 + 32 + 8 + 8 + 8;
+    // odds are stored in thousandths, 1500 means 1.5
+    pub const ODDS_PRECISION: u64 = 1000;
 
     pub fn odds(&self) -> u32 {
         (self.id >> 32) as u32
     }
-
-    pub fn odds_f(&self) -> f64 {
-        (self.odds() as f64) / 1000.0
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && self.expires_at <= now
     }
 
-    pub fn opposite_odds(&self) -> f64 {
-        1.0 / (self.odds_f() - 1.0) + 1.0
+    // whether this bet for and the given bet against can be matched, i.e. the implied probabilities of the two
+    // sides add up to at least one: 1 / odds_for + 1 / odds_against >= 1
+    pub fn crosses(&self, bet_against: &Bet) -> bool {
+        let odds_for = self.odds() as u128;
+        let odds_against = bet_against.odds() as u128;
+        let precision = Self::ODDS_PRECISION as u128;
+        odds_for * odds_against <= precision * (odds_for + odds_against)
     }
-    pub fn payout(&self) -> u64 {
-        ((self.wager as f64) * self.odds_f()).floor() as u64
+    // fills this bet for and the given bet against until one of them is filled completely, both sides are credited
+    // the same payout and the counter side deals the rest of it, so a crossing spread improves the price of the counter
+    // side, returns the payout and the dealt wagers of both sides
+    pub fn fill(&mut self, bet_against: &mut Bet) -> Result<(u64, u64, u64)> {
+        let (payout_for, payout_against) = (self.payout()?, bet_against.payout()?);
+        let (payout, for_dealt_wager, against_dealt_wager) = if payout_for <= payout_against {
+            // the bet for is filled completely
+            let against_dealt_wager = payout_for.checked_sub(self.wager).ok_or(BettingError::MathOverflow)?;
+            (payout_for, self.wager, against_dealt_wager)
+        } else {
+            // the bet against is filled completely
+            let for_dealt_wager = payout_against
+                .checked_sub(bet_against.wager)
+                .ok_or(BettingError::MathOverflow)?;
+            (payout_against, for_dealt_wager, bet_against.wager)
+        };
+        self.wager -= for_dealt_wager;
+        bet_against.wager -= against_dealt_wager;
+        Ok((payout, for_dealt_wager, against_dealt_wager))
     }
-    pub fn get_payout_by_wager(&self, wager: u64) -> u64 {
-        ((wager as f64) * self.odds_f()).floor() as u64
+    // rounded down
+    pub fn payout(&self) -> Result<u64> {
+        self.get_payout_by_wager(self.wager)
     }
-    pub fn get_wager_by_payout(&self, payout: u64) -> u64 {
-        (payout as f64 / self.odds_f()).ceil() as u64
+    // rounded down, fails if the payout does not fit in a u64
+    pub fn get_payout_by_wager(&self, wager: u64) -> Result<u64> {
+        let payout = (wager as u128) * (self.odds() as u128) / (Self::ODDS_PRECISION as u128);
+        u64::try_from(payout).map_err(|_| error!(BettingError::MathOverflow))
+    }
+    // rounded up, so that the payout of the returned wager is never less than the given payout
+    pub fn get_wager_by_payout(&self, payout: u64) -> Result<u64> {
+        let odds = self.odds() as u128;
+        let numerator = (payout as u128) * (Self::ODDS_PRECISION as u128);
+        let mut wager = numerator / odds;
+        if wager * odds < numerator {
+            wager += 1;
+        }
+        u64::try_from(wager).map_err(|_| error!(BettingError::MathOverflow))
     }
-}
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
-pub struct Position {
-    pub active_bets_count: u32, // 4
-    pub bets_count: u32,        // 4
-    pub payout_for: u64,        // 8
-    pub payout_against: u64,    // 8
-    pub wager: u64,             // 8
-    pub dealt_wager: u64,       // 8
-    pub dispute_stake: u64,     // 8
-}
-impl Position {
-    pub const INIT_SPACE: usize = 4 + 4 + 8 + 8 + 8 + 8 + 8;
 }
 
 #[cfg(test)]
 mod test {
-    use std::collections::{BTreeMap, VecDeque};
-
     use anchor_lang::AccountSerialize;
     use solana_sdk::pubkey::Pubkey;
 
-    use crate::state::{BetDirection, BetOutcome, Oracle, Position};
+    use crate::{
+        constants::{
+            COUNCIL_RESOLUTION_WINDOW, DISPUTE_ROUND_WINDOW, MAX_BOOK_BETTOR_DISPUTE_WINDOW,
+            MAX_BOOK_INITIATOR_REWARD_SHARE, MAX_QUORUM_ORACLES, MAX_QUORUM_STAKE_RATIO, MIN_BETTOR_DISPUTE_STAKE,
+            MIN_BOOK_BETTOR_DISPUTE_STAKE, MIN_BOOK_BET_AMOUNT, MIN_BOOK_ORACLE_STAKE, MIN_BOOK_ORACLE_UPDATE_WINDOW,
+            ORACLE_REVEAL_WINDOW,
+        },
+        state::{
+            AggregationRule, Bet, BetDirection, BetFilter, BetOutcome, BookPage, BookParams, Config, DelayedLevel,
+            InPlay, LevelPages, Position, Quorum,
+        },
+    };
 
     use super::{BetType, Book};
 
+    fn page(bet_direction: BetDirection, odds: u32) -> Box<BookPage> {
+        let mut page: Box<BookPage> = Box::new(bytemuck::Zeroable::zeroed());
+        page.bet_direction = bet_direction as u8;
+        page.odds = odds;
+        page
+    }
+    fn position(bettor: Pubkey) -> Box<Position> {
+        Box::new(Position {
+            bettor,
+            ..Default::default()
+        })
+    }
+
     #[test]
     fn test_state_book_init_space() {
         let book = Book {
@@ -326,10 +1225,23 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: None,
             concluded_at: None,
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
         let mut book_data: Vec<u8> = Vec::new();
@@ -351,33 +1263,41 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: Some(BetOutcome::For),
             concluded_at: Some(2),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
-        book.oracles.insert(
-            Pubkey::new_unique(),
-            Oracle {
-                stake: 123,
-                outcome: None,
-            },
-        );
+        book.oracles_count += 1;
+        book.add_oracle_stake(None, 123);
         let bettor_key = Pubkey::new_unique();
-        book.positions.insert(
+        let mut positions = vec![position(bettor_key)];
+        let mut pages = vec![page(BetDirection::For, 123)];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            123,
+            123,
             bettor_key,
-            Position {
-                active_bets_count: 0,
-                bets_count: 0,
-                payout_for: 0,
-                payout_against: 0,
-                wager: 0,
-                dealt_wager: 0,
-                dispute_stake: 0,
-            },
-        );
-        book.new_bet(123, 123, bettor_key, BetDirection::For);
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
         let mut book_data: Vec<u8> = Vec::new();
         book.try_serialize(&mut book_data).unwrap();
         assert!(book_data.len() <= book.current_space());
@@ -397,34 +1317,1461 @@ mod test {
             total_dispute_stake: 0,
             dispute_resolution_result: Some(BetOutcome::For),
             concluded_at: Some(2),
-            oracles: BTreeMap::new(),
-            bets_for: VecDeque::new(),
-            bets_against: VecDeque::new(),
-            positions: BTreeMap::new(),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
             aggregated_oracle_outcome: None,
         };
 
         let bettor_key = Pubkey::new_unique();
-        book.positions.insert(
+        let mut positions = vec![position(bettor_key)];
+        let mut pages = vec![page(BetDirection::For, 123)];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            123,
+            123,
             bettor_key,
-            Position {
-                active_bets_count: 0,
-                bets_count: 0,
-                payout_for: 0,
-                payout_against: 0,
-                wager: 0,
-                dealt_wager: 0,
-                dispute_stake: 0,
-            },
-        );
-        book.new_bet(123, 123, bettor_key, BetDirection::For);
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
 
         assert_eq!(book.bets_count, 1);
         assert_eq!(book.wager_total, 123);
-        assert_eq!(book.bets_for.len(), 1);
-        assert_eq!(book.bets_for[0].odds(), 123);
-        assert_eq!(book.bets_for[0].id, 528280977408);
-        assert_eq!(book.positions[&bettor_key].bets_count, 1);
-        assert_eq!(book.positions[&bettor_key].wager, 123);
+        assert_eq!(book.levels_for, vec![123]);
+        assert_eq!(pages[0].bets().len(), 1);
+        assert_eq!(pages[0].bets()[0].odds(), 123);
+        assert_eq!(pages[0].bets()[0].id, 528280977408);
+        assert_eq!(positions[0].bets_count, 1);
+        assert_eq!(positions[0].wager, 123);
+
+        // the position of the bettor and the page of the price level must be passed and have room for the bet
+        assert!(book
+            .new_bet(
+                &mut pages,
+                &mut positions[..0],
+                123,
+                123,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0
+            )
+            .is_err());
+        assert!(book
+            .new_bet(
+                &mut pages,
+                &mut positions,
+                124,
+                123,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0
+            )
+            .is_err());
+        assert!(book
+            .new_bet(
+                &mut pages,
+                &mut positions,
+                123,
+                123,
+                bettor_key,
+                BetDirection::Against,
+                0,
+                0
+            )
+            .is_err());
+        while !pages[0].is_full() {
+            book.new_bet(
+                &mut pages,
+                &mut positions,
+                123,
+                123,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        }
+        assert!(book
+            .new_bet(
+                &mut pages,
+                &mut positions,
+                123,
+                123,
+                bettor_key,
+                BetDirection::For,
+                0,
+                0
+            )
+            .is_err());
+    }
+    #[test]
+    fn test_state_bet_fixed_point_math() {
+        let bet = Bet {
+            id: u64::from_le_bytes([0x00, 0x00, 0x00, 0x00, 0x35, 0x05, 0x00, 0x00]),
+            bettor: Pubkey::new_unique(),
+            wager: 10,
+            placed_at: 0,
+            expires_at: 0,
+        };
+        assert_eq!(bet.odds(), 1333);
+        // payouts are rounded down
+        assert_eq!(bet.payout().unwrap(), 13);
+        assert_eq!(bet.get_payout_by_wager(1000000 * 3).unwrap(), 3999000);
+        // wagers are rounded up
+        assert_eq!(bet.get_wager_by_payout(13).unwrap(), 10);
+        assert_eq!(bet.get_wager_by_payout(14).unwrap(), 11);
+        assert_eq!(bet.get_wager_by_payout(3999000).unwrap(), 1000000 * 3);
+        assert!(bet.get_payout_by_wager(bet.get_wager_by_payout(14).unwrap()).unwrap() >= 14);
+        // payouts beyond a u64 fail instead of panicking
+        assert!(bet.get_payout_by_wager(u64::MAX).is_err());
+        assert!(bet.get_payout_by_wager(u64::MAX / 2).is_ok());
+    }
+    #[test]
+    fn test_state_bet_crosses() {
+        let bet_with_odds = |odds: u32| Bet {
+            id: (odds as u64) << 32,
+            bettor: Pubkey::new_unique(),
+            wager: 1000000,
+            placed_at: 0,
+            expires_at: 0,
+        };
+        assert!(bet_with_odds(2000).crosses(&bet_with_odds(2000)));
+        assert!(bet_with_odds(1900).crosses(&bet_with_odds(2000)));
+        assert!(bet_with_odds(1200).crosses(&bet_with_odds(6000)));
+        assert!(!bet_with_odds(2100).crosses(&bet_with_odds(2000)));
+        assert!(!bet_with_odds(1201).crosses(&bet_with_odds(6000)));
+    }
+    #[test]
+    fn test_state_book_match_bets() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+        let mut positions = vec![position(bettor_for), position(bettor_against)];
+        let mut pages = vec![page(BetDirection::For, 1900), page(BetDirection::Against, 2100)];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            1900,
+            1000000 * 100,
+            bettor_for,
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2100,
+            1000000 * 200,
+            bettor_against,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 1, 0).unwrap(), (1, false));
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 1, 0).unwrap(), (0, false));
+
+        // both sides get the same payout, the wager against covers the rest of it at better odds than asked for
+        assert_eq!(book.payout_for_total, 1000000 * 190);
+        assert_eq!(book.payout_against_total, 1000000 * 190);
+        assert_eq!(book.dealt_wager, 1000000 * 190);
+        assert!(book.levels_for.is_empty());
+        assert!(pages[0].bets().is_empty());
+        assert_eq!(pages[1].bets().len(), 1);
+        assert_eq!(pages[1].bets()[0].wager, 1000000 * 200 - 1000000 * 90);
+        assert_eq!(positions[0].payout_for, 1000000 * 190);
+        assert_eq!(positions[0].dealt_wager, 1000000 * 100);
+        assert_eq!(positions[0].active_bets_count, 0);
+        assert_eq!(positions[1].payout_against, 1000000 * 190);
+        assert_eq!(positions[1].dealt_wager, 1000000 * 90);
+        assert_eq!(positions[1].active_bets_count, 1);
+    }
+    #[test]
+    fn test_state_book_match_bets_bounded() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+        let mut positions = vec![position(bettor_for), position(bettor_against)];
+        let mut pages = vec![page(BetDirection::For, 2000), page(BetDirection::Against, 2000)];
+        for _ in 0..3 {
+            book.new_bet(
+                &mut pages,
+                &mut positions,
+                2000,
+                1000000 * 10,
+                bettor_for,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+        }
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 30,
+            bettor_against,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+
+        // matching stops after the max number of fills or at a missing page and reports the rest
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 0, 0).unwrap(), (0, true));
+        assert_eq!(
+            book.match_bets(&mut pages, &mut positions[..1], 10, 0).unwrap(),
+            (0, true)
+        );
+        assert_eq!(
+            book.match_bets(&mut pages[..1], &mut positions, 10, 0).unwrap(),
+            (0, true)
+        );
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 2, 0).unwrap(), (2, true));
+        assert_eq!(pages[0].bets().len(), 1);
+        assert_eq!(pages[1].bets()[0].wager, 1000000 * 10);
+        // and resumes from the fronts
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 2, 0).unwrap(), (1, false));
+        assert!(book.levels_for.is_empty());
+        assert!(book.levels_against.is_empty());
+        assert_eq!(book.payout_for_total, 1000000 * 60);
+    }
+    #[test]
+    fn test_state_book_level_overflow() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_for1 = Pubkey::new_unique();
+        let bettor_for2 = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+        let mut positions = vec![position(bettor_for1), position(bettor_for2), position(bettor_against)];
+        let mut pages = vec![
+            page(BetDirection::For, 2000),
+            page(BetDirection::For, 2000),
+            page(BetDirection::Against, 2000),
+        ];
+        pages[1].index = 1;
+        for _ in 0..BookPage::CAPACITY {
+            book.new_bet(
+                &mut pages[..1],
+                &mut positions,
+                2000,
+                1000000 * 10,
+                bettor_for1,
+                BetDirection::For,
+                0,
+                10,
+            )
+            .unwrap();
+        }
+
+        // a full level overflows into its next page
+        assert!(book
+            .new_bet(
+                &mut pages[..1],
+                &mut positions,
+                2000,
+                1000000 * 10,
+                bettor_for2,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .is_err());
+        book.new_bet(
+            &mut pages[..2],
+            &mut positions,
+            2000,
+            1000000 * 10,
+            bettor_for2,
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
+        assert_eq!(pages[1].bets().len(), 1);
+        assert_eq!(
+            book.level_pages,
+            vec![LevelPages {
+                bet_direction: BetDirection::For,
+                odds: 2000,
+                head: 0,
+                lens: vec![BookPage::CAPACITY as u16, 1],
+            }]
+        );
+        let mut book_data: Vec<u8> = Vec::new();
+        book.try_serialize(&mut book_data).unwrap();
+        assert!(book_data.len() <= book.current_space());
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 10,
+            bettor_against,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+
+        // the pages are matched in order, the emptied first page is no longer needed
+        assert_eq!(
+            book.match_bets(&mut pages[1..], &mut positions, 10, 10).unwrap(),
+            (0, true)
+        );
+        assert_eq!(
+            book.purge_expired_bets(&mut pages[..1], &mut positions, &bettor_for1, 10)
+                .unwrap(),
+            1000000 * 10 * BookPage::CAPACITY as u64
+        );
+        assert_eq!(book.level_pages[0].head, 1);
+        assert_eq!(book.level_pages[0].lens, vec![1]);
+        assert_eq!(
+            book.match_bets(&mut pages[1..], &mut positions, 10, 10).unwrap(),
+            (1, false)
+        );
+        assert!(book.levels_for.is_empty());
+        assert!(book.level_pages.is_empty());
+    }
+    #[test]
+    fn test_state_book_take_bet() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let maker = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+        let mut positions = vec![position(maker), position(taker)];
+        let mut pages = vec![page(BetDirection::Against, 2000), page(BetDirection::Against, 3000)];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 10,
+            maker,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            3000,
+            1000000 * 10,
+            maker,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+
+        assert!(book.crosses_front(&pages, 2000, BetDirection::For, 0).unwrap());
+        assert!(!book.crosses_front(&pages, 2001, BetDirection::For, 0).unwrap());
+        assert!(!book.crosses_front(&pages, 1000, BetDirection::Against, 0).unwrap());
+        assert!(book.crosses_front(&pages[1..], 2000, BetDirection::For, 0).is_err());
+
+        // the taker is matched against both resting bets and covers the rest of their payouts, the rest is left
+        // unmatched
+        let (dealt_wager, payout) = book
+            .take_bet(
+                &mut pages,
+                &mut positions,
+                1500,
+                1000000 * 100,
+                taker,
+                BetDirection::For,
+                0,
+            )
+            .unwrap();
+        assert!(book.levels_for.is_empty());
+        assert!(book.levels_against.is_empty());
+        assert_eq!(payout, 1000000 * 50);
+        assert_eq!(dealt_wager, 1000000 * 30);
+        assert_eq!(book.bets_count, 3);
+        assert_eq!(book.wager_total, 1000000 * 20 + 1000000 * 30);
+        assert_eq!(positions[1].active_bets_count, 0);
+        assert_eq!(positions[1].wager, 1000000 * 30);
+        assert_eq!(positions[1].payout_for, 1000000 * 50);
+        assert_eq!(positions[0].active_bets_count, 0);
+        assert_eq!(positions[0].payout_against, 1000000 * 50);
+    }
+    #[test]
+    fn test_state_book_expiry() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor1 = Pubkey::new_unique();
+        let bettor2 = Pubkey::new_unique();
+        let mut positions = vec![position(bettor1), position(bettor2)];
+        let mut pages = vec![
+            page(BetDirection::For, 1500),
+            page(BetDirection::For, 2000),
+            page(BetDirection::Against, 2000),
+        ];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            1500,
+            1000000 * 10,
+            bettor1,
+            BetDirection::For,
+            0,
+            100,
+        )
+        .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 10,
+            bettor1,
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 10,
+            bettor2,
+            BetDirection::Against,
+            0,
+            0,
+        )
+        .unwrap();
+
+        // the expired bet at the front is skipped
+        assert!(book.crosses_front(&pages, 3000, BetDirection::Against, 99).unwrap());
+        assert!(!book.crosses_front(&pages, 3000, BetDirection::Against, 100).unwrap());
+        assert_eq!(
+            book.match_bets(&mut pages, &mut positions, 10, 100).unwrap(),
+            (1, false)
+        );
+        assert_eq!(book.levels_for, vec![1500]);
+        assert!(book.levels_against.is_empty());
+
+        // only the expired bets of the bettor are purged
+        assert_eq!(
+            book.purge_expired_bets(&mut pages, &mut positions, &bettor2, 100)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            book.purge_expired_bets(&mut pages, &mut positions, &bettor1, 99)
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            book.purge_expired_bets(&mut pages, &mut positions, &bettor1, 100)
+                .unwrap(),
+            1000000 * 10
+        );
+        assert!(book.levels_for.is_empty());
+        assert_eq!(book.wager_total, 1000000 * 20);
+        assert_eq!(positions[0].active_bets_count, 0);
+        assert_eq!(positions[0].wager, 1000000 * 10);
+    }
+    #[test]
+    fn test_state_book_amend_bet() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor1 = Pubkey::new_unique();
+        let bettor2 = Pubkey::new_unique();
+        let mut positions = vec![position(bettor1), position(bettor2)];
+        let mut pages = vec![page(BetDirection::For, 1500), page(BetDirection::For, 2000)];
+        let bet_id = book
+            .new_bet(
+                &mut pages,
+                &mut positions,
+                2000,
+                1000000 * 10,
+                bettor1,
+                BetDirection::For,
+                0,
+                100,
+            )
+            .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000 * 10,
+            bettor2,
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
+
+        // reducing the wager keeps the priority
+        assert_eq!(
+            book.amend_bet(
+                &mut pages,
+                &mut positions,
+                bet_id,
+                BetDirection::For,
+                2000,
+                1000000 * 5,
+                1
+            )
+            .unwrap(),
+            bet_id
+        );
+        assert_eq!(pages[1].bets()[0].bettor, bettor1);
+        assert_eq!(pages[1].bets()[0].wager, 1000000 * 5);
+        assert_eq!(pages[1].bets()[0].placed_at, 0);
+        assert_eq!(book.bets_count, 2);
+        assert_eq!(book.wager_total, 1000000 * 15);
+        assert_eq!(positions[0].wager, 1000000 * 5);
+
+        // raising the wager requeues the bet behind the bets at the same odds
+        let amended_bet_id = book
+            .amend_bet(
+                &mut pages,
+                &mut positions,
+                bet_id,
+                BetDirection::For,
+                2000,
+                1000000 * 20,
+                1,
+            )
+            .unwrap();
+        assert_ne!(amended_bet_id, bet_id);
+        assert_eq!(pages[1].bets()[0].bettor, bettor2);
+        assert_eq!(pages[1].bets()[1].id, amended_bet_id);
+        assert_eq!(pages[1].bets()[1].wager, 1000000 * 20);
+        assert_eq!(pages[1].bets()[1].placed_at, 1);
+        assert_eq!(pages[1].bets()[1].expires_at, 100);
+        assert_eq!(book.bets_count, 3);
+        assert_eq!(book.wager_total, 1000000 * 30);
+        assert_eq!(positions[0].wager, 1000000 * 20);
+        assert_eq!(positions[0].bets_count, 1);
+        assert_eq!(positions[0].active_bets_count, 1);
+
+        // changing the odds moves the bet to its new place in the queue
+        let amended_bet_id = book
+            .amend_bet(
+                &mut pages,
+                &mut positions,
+                amended_bet_id,
+                BetDirection::For,
+                1500,
+                1000000 * 20,
+                2,
+            )
+            .unwrap();
+        assert_eq!(pages[0].bets()[0].id, amended_bet_id);
+        assert_eq!(pages[0].bets()[0].odds(), 1500);
+        assert_eq!(pages[1].bets().len(), 1);
+        assert_eq!(book.levels_for, vec![1500, 2000]);
+    }
+    #[test]
+    fn test_state_book_cancel_bets() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor1 = Pubkey::new_unique();
+        let bettor2 = Pubkey::new_unique();
+        let mut positions = vec![position(bettor1), position(bettor2)];
+        let mut pages = Vec::new();
+        for odds in [1500, 2000, 2500, 3000] {
+            pages.push(page(BetDirection::For, odds));
+            pages.push(page(BetDirection::Against, odds));
+            book.new_bet(
+                &mut pages,
+                &mut positions,
+                odds,
+                1000000,
+                bettor1,
+                BetDirection::For,
+                0,
+                0,
+            )
+            .unwrap();
+            book.new_bet(
+                &mut pages,
+                &mut positions,
+                odds,
+                1000000,
+                bettor1,
+                BetDirection::Against,
+                0,
+                0,
+            )
+            .unwrap();
+        }
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2000,
+            1000000,
+            bettor2,
+            BetDirection::For,
+            0,
+            0,
+        )
+        .unwrap();
+
+        // the odds range is inclusive
+        let filter = BetFilter {
+            bet_direction: Some(BetDirection::For),
+            min_odds: Some(2000),
+            max_odds: Some(2500),
+        };
+        assert_eq!(
+            book.cancel_bets(&mut pages, &mut positions, &bettor1, &filter).unwrap(),
+            1000000 * 2
+        );
+        assert_eq!(book.levels_for, vec![1500, 2000, 3000]);
+        assert_eq!(book.levels_against.len(), 4);
+        assert_eq!(positions[0].active_bets_count, 6);
+
+        // an empty filter cancels every bet of the bettor
+        assert_eq!(
+            book.cancel_bets(&mut pages, &mut positions, &bettor1, &BetFilter::default())
+                .unwrap(),
+            1000000 * 6
+        );
+        assert_eq!(book.levels_for, vec![2000]);
+        assert_eq!(pages[2].bets()[0].bettor, bettor2);
+        assert!(book.levels_against.is_empty());
+        assert_eq!(book.wager_total, 1000000);
+        assert_eq!(positions[0].active_bets_count, 0);
+        assert_eq!(positions[0].wager, 0);
+        assert_eq!(
+            book.cancel_bets(&mut pages, &mut positions, &bettor1, &BetFilter::default())
+                .unwrap(),
+            0
+        );
+    }
+    #[test]
+    fn test_state_book_in_play() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: Some(InPlay {
+                bet_delay: 5,
+                suspended_until: 0,
+            }),
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+        let mut positions = vec![position(bettor_for), position(bettor_against)];
+        let mut pages = vec![page(BetDirection::For, 1900), page(BetDirection::Against, 2100)];
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            1900,
+            1000000 * 100,
+            bettor_for,
+            BetDirection::For,
+            100,
+            0,
+        )
+        .unwrap();
+        book.new_bet(
+            &mut pages,
+            &mut positions,
+            2100,
+            1000000 * 200,
+            bettor_against,
+            BetDirection::Against,
+            103,
+            0,
+        )
+        .unwrap();
+
+        // the bets can only be matched after the bet delay, the crossing is still reported
+        assert_eq!(book.match_bets(&mut pages, &mut positions, 10, 104).unwrap(), (0, true));
+        assert!(book.is_accepted(&pages[0].bets()[0], 105).unwrap());
+        assert!(!book.is_accepted(&pages[1].bets()[0], 105).unwrap());
+
+        // a suspension voids the bets still in their bet delay, only the pages of the delayed levels are needed
+        assert_eq!(
+            book.delayed_levels,
+            vec![
+                DelayedLevel {
+                    bet_direction: BetDirection::For,
+                    odds: 1900,
+                    index: 0,
+                    delayed_until: 105,
+                },
+                DelayedLevel {
+                    bet_direction: BetDirection::Against,
+                    odds: 2100,
+                    index: 0,
+                    delayed_until: 108,
+                },
+            ]
+        );
+        assert!(book.suspend(&mut pages[..1], &mut positions, 105, 225).is_err());
+        assert!(book.suspend(&mut pages[1..], &mut positions[..1], 105, 225).is_err());
+        book.suspend(&mut pages[1..], &mut positions, 105, 225).unwrap();
+        assert!(book.delayed_levels.is_empty());
+        assert!(book.is_suspended(224));
+        assert!(!book.is_suspended(225));
+        assert_eq!(pages[0].bets().len(), 1);
+        assert!(book.levels_against.is_empty());
+        assert_eq!(positions[0].active_bets_count, 1);
+        assert_eq!(positions[1].active_bets_count, 0);
+        assert_eq!(positions[1].wager, 1000000 * 200);
+    }
+    #[test]
+    fn test_state_bet_type_outcome() {
+        assert_eq!(BetType::One { handicap: 0 }.outcome(2, 1), BetOutcome::For);
+        assert_eq!(BetType::One { handicap: -1 }.outcome(2, 1), BetOutcome::Against);
+        assert_eq!(BetType::X { handicap: 0 }.outcome(1, 1), BetOutcome::For);
+        assert_eq!(BetType::X { handicap: 1 }.outcome(1, 1), BetOutcome::Against);
+        assert_eq!(BetType::Two { handicap: 0 }.outcome(0, 1), BetOutcome::For);
+        assert_eq!(BetType::Two { handicap: 1 }.outcome(0, 1), BetOutcome::Against);
+        // 2.5 goals
+        assert_eq!(BetType::OverUnder { line: 5 }.outcome(2, 1), BetOutcome::For);
+        assert_eq!(BetType::OverUnder { line: 5 }.outcome(1, 1), BetOutcome::Against);
+        // 2 goals
+        assert_eq!(BetType::OverUnder { line: 4 }.outcome(2, 1), BetOutcome::For);
+        assert_eq!(BetType::OverUnder { line: 4 }.outcome(1, 1), BetOutcome::Cancel);
+        assert_eq!(BetType::OverUnder { line: 4 }.outcome(1, 0), BetOutcome::Against);
+    }
+    #[test]
+    fn test_state_bet_type_outcome_asian_handicap() {
+        // -0.25
+        assert_eq!(BetType::AsianHandicap { line: -1 }.outcome(1, 0), BetOutcome::For);
+        assert_eq!(
+            BetType::AsianHandicap { line: -1 }.outcome(1, 1),
+            BetOutcome::HalfAgainst
+        );
+        assert_eq!(BetType::AsianHandicap { line: -1 }.outcome(0, 1), BetOutcome::Against);
+        // -0.75
+        assert_eq!(BetType::AsianHandicap { line: -3 }.outcome(2, 0), BetOutcome::For);
+        assert_eq!(BetType::AsianHandicap { line: -3 }.outcome(1, 0), BetOutcome::HalfFor);
+        assert_eq!(BetType::AsianHandicap { line: -3 }.outcome(1, 1), BetOutcome::Against);
+        // +0.5
+        assert_eq!(BetType::AsianHandicap { line: 2 }.outcome(1, 1), BetOutcome::For);
+        assert_eq!(BetType::AsianHandicap { line: 2 }.outcome(0, 1), BetOutcome::Against);
+        // -1
+        assert_eq!(BetType::AsianHandicap { line: -4 }.outcome(2, 0), BetOutcome::For);
+        assert_eq!(BetType::AsianHandicap { line: -4 }.outcome(1, 0), BetOutcome::Cancel);
+        assert_eq!(BetType::AsianHandicap { line: -4 }.outcome(1, 1), BetOutcome::Against);
+    }
+    #[test]
+    fn test_state_book_total_profit() {
+        let book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 1000,
+            bet_type: BetType::AsianHandicap { line: -1 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::HalfFor),
+        };
+        assert_eq!(book.final_outcome(), Some(BetOutcome::HalfFor));
+        assert_eq!(book.total_profit(BetOutcome::For), 1000000 * 10);
+        assert_eq!(book.total_profit(BetOutcome::Against), 1000000 * 10);
+        assert_eq!(book.total_profit(BetOutcome::HalfFor), 1000000 * 5);
+        assert_eq!(book.total_profit(BetOutcome::HalfAgainst), 1000000 * 5);
+        assert_eq!(book.total_profit(BetOutcome::Cancel), 0);
+    }
+    #[test]
+    fn test_state_book_aggregated_outcome() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        assert_eq!(book.aggregated_outcome(), None);
+        // three oracles opt in
+        book.oracles_count = 3;
+        book.add_oracle_stake(None, 100);
+        book.add_oracle_stake(None, 200);
+        book.add_oracle_stake(None, 300);
+        assert_eq!(book.aggregated_outcome(), None);
+        // the stake of an oracle moves with its outcome
+        book.remove_oracle_stake(None, 300);
+        book.add_oracle_stake(Some(BetOutcome::Against), 300);
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::Against));
+        book.remove_oracle_stake(None, 200);
+        book.add_oracle_stake(Some(BetOutcome::For), 200);
+        book.remove_oracle_stake(None, 100);
+        book.add_oracle_stake(Some(BetOutcome::For), 100);
+        // exact ties never conclude
+        assert_eq!(book.oracle_stakes, [0, 300, 0, 300, 0, 0]);
+        assert_eq!(book.aggregated_outcome(), None);
+        // a simple majority still needs the minimum supermajority
+        book.add_oracle_stake(Some(BetOutcome::For), 1);
+        assert_eq!(book.aggregated_outcome(), None);
+        book.add_oracle_stake(Some(BetOutcome::For), 149);
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::For));
+        // a higher supermajority is not reached by the minimum one
+        book.quorum.rule = AggregationRule::Supermajority { threshold: 7000 };
+        assert_eq!(book.aggregated_outcome(), None);
+        book.add_oracle_stake(Some(BetOutcome::For), 250);
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::For));
+        // the median lies between the outcomes
+        book.quorum.rule = AggregationRule::Median;
+        book.oracle_stakes = [0, 100, 0, 300, 0, 100];
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::Against));
+        book.oracle_stakes = [0, 100, 0, 0, 400, 0];
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::HalfFor));
+        // the median needs the minimum supermajority as well
+        book.oracle_stakes = [0, 300, 0, 100, 150, 100];
+        assert_eq!(book.aggregated_outcome(), None);
+        // a median between two outcomes falls back to cancel, which never holds the supermajority
+        book.oracle_stakes = [0, 200, 0, 200, 0, 0];
+        assert_eq!(book.aggregated_outcome(), None);
+    }
+    #[test]
+    fn test_state_book_slashed_stake_shares() {
+        let mut book = Book {
+            total_oracle_stake: 1000000 * 300,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [1000000 * 50, 1000000 * 100, 0, 1000000 * 150, 0, 0],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        // without disputes the correct oracles get the stake of the wrong and the unrevealed oracles
+        assert!(!book.is_dispute_successful());
+        assert_eq!(book.correct_oracle_stake(BetOutcome::Against), 1000000 * 150);
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Against), (1000000 * 150, 0));
+        // a failed dispute does not share
+        book.new_dispute_round(BetOutcome::For, 1000000 * 30, 0).unwrap();
+        book.dispute.as_mut().unwrap().escalated = true;
+        book.dispute_resolution_result = Some(BetOutcome::Against);
+        assert!(!book.is_dispute_successful());
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Against), (1000000 * 150, 0));
+        // a successful dispute splits the slashed stake
+        book.dispute_resolution_result = Some(BetOutcome::For);
+        assert!(book.is_dispute_successful());
+        assert_eq!(book.correct_oracle_stake(BetOutcome::For), 1000000 * 100);
+        assert_eq!(
+            book.slashed_stake_shares(BetOutcome::For),
+            (1000000 * 100, 1000000 * 100)
+        );
+        // an outcome no round proposed is not a successful dispute
+        book.dispute_resolution_result = Some(BetOutcome::Cancel);
+        assert!(!book.is_dispute_successful());
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Cancel), (0, 0));
+        // without correct oracles the disputers get all of it
+        book.dispute_bonds[BetOutcome::Cancel as usize] += 1000000 * 60;
+        book.total_dispute_stake += 1000000 * 60;
+        assert!(book.is_dispute_successful());
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Cancel), (0, 1000000 * 300));
+        // without either it stays in the book
+        book.dispute = None;
+        book.aggregated_oracle_outcome = Some(BetOutcome::Cancel);
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Cancel), (0, 0));
+    }
+    #[test]
+    fn test_state_book_dispute_rounds() {
+        let mut book = Book {
+            total_oracle_stake: 1000000 * 300,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 0,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: Some(0),
+            reveal_starts_at: None,
+            oracles_count: 3,
+            oracle_stakes: [0, 1000000 * 100, 0, 1000000 * 200, 0, 0],
+            revealed_oracles_count: 3,
+            quorum_timed_out: false,
+            quorum: Quorum::default(),
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: Some(BetOutcome::Against),
+        };
+        assert_eq!(book.next_dispute_round(), 1);
+        assert_eq!(book.final_outcome(), Some(BetOutcome::Against));
+        // the outcome must be one the bet type can settle on
+        assert!(book
+            .new_dispute_round(BetOutcome::HalfFor, MIN_BETTOR_DISPUTE_STAKE, 0)
+            .is_err());
+        // the first round must challenge the oracles with at least the minimum bond
+        assert!(book
+            .new_dispute_round(BetOutcome::Against, MIN_BETTOR_DISPUTE_STAKE, 0)
+            .is_err());
+        assert!(book
+            .new_dispute_round(BetOutcome::For, MIN_BETTOR_DISPUTE_STAKE - 1, 0)
+            .is_err());
+        assert_eq!(
+            book.new_dispute_round(BetOutcome::For, MIN_BETTOR_DISPUTE_STAKE, 0)
+                .unwrap(),
+            1
+        );
+        assert!(book.is_in_dispute(DISPUTE_ROUND_WINDOW));
+        assert_eq!(book.final_outcome(), Some(BetOutcome::For));
+        // a counter round must propose another outcome with at least twice the bond within the window
+        assert!(book
+            .new_dispute_round(BetOutcome::For, MIN_BETTOR_DISPUTE_STAKE * 2, 1)
+            .is_err());
+        assert!(book
+            .new_dispute_round(BetOutcome::Against, MIN_BETTOR_DISPUTE_STAKE * 2 - 1, 1)
+            .is_err());
+        assert!(book
+            .new_dispute_round(
+                BetOutcome::Against,
+                MIN_BETTOR_DISPUTE_STAKE * 2,
+                DISPUTE_ROUND_WINDOW + 1
+            )
+            .is_err());
+        assert_eq!(
+            book.new_dispute_round(BetOutcome::Against, MIN_BETTOR_DISPUTE_STAKE * 2, 1)
+                .unwrap(),
+            2
+        );
+        assert_eq!(book.final_outcome(), Some(BetOutcome::Against));
+        assert_eq!(
+            book.new_dispute_round(BetOutcome::For, MIN_BETTOR_DISPUTE_STAKE * 4, 2)
+                .unwrap(),
+            3
+        );
+        assert_eq!(book.disputes_count, 3);
+        assert_eq!(book.total_dispute_stake, MIN_BETTOR_DISPUTE_STAKE * 7);
+        assert_eq!(
+            book.dispute_bonds,
+            [MIN_BETTOR_DISPUTE_STAKE * 5, 0, MIN_BETTOR_DISPUTE_STAKE * 2, 0, 0]
+        );
+        // the winning rounds take the bonds lost by the other rounds
+        assert_eq!(
+            book.winning_dispute_bonds(BetOutcome::For),
+            MIN_BETTOR_DISPUTE_STAKE * 5
+        );
+        assert_eq!(
+            book.lost_dispute_bonds_shares(BetOutcome::For),
+            (0, MIN_BETTOR_DISPUTE_STAKE * 2)
+        );
+        // without winning rounds the correct oracles take them, without either they stay in the book
+        assert_eq!(book.lost_dispute_bonds_shares(BetOutcome::HalfFor), (0, 0));
+        book.oracle_stakes = [0, 1000000 * 100, 0, 1000000 * 100, 1000000 * 100, 0];
+        assert_eq!(
+            book.lost_dispute_bonds_shares(BetOutcome::HalfFor),
+            (MIN_BETTOR_DISPUTE_STAKE * 7, 0)
+        );
+        // the last round falls through to the council and can not be countered
+        assert_eq!(
+            book.new_dispute_round(BetOutcome::Cancel, MIN_BETTOR_DISPUTE_STAKE * 8, 3)
+                .unwrap(),
+            4
+        );
+        assert!(book.dispute.unwrap().escalated);
+        assert!(!book.is_in_dispute(3));
+        assert_eq!(book.final_outcome(), None);
+        assert!(book
+            .new_dispute_round(BetOutcome::For, MIN_BETTOR_DISPUTE_STAKE * 16, 4)
+            .is_err());
+        assert_eq!(
+            book.resolution_deadline(),
+            Some(3 + DISPUTE_ROUND_WINDOW + COUNCIL_RESOLUTION_WINDOW)
+        );
+        book.dispute_resolution_result = Some(BetOutcome::For);
+        assert_eq!(book.final_outcome(), Some(BetOutcome::For));
+        // past the deadline the book is cancelled and nobody is slashed
+        book.dispute_resolution_result = Some(BetOutcome::Cancel);
+        book.dispute.as_mut().unwrap().timed_out = true;
+        assert!(book.is_dispute_timed_out());
+        assert_eq!(book.final_outcome(), Some(BetOutcome::Cancel));
+        assert_eq!(book.lost_dispute_bonds_shares(BetOutcome::Cancel), (0, 0));
+        assert_eq!(book.slashed_stake_shares(BetOutcome::Cancel), (0, 0));
+    }
+    #[test]
+    fn test_state_book_quorum() {
+        let mut book = Book {
+            total_oracle_stake: 0,
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bets_count: 0,
+            wager_total: 0,
+            payout_for_total: 0,
+            payout_against_total: 0,
+            dealt_wager: 1000000 * 1000,
+            bet_type: BetType::One { handicap: 0 },
+            total_dispute_stake: 0,
+            dispute_resolution_result: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            oracles_count: 0,
+            oracle_stakes: [0; 6],
+            revealed_oracles_count: 0,
+            quorum_timed_out: false,
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Supermajority { threshold: 6000 },
+            },
+            params: BookParams::default(),
+            dispute: None,
+            dispute_bonds: [0; 5],
+            disputes_count: 0,
+            in_play: None,
+            parlays_count: 0,
+            levels_for: Vec::new(),
+            levels_against: Vec::new(),
+            level_pages: Vec::new(),
+            delayed_levels: Vec::new(),
+            positions_count: 0,
+            aggregated_oracle_outcome: None,
+        };
+        assert!(book.quorum.is_valid());
+        // two oracles reveal the same outcome
+        book.oracles_count = 3;
+        book.total_oracle_stake = 1000000 * 90;
+        book.add_oracle_stake(Some(BetOutcome::For), 1000000 * 30);
+        book.add_oracle_stake(Some(BetOutcome::For), 1000000 * 30);
+        book.add_oracle_stake(None, 1000000 * 30);
+        book.revealed_oracles_count = 2;
+        assert_eq!(book.aggregated_outcome(), None);
+        // the third oracle reveals but the revealed stake is below the ratio of the dealt wager
+        book.remove_oracle_stake(None, 1000000 * 30);
+        book.add_oracle_stake(Some(BetOutcome::Against), 1000000 * 30);
+        book.revealed_oracles_count = 3;
+        assert_eq!(book.aggregated_outcome(), None);
+        // enough stake but no supermajority
+        book.total_oracle_stake = 1000000 * 140;
+        book.add_oracle_stake(Some(BetOutcome::Against), 1000000 * 50);
+        assert_eq!(book.oracle_stakes, [0, 1000000 * 60, 0, 1000000 * 80, 0, 0]);
+        assert_eq!(book.aggregated_outcome(), None);
+        // the leading outcome reaches the supermajority
+        book.total_oracle_stake = 1000000 * 150;
+        book.add_oracle_stake(Some(BetOutcome::Against), 1000000 * 10);
+        assert_eq!(book.aggregated_outcome(), Some(BetOutcome::Against));
+        // quorums below the minimum are not valid
+        book.quorum.min_oracles = 2;
+        assert!(!book.quorum.is_valid());
+        book.quorum.min_oracles = 3;
+        book.quorum.min_stake_ratio = 999;
+        assert!(!book.quorum.is_valid());
+        book.quorum.min_stake_ratio = 1000;
+        book.quorum.rule = AggregationRule::Supermajority { threshold: 5000 };
+        assert!(!book.quorum.is_valid());
+        book.quorum.rule = AggregationRule::Supermajority { threshold: 10001 };
+        assert!(!book.quorum.is_valid());
+        book.quorum.rule = AggregationRule::Median;
+        assert!(book.quorum.is_valid());
+        // quorums above the maximum could never be reached
+        book.quorum.min_oracles = MAX_QUORUM_ORACLES + 1;
+        assert!(!book.quorum.is_valid());
+        book.quorum.min_oracles = 3;
+        book.quorum.min_stake_ratio = MAX_QUORUM_STAKE_RATIO + 1;
+        assert!(!book.quorum.is_valid());
+        // the stake ratio does not overflow on a large dealt wager
+        book.quorum.min_stake_ratio = MAX_QUORUM_STAKE_RATIO;
+        book.dealt_wager = u64::MAX;
+        assert_eq!(book.aggregated_outcome(), None);
+    }
+    #[test]
+    fn test_state_book_params() {
+        let mut config = Config::default();
+        let mut params = BookParams::default();
+        assert!(params.is_valid(&config));
+        // params below the global limits are not valid
+        params.min_bet_amount = MIN_BOOK_BET_AMOUNT - 1;
+        assert!(!params.is_valid(&config));
+        params.min_bet_amount = MIN_BOOK_BET_AMOUNT;
+        params.min_oracle_stake = MIN_BOOK_ORACLE_STAKE - 1;
+        assert!(!params.is_valid(&config));
+        params.min_oracle_stake = MIN_BOOK_ORACLE_STAKE;
+        params.min_bettor_dispute_stake = MIN_BOOK_BETTOR_DISPUTE_STAKE - 1;
+        assert!(!params.is_valid(&config));
+        params.min_bettor_dispute_stake = MIN_BOOK_BETTOR_DISPUTE_STAKE;
+        params.oracle_update_window = MIN_BOOK_ORACLE_UPDATE_WINDOW - 1;
+        assert!(!params.is_valid(&config));
+        // the oracle update window must cover the reveal phase
+        params.oracle_update_window = ORACLE_REVEAL_WINDOW - 1;
+        assert!(!params.is_valid(&config));
+        params.oracle_update_window = ORACLE_REVEAL_WINDOW;
+        assert!(params.is_valid(&config));
+        // the dispute window must outlast the oracle update window
+        params.bettor_dispute_window = MIN_BOOK_ORACLE_UPDATE_WINDOW;
+        assert!(!params.is_valid(&config));
+        params.bettor_dispute_window = MAX_BOOK_BETTOR_DISPUTE_WINDOW + 1;
+        assert!(!params.is_valid(&config));
+        params.bettor_dispute_window = MAX_BOOK_BETTOR_DISPUTE_WINDOW;
+        params.initiator_reward_share = MAX_BOOK_INITIATOR_REWARD_SHARE + 1;
+        assert!(!params.is_valid(&config));
+        params.initiator_reward_share = MAX_BOOK_INITIATOR_REWARD_SHARE;
+        assert!(params.is_valid(&config));
+        // the fee split is the one of the config
+        params.bettor_payout_rate = config.bettor_payout_rate - 1;
+        assert!(!params.is_valid(&config));
+        params.bettor_payout_rate = config.bettor_payout_rate;
+        params.oracles_reward_share = config.oracles_reward_share + 1;
+        assert!(!params.is_valid(&config));
+        // the fee can not be shared beyond the whole
+        config.oracles_reward_share = 10000 - MAX_BOOK_INITIATOR_REWARD_SHARE + 1;
+        params.oracles_reward_share = config.oracles_reward_share;
+        assert!(!params.is_valid(&config));
+        config.oracles_reward_share = u64::MAX;
+        params.oracles_reward_share = u64::MAX;
+        assert!(!params.is_valid(&config));
+    }
+    #[test]
+    fn test_state_book_pro_rata_share() {
+        // 100k usdc shared by a 100k usdc stake overflows u64 before the division
+        assert_eq!(
+            Book::pro_rata_share(1000000 * 100000, 1000000 * 100000, 1000000 * 300000).unwrap(),
+            1000000 * 100000 / 3
+        );
+        assert!(Book::pro_rata_share(u64::MAX, 2, 1).is_err());
+        assert!(Book::pro_rata_share(1, 1, 0).is_err());
+    }
+    #[test]
+    fn test_state_bet_type_is_valid_outcome() {
+        assert!(BetType::One { handicap: 0 }.is_valid_outcome(BetOutcome::Cancel));
+        assert!(!BetType::X { handicap: 0 }.is_valid_outcome(BetOutcome::HalfFor));
+        assert!(!BetType::OverUnder { line: 4 }.is_valid_outcome(BetOutcome::HalfAgainst));
+        // half and quarter lines
+        assert!(!BetType::AsianHandicap { line: -2 }.is_valid_outcome(BetOutcome::HalfFor));
+        assert!(BetType::AsianHandicap { line: -3 }.is_valid_outcome(BetOutcome::HalfFor));
+        assert!(BetType::AsianHandicap { line: 1 }.is_valid_outcome(BetOutcome::HalfAgainst));
     }
 }
@@ -0,0 +1,143 @@
+use std::cell::RefMut;
+
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    error::BettingError,
+    state::{Bet, BetDirection},
+};
+
+// a page of a price level of one side of a book, its bets rest in the slab in the order they were placed, a crowded
+// level overflows into its next pages
+#[account(zero_copy)]
+pub struct BookPage {
+    pub book: Pubkey,
+    // receives the rent when the page is closed
+    pub payer: Pubkey,
+    pub odds: u32,
+    pub len: u16,
+    // the index of the page within its price level, as in the seeds of the page
+    pub index: u16,
+    // the borsh encoding of the bet direction, as in the seeds of the page
+    pub bet_direction: u8,
+    pub bump: u8,
+    pub padding: [u8; 6],
+    pub bets: [Bet; 64],
+}
+impl BookPage {
+    pub const CAPACITY: usize = 64;
+    pub const INIT_SPACE: usize = 8 + 32 + 32 + 4 + 2 + 2 + 1 + 1 + 6 + Bet::INIT_SPACE * Self::CAPACITY;
+
+    // the pages of a book are passed as remaining accounts
+    pub fn loaders<'info>(accounts: &[AccountInfo<'info>]) -> Result<Vec<AccountLoader<'info, BookPage>>> {
+        accounts
+            .iter()
+            .filter(|a| matches!(a.try_borrow_data(), Ok(data) if data.starts_with(&BookPage::discriminator())))
+            .map(AccountLoader::<BookPage>::try_from)
+            .collect()
+    }
+    // a page can only be loaded once, so every page is passed at most once
+    pub fn load_pages<'a>(
+        book: &Pubkey,
+        loaders: &'a [AccountLoader<'_, BookPage>],
+    ) -> Result<Vec<RefMut<'a, BookPage>>> {
+        loaders
+            .iter()
+            .map(|loader| {
+                let page = loader.load_mut()?;
+                require_keys_eq!(page.book, *book, BettingError::InvalidPages);
+                Ok(page)
+            })
+            .collect()
+    }
+    pub fn bet_direction(&self) -> BetDirection {
+        match self.bet_direction {
+            0 => BetDirection::For,
+            _ => BetDirection::Against,
+        }
+    }
+    pub fn bets(&self) -> &[Bet] {
+        &self.bets[..self.len as usize]
+    }
+    pub fn is_full(&self) -> bool {
+        self.len as usize == Self::CAPACITY
+    }
+    pub fn find(&self, bet_id: u64) -> Option<usize> {
+        self.bets().binary_search_by_key(&bet_id, |b| b.id).ok()
+    }
+    // the index of the first bet that has not expired
+    pub fn live_front(&self, now: i64) -> Option<usize> {
+        self.bets().iter().position(|b| !b.is_expired(now))
+    }
+    // bets get increasing ids, so pushing keeps the slab sorted
+    pub fn push(&mut self, bet: Bet) {
+        self.bets[self.len as usize] = bet;
+        self.len += 1;
+    }
+    pub fn remove(&mut self, index: usize) -> Bet {
+        let bet = self.bets[index];
+        self.bets.copy_within(index + 1..self.len as usize, index);
+        self.len -= 1;
+        bet
+    }
+    pub fn retain(&mut self, mut keep: impl FnMut(&Bet) -> bool) {
+        let mut len = 0;
+        for index in 0..self.len as usize {
+            if keep(&self.bets[index]) {
+                self.bets[len] = self.bets[index];
+                len += 1;
+            }
+        }
+        self.len = len as u16;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::{prelude::Pubkey, Discriminator};
+
+    use crate::state::{Bet, BetDirection};
+
+    use super::BookPage;
+
+    fn bet(id: u64, expires_at: i64) -> Bet {
+        Bet {
+            id,
+            bettor: Pubkey::new_unique(),
+            wager: 1000000,
+            placed_at: 0,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_state_book_page_space() {
+        assert_eq!(
+            BookPage::discriminator().len() + std::mem::size_of::<BookPage>(),
+            BookPage::INIT_SPACE
+        );
+    }
+    #[test]
+    fn test_state_book_page_slab() {
+        let mut page: BookPage = bytemuck::Zeroable::zeroed();
+        page.bet_direction = BetDirection::Against as u8;
+        assert_eq!(page.bet_direction(), BetDirection::Against);
+        for id in 1..=4 {
+            page.push(bet(id, if id < 3 { 10 } else { 0 }));
+        }
+        assert_eq!(page.find(3), Some(2));
+        assert_eq!(page.live_front(9), Some(0));
+        assert_eq!(page.live_front(10), Some(2));
+
+        assert_eq!(page.remove(1).id, 2);
+        assert_eq!(page.bets().iter().map(|b| b.id).collect::<Vec<_>>(), vec![1, 3, 4]);
+        page.retain(|b| b.id != 3);
+        assert_eq!(page.bets().iter().map(|b| b.id).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(page.find(3), None);
+
+        while !page.is_full() {
+            page.push(bet(5, 0));
+        }
+        assert_eq!(page.bets().len(), BookPage::CAPACITY);
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{
+    BETTOR_DISPUTE_WINDOW, BETTOR_PAYOUT_RATE, INITIATOR_REWARD_SHARE, MIN_BETTOR_DISPUTE_STAKE, MIN_BET_AMOUNT,
+    MIN_ORACLE_STAKE, OPERATOR_PUBKEY, ORACLE_REVEAL_WINDOW, ORACLE_UPDATE_WINDOW, ORALCES_REWARD_SHARE,
+};
+
+// the economic parameters every instruction reads, updatable by the admin without redeploying the program
+#[account]
+#[derive(Debug, PartialEq, Eq)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub min_bet_amount: u64,
+    pub min_oracle_stake: u64,
+    pub min_bettor_dispute_stake: u64,
+    pub oracle_update_window: i64,
+    pub bettor_dispute_window: i64,
+    pub bettor_payout_rate: u64,
+    pub oracles_reward_share: u64,
+    pub initiator_reward_share: u64,
+}
+impl Config {
+    pub const INIT_SPACE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+    // the disputes start after the oracle window so the dispute window must outlast it, the rewards are shares of the
+    // fee kept from the bettor payouts
+    pub fn is_valid(&self) -> bool {
+        self.min_bet_amount > 0
+            && self.min_oracle_stake > 0
+            && self.min_bettor_dispute_stake > 0
+            && self.oracle_update_window >= ORACLE_REVEAL_WINDOW
+            && self.bettor_dispute_window > self.oracle_update_window
+            && self.bettor_payout_rate <= 10000
+            && matches!(self.oracles_reward_share.checked_add(self.initiator_reward_share), Some(s) if s <= 10000)
+    }
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            admin: OPERATOR_PUBKEY,
+            min_bet_amount: MIN_BET_AMOUNT,
+            min_oracle_stake: MIN_ORACLE_STAKE,
+            min_bettor_dispute_stake: MIN_BETTOR_DISPUTE_STAKE,
+            oracle_update_window: ORACLE_UPDATE_WINDOW,
+            bettor_dispute_window: BETTOR_DISPUTE_WINDOW,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            oracles_reward_share: ORALCES_REWARD_SHARE,
+            initiator_reward_share: INITIATOR_REWARD_SHARE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::AccountSerialize;
+
+    use super::Config;
+    use crate::constants::ORACLE_REVEAL_WINDOW;
+
+    #[test]
+    fn test_state_config_space() {
+        let config = Config::default();
+        let mut config_data: Vec<u8> = Vec::new();
+        config.try_serialize(&mut config_data).unwrap();
+        assert!(config_data.len() <= Config::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_state_config_valid() {
+        assert!(Config::default().is_valid());
+        // the rewards can not exceed the fee
+        assert!(!Config {
+            oracles_reward_share: 8001,
+            initiator_reward_share: 2000,
+            ..Config::default()
+        }
+        .is_valid());
+        assert!(!Config {
+            oracles_reward_share: u64::MAX,
+            initiator_reward_share: 1,
+            ..Config::default()
+        }
+        .is_valid());
+        assert!(!Config {
+            bettor_payout_rate: 10001,
+            ..Config::default()
+        }
+        .is_valid());
+        // the dispute window must outlast the oracle window
+        assert!(!Config {
+            bettor_dispute_window: Config::default().oracle_update_window,
+            ..Config::default()
+        }
+        .is_valid());
+        // the oracle window must cover the reveal phase
+        assert!(!Config {
+            oracle_update_window: ORACLE_REVEAL_WINDOW - 1,
+            ..Config::default()
+        }
+        .is_valid());
+        assert!(!Config {
+            min_bet_amount: 0,
+            ..Config::default()
+        }
+        .is_valid());
+    }
+}
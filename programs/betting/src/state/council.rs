@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_COUNCIL_MEMBERS,
+    state::{BetOutcome, MultiBetOutcome},
+};
+
+// the members which resolve escalated disputes and manage the games, an action applies once a proposal is approved by
+// threshold members
+#[account]
+pub struct Council {
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposals_count: u32,
+}
+impl Council {
+    pub const INIT_SPACE: usize = 8 + 4 + 32 * MAX_COUNCIL_MEMBERS + 1 + 4;
+    pub fn is_member(&self, key: &Pubkey) -> bool {
+        self.members.contains(key)
+    }
+    pub fn is_valid(members: &[Pubkey], threshold: u8) -> bool {
+        let mut sorted_members = members.to_vec();
+        sorted_members.sort();
+        sorted_members.dedup();
+        sorted_members.len() == members.len()
+            && members.len() <= MAX_COUNCIL_MEMBERS
+            && threshold > 0
+            && threshold as usize <= members.len()
+    }
+    // only the votes of the current members count, members may have been rotated since they voted
+    pub fn is_approved(&self, proposal: &CouncilProposal) -> bool {
+        proposal.votes.iter().filter(|v| self.is_member(v)).count() >= self.threshold as usize
+    }
+}
+
+#[account]
+pub struct CouncilProposal {
+    pub id: u32,
+    pub proposer: Pubkey,
+    pub action: CouncilAction,
+    pub votes: Vec<Pubkey>,
+}
+impl CouncilProposal {
+    pub const INIT_SPACE: usize = 8 + 4 + 32 + CouncilAction::INIT_SPACE + 4 + 32 * MAX_COUNCIL_MEMBERS;
+    // votes of removed members are dropped to make room for the current members
+    pub fn vote(&mut self, council: &Council, member: Pubkey) {
+        self.votes.retain(|v| council.is_member(v));
+        if !self.votes.contains(&member) {
+            self.votes.push(member);
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum CouncilAction {
+    ResolveDispute {
+        book: Pubkey,
+        outcome: BetOutcome,
+    },
+    ResolveMultiDispute {
+        book: Pubkey,
+        outcome: MultiBetOutcome,
+    },
+    GameInit {
+        game_id: u32,
+        league_id: u32,
+        home_team_id: u32,
+        away_team_id: u32,
+        kickoff: i64,
+    },
+    GameClose {
+        game_id: u32,
+    },
+    RotateMembers {
+        members: Vec<Pubkey>,
+        threshold: u8,
+    },
+}
+impl CouncilAction {
+    pub const INIT_SPACE: usize = 1 + 4 + 32 * MAX_COUNCIL_MEMBERS + 1;
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{Council, CouncilAction, CouncilProposal};
+    use crate::constants::MAX_COUNCIL_MEMBERS;
+
+    #[test]
+    fn test_state_council_space() {
+        let members: Vec<Pubkey> = (0..MAX_COUNCIL_MEMBERS).map(|_| Pubkey::new_unique()).collect();
+        let council = Council {
+            members: members.clone(),
+            threshold: 1,
+            proposals_count: 1,
+        };
+        let mut council_data: Vec<u8> = Vec::new();
+        council.try_serialize(&mut council_data).unwrap();
+        assert!(council_data.len() <= Council::INIT_SPACE);
+        let proposal = CouncilProposal {
+            id: 1,
+            proposer: members[0],
+            action: CouncilAction::RotateMembers {
+                members: members.clone(),
+                threshold: 1,
+            },
+            votes: members,
+        };
+        let mut proposal_data: Vec<u8> = Vec::new();
+        proposal.try_serialize(&mut proposal_data).unwrap();
+        assert!(proposal_data.len() <= CouncilProposal::INIT_SPACE);
+    }
+
+    #[test]
+    fn test_state_council_valid() {
+        let member_1 = Pubkey::new_unique();
+        let member_2 = Pubkey::new_unique();
+        assert!(Council::is_valid(&[member_1, member_2], 2));
+        assert!(!Council::is_valid(&[member_1, member_2], 0));
+        assert!(!Council::is_valid(&[member_1, member_2], 3));
+        assert!(!Council::is_valid(&[member_1, member_1], 1));
+        let members: Vec<Pubkey> = (0..MAX_COUNCIL_MEMBERS + 1).map(|_| Pubkey::new_unique()).collect();
+        assert!(!Council::is_valid(&members, 1));
+    }
+
+    #[test]
+    fn test_state_council_approved() {
+        let member_1 = Pubkey::new_unique();
+        let member_2 = Pubkey::new_unique();
+        let member_3 = Pubkey::new_unique();
+        let mut council = Council {
+            members: vec![member_1, member_2, member_3],
+            threshold: 2,
+            proposals_count: 1,
+        };
+        let mut proposal = CouncilProposal {
+            id: 0,
+            proposer: member_1,
+            action: CouncilAction::GameClose { game_id: 1 },
+            votes: vec![member_1],
+        };
+        assert!(!council.is_approved(&proposal));
+        // voting twice counts once
+        proposal.vote(&council, member_1);
+        assert!(!council.is_approved(&proposal));
+        proposal.vote(&council, member_2);
+        assert!(council.is_approved(&proposal));
+        // the votes of removed members no longer count
+        council.members = vec![member_2, member_3, Pubkey::new_unique()];
+        assert!(!council.is_approved(&proposal));
+        proposal.vote(&council, member_3);
+        assert_eq!(proposal.votes, vec![member_2, member_3]);
+        assert!(council.is_approved(&proposal));
+    }
+}
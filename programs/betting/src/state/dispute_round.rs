@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::state::BetOutcome;
+
+// a round of the dispute of a book, every round proposes another outcome than the round before it and bonds at least
+// twice as much, it lives in its own pda keyed by the book and the round number
+#[account]
+pub struct DisputeRound {
+    pub book: Pubkey,
+    pub disputer: Pubkey,
+    pub round: u8,
+    // the outcome the disputer claims is correct and the hash of the evidence backing the claim
+    pub outcome: BetOutcome,
+    pub evidence: [u8; 32],
+    pub bond: u64,
+}
+impl DisputeRound {
+    pub const INIT_SPACE: usize = 8 + 32 + 32 + 1 + BetOutcome::INIT_SPACE + 32 + 8;
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::DisputeRound;
+    use crate::state::BetOutcome;
+
+    #[test]
+    fn test_state_dispute_round_space() {
+        let dispute_round = DisputeRound {
+            book: Pubkey::new_unique(),
+            disputer: Pubkey::new_unique(),
+            round: 1,
+            outcome: BetOutcome::HalfFor,
+            evidence: [1; 32],
+            bond: 1,
+        };
+        let mut dispute_round_data: Vec<u8> = Vec::new();
+        dispute_round.try_serialize(&mut dispute_round_data).unwrap();
+        assert!(dispute_round_data.len() <= DisputeRound::INIT_SPACE);
+    }
+}
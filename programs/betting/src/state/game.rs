@@ -1,4 +1,8 @@
-use anchor_lang::prelude::*;
+use std::collections::BTreeMap;
+
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::constants::{MIN_QUORUM_ORACLES, MIN_QUORUM_SUPERMAJORITY};
 
 #[account]
 pub struct Game {
@@ -8,7 +12,160 @@ pub struct Game {
     pub away_team_id: u32,
     pub kickoff: i64,
     pub books_count: u32,
+    pub final_score: Option<Score>,
+    pub concluded_at: Option<i64>,
+    // the oracles commit to their scores first, the reveal phase starts here
+    pub reveal_starts_at: Option<i64>,
+    pub score_reports: BTreeMap<Pubkey, ScoreReport>,
 }
 impl Game {
-    pub const INIT_SPACE: usize = 8 + 4 + 4 + 4 + 4 + 8 + 4;
+    pub const INIT_SPACE: usize = 8 + 4 + 4 + 4 + 4 + 8 + 4 + 1 + Score::INIT_SPACE + 1 + 8 + 1 + 8 + 4;
+    pub fn current_space(&self) -> usize {
+        Self::INIT_SPACE + (32 + ScoreReport::INIT_SPACE) * self.score_reports.len()
+    }
+    // the score with the most revealed stake, none until enough oracles revealed and the score holds the minimum
+    // supermajority, so exact ties never conclude, the books still apply their own quorum when they derive their
+    // outcomes
+    pub fn aggregated_score(&self) -> Option<Score> {
+        let mut map: BTreeMap<Score, u64> = BTreeMap::new();
+        for r in self.score_reports.values() {
+            if let Some(score) = r.score {
+                *map.entry(score).or_insert(0) += r.stake;
+            }
+        }
+        let revealed_count = self.score_reports.values().filter(|r| r.score.is_some()).count();
+        if revealed_count < MIN_QUORUM_ORACLES as usize {
+            return None;
+        }
+        let revealed_stake: u64 = map.values().sum();
+        let (score, stake) = map.into_iter().max_by_key(|(_, stake)| *stake)?;
+        (stake as u128 * 10000 >= revealed_stake as u128 * MIN_QUORUM_SUPERMAJORITY as u128).then_some(score)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Score {
+    pub home: u8,
+    pub away: u8,
+}
+impl Score {
+    pub const INIT_SPACE: usize = 1 + 1;
+}
+
+// the stake is the stake of the oracle in the book it committed the score through, the score stays none until the
+// committed score is revealed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ScoreReport {
+    pub stake: u64,
+    pub score: Option<Score>,
+    pub commitment: Option<[u8; 32]>,
+}
+impl ScoreReport {
+    pub const INIT_SPACE: usize = 8 + 1 + Score::INIT_SPACE + 1 + 32;
+
+    // the hash an oracle commits to, see Oracle::commitment
+    pub fn commitment(game: &Pubkey, oracle: &Pubkey, score: Score, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[game.as_ref(), oracle.as_ref(), &[score.home, score.away], salt]).to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::{Game, Score, ScoreReport};
+
+    #[test]
+    fn test_state_game_current_space() {
+        let mut game = Game {
+            game_id: 1,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 0,
+            final_score: Some(Score { home: 1, away: 0 }),
+            concluded_at: Some(1),
+            reveal_starts_at: Some(1),
+            score_reports: BTreeMap::new(),
+        };
+        let mut game_data: Vec<u8> = Vec::new();
+        game.try_serialize(&mut game_data).unwrap();
+        assert_eq!(game_data.len(), Game::INIT_SPACE);
+
+        game.score_reports.insert(
+            Pubkey::new_unique(),
+            ScoreReport {
+                stake: 1,
+                score: Some(Score { home: 1, away: 0 }),
+                commitment: Some([0; 32]),
+            },
+        );
+        let mut game_data: Vec<u8> = Vec::new();
+        game.try_serialize(&mut game_data).unwrap();
+        assert_eq!(game_data.len(), game.current_space());
+    }
+    #[test]
+    fn test_state_game_aggregated_score() {
+        let mut game = Game {
+            game_id: 1,
+            league_id: 1,
+            home_team_id: 1,
+            away_team_id: 2,
+            kickoff: 1,
+            books_count: 0,
+            final_score: None,
+            concluded_at: None,
+            reveal_starts_at: None,
+            score_reports: BTreeMap::new(),
+        };
+        assert!(game.aggregated_score().is_none());
+
+        let report = |stake: u64, score: Option<Score>| ScoreReport {
+            stake,
+            score,
+            commitment: None,
+        };
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(100, Some(Score { home: 2, away: 0 })));
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(100, Some(Score { home: 2, away: 0 })));
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(200, Some(Score { home: 1, away: 0 })));
+        game.score_reports.insert(Pubkey::new_unique(), report(1000, None));
+        // exact ties never conclude, unrevealed reports abstain
+        assert!(game.aggregated_score().is_none());
+        game.score_reports.clear();
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(100, Some(Score { home: 2, away: 0 })));
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(100, Some(Score { home: 2, away: 0 })));
+        game.score_reports.insert(Pubkey::new_unique(), report(1000, None));
+        // unrevealed reports do not count towards the quorum
+        assert!(game.aggregated_score().is_none());
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(150, Some(Score { home: 1, away: 0 })));
+        // the leading score holds less than the minimum supermajority
+        assert!(game.aggregated_score().is_none());
+        game.score_reports
+            .insert(Pubkey::new_unique(), report(50, Some(Score { home: 2, away: 0 })));
+        assert_eq!(game.aggregated_score(), Some(Score { home: 2, away: 0 }));
+    }
+    #[test]
+    fn test_state_game_score_commitment() {
+        let game = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let commitment = ScoreReport::commitment(&game, &oracle, Score { home: 2, away: 1 }, &[7; 32]);
+        assert_ne!(
+            commitment,
+            ScoreReport::commitment(&game, &oracle, Score { home: 1, away: 2 }, &[7; 32])
+        );
+        assert_ne!(
+            commitment,
+            ScoreReport::commitment(&game, &Pubkey::new_unique(), Score { home: 2, away: 1 }, &[7; 32])
+        );
+    }
 }
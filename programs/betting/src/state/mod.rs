@@ -1,7 +1,23 @@
 pub mod book;
+pub mod book_page;
+pub mod config;
+pub mod council;
+pub mod dispute_round;
 pub mod game;
+pub mod multi_book;
+pub mod oracle;
+pub mod parlay;
+pub mod position;
 pub mod user_account;
 
 pub use book::*;
+pub use book_page::*;
+pub use config::*;
+pub use council::*;
+pub use dispute_round::*;
 pub use game::*;
+pub use multi_book::*;
+pub use oracle::*;
+pub use parlay::*;
+pub use position::*;
 pub use user_account::*;
@@ -0,0 +1,539 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{
+    constants::{COUNCIL_RESOLUTION_WINDOW, MIN_QUORUM_SUPERMAJORITY},
+    error::BettingError,
+    state::{AggregationRule, Bet, BetDirection, BookParams, Quorum},
+};
+
+// a book on mutually exclusive outcomes, e.g. the correct score or the first goalscorer of a game,
+// every outcome has its own queues of bets for (back) and against (lay) it
+#[account]
+pub struct MultiBook {
+    pub game_id: u32,
+    pub initiator: Pubkey,
+    pub bet_type: MultiBetType,
+    pub outcomes_count: u8,
+    pub bets_count: u32,
+    pub wager_total: u64,
+    pub dealt_wager: u64,
+    pub total_dispute_stake: u64,
+    pub total_oracle_stake: u64,
+    pub dispute_resolution_result: Option<MultiBetOutcome>,
+    // the council missed the deadline and the book was cancelled, every stake is returned
+    pub dispute_timed_out: bool,
+    pub concluded_at: Option<i64>,
+    pub aggregated_oracle_outcome: Option<MultiBetOutcome>,
+    // the oracles commit to their outcomes first, the reveal phase starts here
+    pub reveal_starts_at: Option<i64>,
+    // the parameters of the config when the book was initialized
+    pub params: BookParams,
+    pub quorum: Quorum,
+    pub oracles: BTreeMap<Pubkey, MultiOracle>,
+    pub outcomes: Vec<OutcomeBets>,
+    pub positions: BTreeMap<Pubkey, MultiPosition>,
+}
+impl MultiBook {
+    pub const INIT_SPACE: usize = 8
+        + 4
+        + 32
+        + MultiBetType::INIT_SPACE
+        + 1
+        + 4
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + MultiBetOutcome::INIT_SPACE
+        + 1
+        + 1
+        + 8
+        + 1
+        + MultiBetOutcome::INIT_SPACE
+        + 1
+        + 8
+        + BookParams::INIT_SPACE
+        + Quorum::INIT_SPACE
+        + 4
+        + 4
+        + 4;
+    pub fn init_space(outcomes_count: u8) -> usize {
+        Self::INIT_SPACE + OutcomeBets::INIT_SPACE * outcomes_count as usize
+    }
+    pub fn current_space(&self) -> usize {
+        Self::init_space(self.outcomes_count)
+            + (32 + MultiOracle::INIT_SPACE) * self.oracles.len()
+            + Bet::INIT_SPACE
+                * self
+                    .outcomes
+                    .iter()
+                    .map(|o| o.bets_for.len() + o.bets_against.len())
+                    .sum::<usize>()
+            + (32 + MultiPosition::space(self.outcomes_count)) * self.positions.len()
+    }
+    pub fn is_valid_outcome(&self, outcome: MultiBetOutcome) -> bool {
+        match outcome {
+            MultiBetOutcome::Winner(index) => index < self.outcomes_count,
+            MultiBetOutcome::Cancel => true,
+        }
+    }
+    pub fn final_outcome(&self) -> Option<MultiBetOutcome> {
+        if self.total_dispute_stake > 0 {
+            self.dispute_resolution_result
+        } else {
+            self.aggregated_oracle_outcome
+        }
+    }
+    // the deadline to resolve a dispute, counted from the end of the dispute window
+    pub fn resolution_deadline(&self) -> Option<i64> {
+        match self.concluded_at {
+            Some(t) if self.total_dispute_stake > 0 => {
+                Some(t + self.params.bettor_dispute_window + COUNCIL_RESOLUTION_WINDOW)
+            }
+            _ => None,
+        }
+    }
+    // the fee kept from the bettor payouts, refunded stakes are not charged
+    pub fn total_profit(&self, final_outcome: MultiBetOutcome) -> u64 {
+        match final_outcome {
+            MultiBetOutcome::Winner(_) => self.dealt_wager * (10000 - self.params.bettor_payout_rate) / 10000,
+            MultiBetOutcome::Cancel => 0,
+        }
+    }
+    // the outcome with the most revealed stake, none until the quorum is met and the outcome holds the threshold of the
+    // rule, at least the minimum supermajority, so exact ties never conclude, unrevealed stake abstains
+    pub fn aggregated_outcome(&self) -> Option<MultiBetOutcome> {
+        let revealed_oracles = self.oracles.values().filter(|o| o.revealed);
+        let mut stakes: BTreeMap<MultiBetOutcome, u64> = BTreeMap::new();
+        for o in revealed_oracles.clone() {
+            if let Some(outcome) = o.outcome {
+                *stakes.entry(outcome).or_insert(0) += o.stake;
+            }
+        }
+        let revealed_stake: u64 = stakes.values().sum();
+        if revealed_stake == 0
+            || (revealed_oracles.count() as u32) < self.quorum.min_oracles
+            || (revealed_stake as u128) * 10000 < (self.dealt_wager as u128) * (self.quorum.min_stake_ratio as u128)
+        {
+            return None;
+        }
+        let threshold = match self.quorum.rule {
+            AggregationRule::Supermajority { threshold } => threshold,
+            AggregationRule::Majority | AggregationRule::Median => MIN_QUORUM_SUPERMAJORITY,
+        };
+        let (outcome, stake) = stakes.into_iter().max_by_key(|(_, stake)| *stake).unwrap();
+        (stake as u128 * 10000 >= revealed_stake as u128 * threshold.max(MIN_QUORUM_SUPERMAJORITY) as u128)
+            .then_some(outcome)
+    }
+    pub fn new_bet(
+        &mut self,
+        outcome: u8,
+        odds: u32,
+        wager: u64,
+        bettor: Pubkey,
+        bet_direction: BetDirection,
+        now: i64,
+    ) -> Result<()> {
+        let mut id = [0_u8; 8];
+        id[0..4].copy_from_slice(self.bets_count.to_le_bytes().as_slice());
+        id[4..8].copy_from_slice(odds.to_le_bytes().as_slice());
+        let bet = Bet {
+            id: u64::from_le_bytes(id),
+            bettor,
+            wager,
+            placed_at: now,
+            expires_at: 0,
+        };
+        // a resting bet must always be priceable, or matching would fail on it
+        bet.payout()?;
+        let outcome_bets = &mut self.outcomes[outcome as usize];
+        let bets = match bet_direction {
+            BetDirection::For => &mut outcome_bets.bets_for,
+            BetDirection::Against => &mut outcome_bets.bets_against,
+        };
+        match bets.binary_search_by_key(&bet.id, |b| b.id) {
+            Ok(_) => {
+                return err!(BettingError::DuplicateBet);
+            }
+            Err(index) => {
+                bets.insert(index, bet);
+            }
+        }
+
+        self.bets_count += 1;
+        self.wager_total += wager;
+        let position = self.positions.get_mut(&bettor).unwrap();
+        position.bets_count += 1;
+        position.wager += wager;
+        position.active_bets_count += 1;
+        Ok(())
+    }
+    // Matches the front bets of both queues of the outcome up to `max_matches` times, the same way as
+    // `Book::match_bets`. Whichever outcome wins, the paid out payouts are the payouts for it plus the payouts against
+    // all the other outcomes, which is at most the sum of the payout totals and therefore covered by the dealt wager.
+    pub fn match_bets(&mut self, outcome: u8, max_matches: u32) -> Result<(u32, bool)> {
+        let mut matches_count = 0;
+        loop {
+            let outcome_bets = &mut self.outcomes[outcome as usize];
+            match (outcome_bets.bets_for.front(), outcome_bets.bets_against.front()) {
+                (Some(bet_for), Some(bet_against)) if bet_for.crosses(bet_against) => {}
+                _ => return Ok((matches_count, false)),
+            }
+            if matches_count == max_matches {
+                return Ok((matches_count, true));
+            }
+            let mut bet_for = outcome_bets.bets_for.pop_front().unwrap();
+            let mut bet_against = outcome_bets.bets_against.pop_front().unwrap();
+
+            let (payout, for_dealt_wager, against_dealt_wager) = bet_for.fill(&mut bet_against)?;
+
+            outcome_bets.payout_total = outcome_bets
+                .payout_total
+                .checked_add(payout)
+                .ok_or(BettingError::MathOverflow)?;
+            self.dealt_wager = self
+                .dealt_wager
+                .checked_add(for_dealt_wager)
+                .and_then(|w| w.checked_add(against_dealt_wager))
+                .ok_or(BettingError::MathOverflow)?;
+
+            let position_for = self.positions.get_mut(&bet_for.bettor).unwrap();
+            position_for.payouts_for[outcome as usize] += payout;
+            position_for.dealt_wager += for_dealt_wager;
+            if bet_for.wager > 0 {
+                outcome_bets.bets_for.push_front(bet_for);
+            } else {
+                position_for.active_bets_count -= 1;
+            }
+            let position_against = self.positions.get_mut(&bet_against.bettor).unwrap();
+            position_against.payouts_against[outcome as usize] += payout;
+            position_against.dealt_wager += against_dealt_wager;
+            if bet_against.wager > 0 {
+                outcome_bets.bets_against.push_front(bet_against);
+            } else {
+                position_against.active_bets_count -= 1;
+            }
+            matches_count += 1;
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiBetType {
+    CorrectScore,
+    FirstGoalscorer,
+    Outright,
+}
+impl MultiBetType {
+    pub const INIT_SPACE: usize = 1;
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MultiBetOutcome {
+    // the index of the winning outcome
+    Winner(u8),
+    Cancel,
+}
+impl MultiBetOutcome {
+    pub const INIT_SPACE: usize = 1 + 1;
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MultiOracle {
+    pub stake: u64,
+    // stays none until the committed outcome is revealed
+    pub outcome: Option<MultiBetOutcome>,
+    pub commitment: Option<[u8; 32]>,
+    pub revealed: bool,
+}
+impl MultiOracle {
+    pub const INIT_SPACE: usize = 8 + 1 + MultiBetOutcome::INIT_SPACE + 1 + 32 + 1;
+
+    pub fn new(stake: u64) -> Self {
+        Self {
+            stake,
+            outcome: None,
+            commitment: None,
+            revealed: false,
+        }
+    }
+    // the hash an oracle commits to, see Oracle::commitment
+    pub fn commitment(book: &Pubkey, oracle: &Pubkey, outcome: Option<MultiBetOutcome>, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[book.as_ref(), oracle.as_ref(), &outcome.try_to_vec().unwrap(), salt]).to_bytes()
+    }
+}
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct OutcomeBets {
+    pub payout_total: u64,
+    pub bets_for: VecDeque<Bet>,
+    pub bets_against: VecDeque<Bet>,
+}
+impl OutcomeBets {
+    pub const INIT_SPACE: usize = 8 + 4 + 4;
+}
+
+// payouts are kept per outcome, the payouts for an outcome are paid if it wins,
+// the payouts against an outcome are paid if any other outcome wins
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct MultiPosition {
+    pub active_bets_count: u32,
+    pub bets_count: u32,
+    pub wager: u64,
+    pub dealt_wager: u64,
+    pub dispute_stake: u64,
+    pub payouts_for: Vec<u64>,
+    pub payouts_against: Vec<u64>,
+}
+impl MultiPosition {
+    pub const INIT_SPACE: usize = 4 + 4 + 8 + 8 + 8 + 4 + 4;
+    pub fn space(outcomes_count: u8) -> usize {
+        Self::INIT_SPACE + 2 * 8 * outcomes_count as usize
+    }
+    pub fn new(outcomes_count: u8) -> Self {
+        MultiPosition {
+            payouts_for: vec![0; outcomes_count as usize],
+            payouts_against: vec![0; outcomes_count as usize],
+            ..Default::default()
+        }
+    }
+    pub fn payout(&self, winner: u8) -> u64 {
+        let winner = winner as usize;
+        let payouts_against: u64 = self.payouts_against.iter().sum();
+        self.payouts_for[winner] + payouts_against - self.payouts_against[winner]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::state::{AggregationRule, BetDirection, BookParams, Quorum};
+
+    use super::{MultiBetOutcome, MultiBetType, MultiBook, MultiOracle, MultiPosition, OutcomeBets};
+
+    #[test]
+    fn test_state_multi_book_current_space() {
+        let bettor = Pubkey::new_unique();
+        let mut book = MultiBook {
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bet_type: MultiBetType::CorrectScore,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: Some(MultiBetOutcome::Winner(0)),
+            dispute_timed_out: false,
+            concluded_at: Some(1),
+            aggregated_oracle_outcome: Some(MultiBetOutcome::Winner(1)),
+            reveal_starts_at: Some(1),
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Supermajority { threshold: 6000 },
+            },
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        let mut book_data: Vec<u8> = Vec::new();
+        book.try_serialize(&mut book_data).unwrap();
+        assert_eq!(book_data.len(), MultiBook::init_space(3));
+
+        book.oracles.insert(
+            Pubkey::new_unique(),
+            MultiOracle {
+                stake: 1,
+                outcome: Some(MultiBetOutcome::Winner(2)),
+                commitment: None,
+                revealed: true,
+            },
+        );
+        book.positions.insert(bettor, MultiPosition::new(3));
+        book.new_bet(2, 1500, 100, bettor, BetDirection::For, 0).unwrap();
+        book.new_bet(0, 1500, 100, bettor, BetDirection::Against, 0).unwrap();
+        // a reused bet id is rejected instead of overwriting the resting bet
+        book.bets_count -= 1;
+        assert!(book.new_bet(0, 1500, 100, bettor, BetDirection::Against, 0).is_err());
+        let mut book_data: Vec<u8> = Vec::new();
+        book.try_serialize(&mut book_data).unwrap();
+        assert!(book_data.len() <= book.current_space());
+    }
+    #[test]
+    fn test_state_multi_book_match_bets() {
+        let bettor_for = Pubkey::new_unique();
+        let bettor_against = Pubkey::new_unique();
+        let mut book = MultiBook {
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bet_type: MultiBetType::CorrectScore,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum::default(),
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::from([
+                (bettor_for, MultiPosition::new(3)),
+                (bettor_against, MultiPosition::new(3)),
+            ]),
+        };
+        // bets on different outcomes are never matched
+        book.new_bet(0, 4000, 1000, bettor_for, BetDirection::For, 0).unwrap();
+        book.new_bet(1, 1200, 6000, bettor_against, BetDirection::Against, 0)
+            .unwrap();
+        assert_eq!(book.match_bets(0, 10).unwrap(), (0, false));
+        assert_eq!(book.match_bets(1, 10).unwrap(), (0, false));
+
+        book.new_bet(0, 1250, 5000, bettor_against, BetDirection::Against, 0)
+            .unwrap();
+        assert_eq!(book.match_bets(0, 0).unwrap(), (0, true));
+        assert_eq!(book.match_bets(0, 10).unwrap(), (1, false));
+        // the bet for is filled completely with a payout of 4000, the bet against deals the other 3000 of it
+        assert!(book.outcomes[0].bets_for.is_empty());
+        assert_eq!(book.outcomes[0].bets_against[0].wager, 2000);
+        assert_eq!(book.outcomes[0].payout_total, 4000);
+        assert_eq!(book.dealt_wager, 1000 + 3000);
+        assert_eq!(book.positions[&bettor_for].payouts_for, vec![4000, 0, 0]);
+        assert_eq!(book.positions[&bettor_for].active_bets_count, 0);
+        assert_eq!(book.positions[&bettor_against].payouts_against, vec![4000, 0, 0]);
+        assert_eq!(book.positions[&bettor_against].active_bets_count, 2);
+        // the bettor against wins unless the outcome 0 wins
+        assert_eq!(book.positions[&bettor_for].payout(0), 4000);
+        assert_eq!(book.positions[&bettor_for].payout(1), 0);
+        assert_eq!(book.positions[&bettor_against].payout(0), 0);
+        assert_eq!(book.positions[&bettor_against].payout(2), 4000);
+    }
+    #[test]
+    fn test_state_multi_position_payout() {
+        let position = MultiPosition {
+            payouts_for: vec![100, 0, 30],
+            payouts_against: vec![0, 50, 20],
+            ..MultiPosition::new(3)
+        };
+        assert_eq!(position.payout(0), 100 + 50 + 20);
+        assert_eq!(position.payout(1), 20);
+        assert_eq!(position.payout(2), 30 + 50);
+    }
+    #[test]
+    fn test_state_multi_book_is_valid_outcome() {
+        let book = MultiBook {
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bet_type: MultiBetType::FirstGoalscorer,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 0,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum::default(),
+            oracles: BTreeMap::new(),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        assert!(book.is_valid_outcome(MultiBetOutcome::Winner(2)));
+        assert!(book.is_valid_outcome(MultiBetOutcome::Cancel));
+        assert!(!book.is_valid_outcome(MultiBetOutcome::Winner(3)));
+    }
+    #[test]
+    fn test_state_multi_book_aggregated_outcome() {
+        let oracle = |stake: u64, outcome: Option<MultiBetOutcome>, revealed: bool| MultiOracle {
+            stake,
+            outcome,
+            commitment: None,
+            revealed,
+        };
+        let mut book = MultiBook {
+            game_id: 1,
+            initiator: Pubkey::new_unique(),
+            bet_type: MultiBetType::CorrectScore,
+            outcomes_count: 3,
+            bets_count: 0,
+            wager_total: 0,
+            dealt_wager: 1000,
+            total_dispute_stake: 0,
+            total_oracle_stake: 0,
+            dispute_resolution_result: None,
+            dispute_timed_out: false,
+            concluded_at: None,
+            aggregated_oracle_outcome: None,
+            reveal_starts_at: None,
+            params: BookParams::default(),
+            quorum: Quorum {
+                min_oracles: 3,
+                min_stake_ratio: 1000,
+                rule: AggregationRule::Majority,
+            },
+            oracles: BTreeMap::from([
+                (
+                    Pubkey::new_unique(),
+                    oracle(100, Some(MultiBetOutcome::Winner(0)), true),
+                ),
+                (
+                    Pubkey::new_unique(),
+                    oracle(100, Some(MultiBetOutcome::Winner(1)), true),
+                ),
+                (Pubkey::new_unique(), oracle(500, None, false)),
+            ]),
+            outcomes: vec![OutcomeBets::default(); 3],
+            positions: BTreeMap::new(),
+        };
+        // unrevealed stake abstains and the quorum is not met
+        assert_eq!(book.aggregated_outcome(), None);
+        // an exact tie never concludes
+        book.oracles.insert(Pubkey::new_unique(), oracle(50, None, true));
+        assert_eq!(book.aggregated_outcome(), None);
+        // the leading outcome needs the minimum supermajority
+        book.oracles
+            .insert(Pubkey::new_unique(), oracle(1, Some(MultiBetOutcome::Winner(1)), true));
+        assert_eq!(book.aggregated_outcome(), None);
+        book.oracles
+            .insert(Pubkey::new_unique(), oracle(49, Some(MultiBetOutcome::Winner(1)), true));
+        assert_eq!(book.aggregated_outcome(), Some(MultiBetOutcome::Winner(1)));
+        // the revealed stake must cover the minimum share of the dealt wager
+        book.dealt_wager = 1000000;
+        assert_eq!(book.aggregated_outcome(), None);
+        // a supermajority rule raises the share the leading outcome needs
+        book.dealt_wager = 1000;
+        assert_eq!(book.aggregated_outcome(), Some(MultiBetOutcome::Winner(1)));
+        book.quorum.rule = AggregationRule::Supermajority { threshold: 7000 };
+        assert_eq!(book.aggregated_outcome(), None);
+    }
+    #[test]
+    fn test_state_multi_oracle_commitment() {
+        let book = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let commitment = MultiOracle::commitment(&book, &oracle, Some(MultiBetOutcome::Winner(1)), &[7; 32]);
+        assert_ne!(
+            commitment,
+            MultiOracle::commitment(&book, &oracle, Some(MultiBetOutcome::Winner(2)), &[7; 32])
+        );
+        assert_ne!(
+            commitment,
+            MultiOracle::commitment(&book, &Pubkey::new_unique(), Some(MultiBetOutcome::Winner(1)), &[7; 32])
+        );
+    }
+}
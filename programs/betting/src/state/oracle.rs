@@ -0,0 +1,94 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+
+use crate::{error::BettingError, state::BetOutcome};
+
+// the stake and the reported outcome of an oracle in a book, it lives in its own pda keyed by the book and the oracle
+// so the same oracle is found under the same key in every book
+#[account]
+pub struct Oracle {
+    pub book: Pubkey,
+    pub oracle: Pubkey,
+    pub stake: u64,
+    // stays none until the committed outcome is revealed
+    pub outcome: Option<BetOutcome>,
+    pub commitment: Option<[u8; 32]>,
+    // oracles which committed but never revealed forfeit their stake
+    pub revealed: bool,
+}
+impl Oracle {
+    pub const INIT_SPACE: usize = 8 + 32 + 32 + 8 + 1 + BetOutcome::INIT_SPACE + 1 + 32 + 1;
+
+    // the hash an oracle commits to, the salt keeps the outcome secret until it is revealed and the keys keep the
+    // commitment from being copied by another oracle or into another book
+    pub fn commitment(book: &Pubkey, oracle: &Pubkey, outcome: Option<BetOutcome>, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[book.as_ref(), oracle.as_ref(), &outcome.try_to_vec().unwrap(), salt]).to_bytes()
+    }
+
+    // the oracles of a book are passed as remaining accounts, every oracle at most once
+    pub fn load_oracles<'info>(book: &Pubkey, accounts: &[AccountInfo<'info>]) -> Result<Vec<Account<'info, Oracle>>> {
+        let mut oracles: Vec<Account<'info, Oracle>> = Vec::new();
+        for account in accounts {
+            let oracle = Account::<Oracle>::try_from(account)?;
+            require_keys_eq!(oracle.book, *book, BettingError::InvalidOracles);
+            require!(
+                !oracles.iter().any(|o| o.key() == oracle.key()),
+                BettingError::InvalidOracles
+            );
+            oracles.push(oracle);
+        }
+        Ok(oracles)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::Oracle;
+    use crate::state::BetOutcome;
+
+    #[test]
+    fn test_state_oracle_space() {
+        let oracle = Oracle {
+            book: Pubkey::new_unique(),
+            oracle: Pubkey::new_unique(),
+            stake: 1,
+            outcome: Some(BetOutcome::HalfFor),
+            commitment: Some([1; 32]),
+            revealed: true,
+        };
+        let mut oracle_data: Vec<u8> = Vec::new();
+        oracle.try_serialize(&mut oracle_data).unwrap();
+        assert!(oracle_data.len() <= Oracle::INIT_SPACE);
+    }
+    #[test]
+    fn test_state_oracle_commitment() {
+        let book = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let salt = [7; 32];
+        let commitment = Oracle::commitment(&book, &oracle, Some(BetOutcome::For), &salt);
+        assert_eq!(
+            commitment,
+            Oracle::commitment(&book, &oracle, Some(BetOutcome::For), &salt)
+        );
+        assert_ne!(
+            commitment,
+            Oracle::commitment(&book, &oracle, Some(BetOutcome::Against), &salt)
+        );
+        assert_ne!(commitment, Oracle::commitment(&book, &oracle, None, &salt));
+        assert_ne!(
+            commitment,
+            Oracle::commitment(&book, &oracle, Some(BetOutcome::For), &[8; 32])
+        );
+        // a copied commitment does not match for another oracle or in another book
+        assert_ne!(
+            commitment,
+            Oracle::commitment(&book, &Pubkey::new_unique(), Some(BetOutcome::For), &salt)
+        );
+        assert_ne!(
+            commitment,
+            Oracle::commitment(&Pubkey::new_unique(), &oracle, Some(BetOutcome::For), &salt)
+        );
+    }
+}
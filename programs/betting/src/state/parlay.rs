@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::BettingError,
+    state::{Bet, BetDirection, BetOutcome, Book, Game},
+};
+
+#[account]
+pub struct Parlay {
+    pub parlay_id: u64,
+    pub backer: Pubkey,
+    pub layer: Option<Pubkey>,
+    pub wager: u64,
+    // the amount locked by the layer, the payout of the parlay minus the wager
+    pub liability: u64,
+    // the payout rate of the config when the parlay was placed
+    pub bettor_payout_rate: u64,
+    pub legs: Vec<ParlayLeg>,
+}
+impl Parlay {
+    pub const INIT_SPACE: usize = 8 + 8 + 32 + 1 + 32 + 8 + 8 + 8 + 4;
+    pub fn space(legs_count: usize) -> usize {
+        Self::INIT_SPACE + ParlayLeg::INIT_SPACE * legs_count
+    }
+    // loads the leg books from the given accounts, which are passed in the order of the legs
+    pub fn leg_books<'info>(&self, accounts: &[AccountInfo<'info>]) -> Result<Vec<Account<'info, Book>>> {
+        require!(accounts.len() == self.legs.len(), BettingError::InvalidLegs);
+        self.legs
+            .iter()
+            .zip(accounts)
+            .map(|(leg, account)| {
+                require_keys_eq!(leg.book, account.key(), BettingError::InvalidLegs);
+                Account::<Book>::try_from(account)
+            })
+            .collect()
+    }
+    // loads the games of the leg books from the given accounts, which are passed in the order of the legs
+    pub fn leg_games<'info>(
+        books: &[Account<'info, Book>],
+        accounts: &[AccountInfo<'info>],
+    ) -> Result<Vec<Account<'info, Game>>> {
+        require!(accounts.len() == books.len(), BettingError::InvalidLegs);
+        books
+            .iter()
+            .zip(accounts)
+            .map(|(book, account)| {
+                let game = Account::<Game>::try_from(account)?;
+                require!(game.game_id == book.game_id, BettingError::InvalidLegs);
+                Ok(game)
+            })
+            .collect()
+    }
+    // parlays have no bet delay, so every leg must be before kickoff, not suspended and not concluded
+    pub fn check_legs_open(books: &[Account<Book>], games: &[Account<Game>], now: i64) -> Result<()> {
+        for (book, game) in books.iter().zip(games) {
+            require!(
+                book.concluded_at.is_none() && now < game.kickoff,
+                BettingError::NotInWindow
+            );
+            require!(!book.is_suspended(now), BettingError::MarketSuspended);
+        }
+        Ok(())
+    }
+    // the payout if every leg wins, rounded down leg by leg, fails if it does not fit in a u64
+    pub fn max_payout(&self) -> Result<u64> {
+        self.payout_by_multipliers(self.legs.iter().map(|l| l.odds as u64))
+    }
+    // the payout given the final outcomes of the leg books in the order of the legs, rounded down leg by leg, it is
+    // never more than the max payout
+    pub fn payout(&self, outcomes: &[BetOutcome]) -> Result<u64> {
+        self.payout_by_multipliers(self.legs.iter().zip(outcomes).map(|(l, o)| l.multiplier(*o)))
+    }
+    fn payout_by_multipliers(&self, mut multipliers: impl Iterator<Item = u64>) -> Result<u64> {
+        let precision = Bet::ODDS_PRECISION as u128;
+        let payout = multipliers.try_fold(self.wager as u128, |payout, m| {
+            payout.checked_mul(m as u128).map(|p| p / precision)
+        });
+        payout
+            .and_then(|p| u64::try_from(p).ok())
+            .ok_or_else(|| error!(BettingError::MathOverflow))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ParlayLeg {
+    pub book: Pubkey,
+    pub bet_direction: BetDirection,
+    // in thousandths like the odds of a bet
+    pub odds: u32,
+}
+impl ParlayLeg {
+    pub const INIT_SPACE: usize = 32 + 1 + 4;
+    // the payout multiplier of the leg in thousandths, half outcomes settle half of the stake and refund the rest
+    pub fn multiplier(&self, outcome: BetOutcome) -> u64 {
+        let odds = self.odds as u64;
+        let precision = Bet::ODDS_PRECISION;
+        match (self.bet_direction, outcome) {
+            (BetDirection::For, BetOutcome::For) | (BetDirection::Against, BetOutcome::Against) => odds,
+            (BetDirection::For, BetOutcome::HalfFor) | (BetDirection::Against, BetOutcome::HalfAgainst) => {
+                (odds + precision) / 2
+            }
+            (_, BetOutcome::Cancel) => precision,
+            (BetDirection::For, BetOutcome::HalfAgainst) | (BetDirection::Against, BetOutcome::HalfFor) => {
+                precision / 2
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::AccountSerialize;
+    use solana_sdk::pubkey::Pubkey;
+
+    use crate::{
+        constants::{BETTOR_PAYOUT_RATE, MAX_PARLAY_LEGS},
+        state::{BetDirection, BetOutcome},
+    };
+
+    use super::{Parlay, ParlayLeg};
+
+    fn parlay() -> Parlay {
+        Parlay {
+            parlay_id: 1,
+            backer: Pubkey::new_unique(),
+            layer: Some(Pubkey::new_unique()),
+            wager: 1000000,
+            liability: 2000000,
+            bettor_payout_rate: BETTOR_PAYOUT_RATE,
+            legs: vec![
+                ParlayLeg {
+                    book: Pubkey::new_unique(),
+                    bet_direction: BetDirection::For,
+                    odds: 1500,
+                },
+                ParlayLeg {
+                    book: Pubkey::new_unique(),
+                    bet_direction: BetDirection::Against,
+                    odds: 2000,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_state_parlay_space() {
+        let mut parlay = parlay();
+        let mut data: Vec<u8> = Vec::new();
+        parlay.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), Parlay::space(2));
+
+        parlay.legs.clear();
+        let mut data: Vec<u8> = Vec::new();
+        parlay.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), Parlay::INIT_SPACE);
+    }
+    #[test]
+    fn test_state_parlay_leg_multiplier() {
+        let leg = ParlayLeg {
+            book: Pubkey::new_unique(),
+            bet_direction: BetDirection::For,
+            odds: 1501,
+        };
+        assert_eq!(leg.multiplier(BetOutcome::For), 1501);
+        assert_eq!(leg.multiplier(BetOutcome::HalfFor), 1250);
+        assert_eq!(leg.multiplier(BetOutcome::Cancel), 1000);
+        assert_eq!(leg.multiplier(BetOutcome::HalfAgainst), 500);
+        assert_eq!(leg.multiplier(BetOutcome::Against), 0);
+
+        let leg = ParlayLeg {
+            bet_direction: BetDirection::Against,
+            ..leg
+        };
+        assert_eq!(leg.multiplier(BetOutcome::Against), 1501);
+        assert_eq!(leg.multiplier(BetOutcome::HalfAgainst), 1250);
+        assert_eq!(leg.multiplier(BetOutcome::Cancel), 1000);
+        assert_eq!(leg.multiplier(BetOutcome::HalfFor), 500);
+        assert_eq!(leg.multiplier(BetOutcome::For), 0);
+    }
+    #[test]
+    fn test_state_parlay_payout() {
+        let parlay = parlay();
+        assert_eq!(parlay.max_payout().unwrap(), 3000000);
+        assert_eq!(parlay.payout(&[BetOutcome::For, BetOutcome::Against]).unwrap(), 3000000);
+        // a void leg drops out of the parlay
+        assert_eq!(
+            parlay.payout(&[BetOutcome::Cancel, BetOutcome::Against]).unwrap(),
+            2000000
+        );
+        assert_eq!(
+            parlay.payout(&[BetOutcome::Cancel, BetOutcome::Cancel]).unwrap(),
+            1000000
+        );
+        assert_eq!(
+            parlay.payout(&[BetOutcome::HalfFor, BetOutcome::Against]).unwrap(),
+            2500000
+        );
+        assert_eq!(parlay.payout(&[BetOutcome::For, BetOutcome::HalfFor]).unwrap(), 750000);
+        assert_eq!(parlay.payout(&[BetOutcome::Against, BetOutcome::Against]).unwrap(), 0);
+        // the settled payout never exceeds the locked pot
+        for o1 in [BetOutcome::For, BetOutcome::HalfFor, BetOutcome::Cancel] {
+            for o2 in [BetOutcome::Against, BetOutcome::HalfAgainst, BetOutcome::Cancel] {
+                assert!(parlay.payout(&[o1, o2]).unwrap() <= parlay.wager + parlay.liability);
+            }
+        }
+    }
+    #[test]
+    fn test_state_parlay_payout_overflow() {
+        let mut parlay = parlay();
+        parlay.legs = (0..MAX_PARLAY_LEGS)
+            .map(|_| ParlayLeg {
+                book: Pubkey::new_unique(),
+                bet_direction: BetDirection::For,
+                odds: 100000,
+            })
+            .collect();
+        // 1 USDC at 100x on every leg pays out more than a u64
+        assert!(parlay.max_payout().is_err());
+        parlay.legs[0].odds = u32::MAX;
+        assert!(parlay.max_payout().is_err());
+        parlay.legs.truncate(3);
+        assert_eq!(parlay.max_payout().unwrap(), 4294967295 * 1000 * 100 * 100);
+    }
+}
@@ -0,0 +1,60 @@
+use std::cell::RefMut;
+
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::error::BettingError;
+
+// the stake of a bettor in a book, it lives in its own pda so opting in does not grow the book
+#[account(zero_copy)]
+#[derive(Default)]
+pub struct Position {
+    pub book: Pubkey,
+    pub bettor: Pubkey,
+    pub active_bets_count: u32,
+    pub bets_count: u32,
+    pub payout_for: u64,
+    pub payout_against: u64,
+    pub wager: u64,
+    pub dealt_wager: u64,
+}
+impl Position {
+    pub const INIT_SPACE: usize = 8 + 32 + 32 + 4 + 4 + 8 + 8 + 8 + 8;
+
+    // the positions of the bettors of the matched bets are passed as remaining accounts after the pages
+    pub fn loaders<'info>(accounts: &[AccountInfo<'info>]) -> Result<Vec<AccountLoader<'info, Position>>> {
+        accounts
+            .iter()
+            .filter(|a| matches!(a.try_borrow_data(), Ok(data) if data.starts_with(&Position::discriminator())))
+            .map(AccountLoader::<Position>::try_from)
+            .collect()
+    }
+    // a position can only be loaded once, so every bettor is passed at most once
+    pub fn load_positions<'a>(
+        book: &Pubkey,
+        loaders: &'a [AccountLoader<'_, Position>],
+    ) -> Result<Vec<RefMut<'a, Position>>> {
+        loaders
+            .iter()
+            .map(|loader| {
+                let position = loader.load_mut()?;
+                require_keys_eq!(position.book, *book, BettingError::InvalidPositions);
+                Ok(position)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::Discriminator;
+
+    use super::Position;
+
+    #[test]
+    fn test_state_position_space() {
+        assert_eq!(
+            Position::discriminator().len() + std::mem::size_of::<Position>(),
+            Position::INIT_SPACE
+        );
+    }
+}